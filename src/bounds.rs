@@ -0,0 +1,324 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright © 2025 Jaxydog
+//
+// This file is part of Desktop Cube Baby.
+//
+// Desktop Cube Baby is free software: you can redistribute it and/or modify it under the terms of the GNU General
+// Public License as published by the Free Software Foundation, either version 3 of the License, or (at your option)
+// any later version.
+//
+// Desktop Cube Baby is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the
+// implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more
+// details.
+//
+// You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
+// see <https://www.gnu.org/licenses/>.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::coords::ScreenPos;
+use crate::events::WallSide;
+
+/// How the cube baby behaves upon passing one of the display's edges.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BoundaryBehavior {
+    /// Bounce off of the edge.
+    #[default]
+    Bounce,
+    /// Reappear at the opposite edge, regardless of that edge's own behavior.
+    Wrap,
+    /// Stop at the edge, losing any speed towards it.
+    Clamp,
+    /// Cling to the edge until pushed away.
+    Sticky,
+}
+
+/// Returns the edges that the window has passed, as its horizontal and vertical edges respectively.
+///
+/// The bounds are given as the minimum and maximum positions of the window's top-left corner.
+#[must_use]
+pub fn passed_edges(position: ScreenPos, minimum: ScreenPos, maximum: ScreenPos) -> [Option<WallSide>; 2] {
+    let horizontal = if position.x < minimum.x {
+        Some(WallSide::Left)
+    } else if position.x > maximum.x {
+        Some(WallSide::Right)
+    } else {
+        None
+    };
+    let vertical = if position.y < minimum.y {
+        Some(WallSide::Top)
+    } else if position.y > maximum.y {
+        Some(WallSide::Bottom)
+    } else {
+        None
+    };
+
+    [horizontal, vertical]
+}
+
+/// Returns the edges that the window is touching, within the given distance, as its horizontal and vertical edges
+/// respectively.
+///
+/// The bounds are given as the minimum and maximum positions of the window's top-left corner.
+#[must_use]
+pub fn touched_edges(
+    position: ScreenPos,
+    minimum: ScreenPos,
+    maximum: ScreenPos,
+    distance: f32,
+) -> [Option<WallSide>; 2] {
+    let horizontal = if position.x - minimum.x <= distance {
+        Some(WallSide::Left)
+    } else if maximum.x - position.x <= distance {
+        Some(WallSide::Right)
+    } else {
+        None
+    };
+    let vertical = if position.y - minimum.y <= distance {
+        Some(WallSide::Top)
+    } else if maximum.y - position.y <= distance {
+        Some(WallSide::Bottom)
+    } else {
+        None
+    };
+
+    [horizontal, vertical]
+}
+
+/// Returns `true` if the given push would press the window further into an edge that it is within the dead zone of.
+///
+/// The bounds are given as the minimum and maximum positions of the window's top-left corner.
+#[must_use]
+pub fn presses_into_edge(
+    impulse: Vec2,
+    position: ScreenPos,
+    minimum: ScreenPos,
+    maximum: ScreenPos,
+    deadzone: f32,
+) -> bool {
+    if deadzone <= 0.0 {
+        return false;
+    }
+
+    (impulse.x < 0.0 && position.x - minimum.x <= deadzone)
+        || (impulse.x > 0.0 && maximum.x - position.x <= deadzone)
+        || (impulse.y < 0.0 && position.y - minimum.y <= deadzone)
+        || (impulse.y > 0.0 && maximum.y - position.y <= deadzone)
+}
+
+/// Moves the window back within its bounds after passing the given edge, according to that edge's behavior.
+///
+/// The bounds are given as the minimum and maximum positions of the window's top-left corner. The impact speed is
+/// returned if the window hit the edge while moving towards it. Bouncing keeps the given fraction of the window's speed
+/// along the edge's axis.
+pub fn resolve_edge(
+    side: WallSide,
+    behavior: BoundaryBehavior,
+    position: &mut ScreenPos,
+    velocity: &mut Vec2,
+    minimum: ScreenPos,
+    maximum: ScreenPos,
+    restitution: f32,
+) -> Option<f32> {
+    let axis = usize::from(side.is_vertical());
+    let (edge, opposite_edge, direction) = match side {
+        WallSide::Left | WallSide::Top => (minimum[axis], maximum[axis], -1.0),
+        WallSide::Right | WallSide::Bottom => (maximum[axis], minimum[axis], 1.0),
+    };
+
+    // Positive if moving into the edge.
+    let speed = velocity[axis] * direction;
+
+    match behavior {
+        BoundaryBehavior::Bounce => {
+            position[axis] = edge;
+            velocity[axis] = -direction * velocity[axis].abs() * restitution.clamp(0.0, 1.0);
+        }
+        BoundaryBehavior::Wrap => {
+            // Carry the overshoot across, so that wrapping doesn't lose any distance.
+            position[axis] = (opposite_edge + (position[axis] - edge)).clamp(minimum[axis], maximum[axis]);
+
+            return None;
+        }
+        BoundaryBehavior::Clamp => {
+            position[axis] = edge;
+            velocity[axis] = 0.0;
+        }
+        BoundaryBehavior::Sticky => {
+            position[axis] = edge;
+            *velocity = Vec2::ZERO;
+        }
+    }
+
+    (speed > 0.0).then_some(speed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINIMUM: ScreenPos = ScreenPos::new(0.0, 0.0);
+    const MAXIMUM: ScreenPos = ScreenPos::new(1000.0, 500.0);
+    const DEADZONE: f32 = 16.0;
+
+    #[test]
+    fn pushes_into_a_nearby_edge_are_ignored() {
+        let position = ScreenPos::new(8.0, 250.0);
+
+        assert!(presses_into_edge(Vec2::new(-1.0, 0.0), position, MINIMUM, MAXIMUM, DEADZONE));
+        assert!(presses_into_edge(Vec2::new(-1.0, 1.0), position, MINIMUM, MAXIMUM, DEADZONE));
+        // The edge of the dead zone is still within it.
+        assert!(presses_into_edge(Vec2::NEG_X, ScreenPos::new(16.0, 250.0), MINIMUM, MAXIMUM, DEADZONE));
+    }
+
+    #[test]
+    fn pushes_away_from_a_nearby_edge_are_allowed() {
+        let position = ScreenPos::new(8.0, 250.0);
+
+        assert!(!presses_into_edge(Vec2::new(1.0, 0.0), position, MINIMUM, MAXIMUM, DEADZONE));
+        assert!(!presses_into_edge(Vec2::new(0.0, -1.0), position, MINIMUM, MAXIMUM, DEADZONE));
+        assert!(!presses_into_edge(Vec2::new(0.0, 1.0), position, MINIMUM, MAXIMUM, DEADZONE));
+    }
+
+    #[test]
+    fn pushes_far_from_every_edge_are_allowed() {
+        let position = ScreenPos::new(500.0, 250.0);
+
+        for impulse in [Vec2::X, Vec2::NEG_X, Vec2::Y, Vec2::NEG_Y, Vec2::ONE, Vec2::NEG_ONE] {
+            assert!(!presses_into_edge(impulse, position, MINIMUM, MAXIMUM, DEADZONE));
+        }
+    }
+
+    #[test]
+    fn corners_ignore_pushes_into_either_edge() {
+        let position = ScreenPos::new(995.0, 495.0);
+
+        assert!(presses_into_edge(Vec2::new(1.0, 0.0), position, MINIMUM, MAXIMUM, DEADZONE));
+        assert!(presses_into_edge(Vec2::new(0.0, 1.0), position, MINIMUM, MAXIMUM, DEADZONE));
+        assert!(presses_into_edge(Vec2::new(1.0, 1.0), position, MINIMUM, MAXIMUM, DEADZONE));
+        // Pressing into only one of the two edges is still pressing into an edge.
+        assert!(presses_into_edge(Vec2::new(-1.0, 1.0), position, MINIMUM, MAXIMUM, DEADZONE));
+        assert!(presses_into_edge(Vec2::new(1.0, -1.0), position, MINIMUM, MAXIMUM, DEADZONE));
+        assert!(!presses_into_edge(Vec2::new(-1.0, -1.0), position, MINIMUM, MAXIMUM, DEADZONE));
+    }
+
+    #[test]
+    fn a_zero_deadzone_allows_everything() {
+        for position in [MINIMUM, MAXIMUM, ScreenPos::new(0.0, 500.0)] {
+            for impulse in [Vec2::X, Vec2::NEG_X, Vec2::Y, Vec2::NEG_Y, Vec2::ONE, Vec2::NEG_ONE] {
+                assert!(!presses_into_edge(impulse, position, MINIMUM, MAXIMUM, 0.0));
+            }
+        }
+    }
+
+    #[test]
+    fn full_restitution_reflects_speed() {
+        let mut position = ScreenPos::new(1010.0, 250.0);
+        let mut velocity = Vec2::new(300.0, -40.0);
+
+        let speed = resolve_edge(
+            WallSide::Right,
+            BoundaryBehavior::Bounce,
+            &mut position,
+            &mut velocity,
+            MINIMUM,
+            MAXIMUM,
+            1.0,
+        );
+
+        assert_eq!(speed, Some(300.0));
+        assert_eq!(position, ScreenPos::new(1000.0, 250.0));
+        assert_eq!(velocity, Vec2::new(-300.0, -40.0));
+    }
+
+    #[test]
+    fn partial_restitution_loses_speed() {
+        let mut position = ScreenPos::new(500.0, -5.0);
+        let mut velocity = Vec2::new(20.0, -200.0);
+
+        let speed =
+            resolve_edge(WallSide::Top, BoundaryBehavior::Bounce, &mut position, &mut velocity, MINIMUM, MAXIMUM, 0.25);
+
+        assert_eq!(speed, Some(200.0));
+        assert_eq!(position, ScreenPos::new(500.0, 0.0));
+        assert_eq!(velocity, Vec2::new(20.0, 50.0));
+    }
+
+    #[test]
+    fn restitution_is_clamped() {
+        let mut position = ScreenPos::new(-5.0, 250.0);
+        let mut velocity = Vec2::new(-100.0, 0.0);
+
+        resolve_edge(WallSide::Left, BoundaryBehavior::Bounce, &mut position, &mut velocity, MINIMUM, MAXIMUM, 4.0);
+
+        assert_eq!(velocity, Vec2::new(100.0, 0.0));
+
+        let mut velocity = Vec2::new(-100.0, 0.0);
+
+        resolve_edge(WallSide::Left, BoundaryBehavior::Bounce, &mut position, &mut velocity, MINIMUM, MAXIMUM, -1.0);
+
+        assert_eq!(velocity.x, 0.0);
+    }
+
+    #[test]
+    fn moving_away_from_an_edge_is_not_an_impact() {
+        let mut position = ScreenPos::new(500.0, 510.0);
+        let mut velocity = Vec2::new(0.0, -100.0);
+
+        let speed = resolve_edge(
+            WallSide::Bottom,
+            BoundaryBehavior::Bounce,
+            &mut position,
+            &mut velocity,
+            MINIMUM,
+            MAXIMUM,
+            1.0,
+        );
+
+        assert_eq!(speed, None);
+        assert_eq!(position, ScreenPos::new(500.0, 500.0));
+        // The velocity still ends up pointing away from the edge.
+        assert_eq!(velocity, Vec2::new(0.0, -100.0));
+    }
+
+    #[test]
+    fn other_behaviors_resolve_the_edge() {
+        let mut position = ScreenPos::new(1010.0, 250.0);
+        let mut velocity = Vec2::new(300.0, -40.0);
+
+        let speed =
+            resolve_edge(WallSide::Right, BoundaryBehavior::Clamp, &mut position, &mut velocity, MINIMUM, MAXIMUM, 1.0);
+
+        assert_eq!(speed, Some(300.0));
+        assert_eq!((position, velocity), (ScreenPos::new(1000.0, 250.0), Vec2::new(0.0, -40.0)));
+
+        let mut position = ScreenPos::new(1010.0, 250.0);
+        let mut velocity = Vec2::new(300.0, -40.0);
+
+        let speed = resolve_edge(
+            WallSide::Right,
+            BoundaryBehavior::Sticky,
+            &mut position,
+            &mut velocity,
+            MINIMUM,
+            MAXIMUM,
+            1.0,
+        );
+
+        assert_eq!(speed, Some(300.0));
+        assert_eq!((position, velocity), (ScreenPos::new(1000.0, 250.0), Vec2::ZERO));
+
+        let mut position = ScreenPos::new(1010.0, 250.0);
+        let mut velocity = Vec2::new(300.0, -40.0);
+
+        let speed =
+            resolve_edge(WallSide::Right, BoundaryBehavior::Wrap, &mut position, &mut velocity, MINIMUM, MAXIMUM, 1.0);
+
+        // Wrapping carries the overshoot across to the opposite edge.
+        assert_eq!(speed, None);
+        assert_eq!((position, velocity), (ScreenPos::new(10.0, 250.0), Vec2::new(300.0, -40.0)));
+    }
+}
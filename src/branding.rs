@@ -0,0 +1,140 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright © 2025 Jaxydog
+//
+// This file is part of Desktop Cube Baby.
+//
+// Desktop Cube Baby is free software: you can redistribute it and/or modify it under the terms of the GNU General
+// Public License as published by the Free Software Foundation, either version 3 of the License, or (at your option)
+// any later version.
+//
+// Desktop Cube Baby is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the
+// implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more
+// details.
+//
+// You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
+// see <https://www.gnu.org/licenses/>.
+
+use bevy::prelude::*;
+
+/// The application identifier, used for the Wayland app id and the X11 `WM_CLASS`.
+pub const APPLICATION_ID: &str = env!("CARGO_PKG_NAME");
+/// The size of one side of the window icon, in pixels.
+pub const ICON_SIZE: u32 = 64;
+/// The size of one side of the taskbar icon, in pixels.
+pub const TASKBAR_ICON_SIZE: u32 = 256;
+
+/// Returns the window's title, using the binary's name if none is configured.
+#[must_use]
+pub fn window_title(title: Option<&str>) -> String {
+    title.unwrap_or(env!("CARGO_BIN_NAME")).to_string()
+}
+
+/// Scales a single frame of an RGBA8 image into a square icon of the given size, using nearest-neighbor sampling.
+///
+/// The frame is given as its top-left corner and size within the image, and is centered within the icon if it is not
+/// square. Returns `None` if the frame does not fit within the image or the image data is incomplete.
+#[must_use]
+pub fn frame_icon(
+    data: &[u8],
+    image_size: UVec2,
+    frame_origin: UVec2,
+    frame_size: UVec2,
+    size: u32,
+) -> Option<Vec<u8>> {
+    let frame_end = frame_origin.saturating_add(frame_size);
+
+    if size == 0
+        || frame_size.min_element() == 0
+        || frame_end.cmpgt(image_size).any()
+        || data.len() < (image_size.x as usize * image_size.y as usize * 4)
+    {
+        return None;
+    }
+
+    // Preserve the frame's aspect ratio, leaving the remaining space transparent.
+    let scale = size as f32 / frame_size.max_element() as f32;
+    let scaled_size = (frame_size.as_vec2() * scale).round().as_uvec2().max(UVec2::ONE);
+    let margin = (UVec2::splat(size) - scaled_size.min(UVec2::splat(size))) / 2;
+
+    let mut icon = vec![0; size as usize * size as usize * 4];
+
+    for y in 0 .. scaled_size.y.min(size) {
+        for x in 0 .. scaled_size.x.min(size) {
+            let source = frame_origin + (UVec2::new(x, y).as_vec2() / scale).as_uvec2().min(frame_size - 1);
+            let source_index = ((source.y * image_size.x) + source.x) as usize * 4;
+            let target_index = (((y + margin.y) * size) + (x + margin.x)) as usize * 4;
+
+            icon[target_index .. target_index + 4].copy_from_slice(&data[source_index .. source_index + 4]);
+        }
+    }
+
+    Some(icon)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns an RGBA8 image of the given size, where each pixel's red and green channels are its coordinates.
+    fn gradient(size: UVec2) -> Vec<u8> {
+        (0 .. size.y).flat_map(|y| (0 .. size.x).flat_map(move |x| [x as u8, y as u8, 0, 255])).collect()
+    }
+
+    /// Returns the pixel at the given position within a square icon of the given size.
+    fn pixel(icon: &[u8], size: u32, x: u32, y: u32) -> [u8; 4] {
+        let index = ((y * size) + x) as usize * 4;
+
+        [icon[index], icon[index + 1], icon[index + 2], icon[index + 3]]
+    }
+
+    #[test]
+    fn icons_have_the_requested_size() {
+        let image_size = UVec2::new(64, 32);
+        let data = self::gradient(image_size);
+
+        for size in [1, 16, 32, ICON_SIZE, TASKBAR_ICON_SIZE] {
+            let icon = frame_icon(&data, image_size, UVec2::ZERO, UVec2::splat(32), size).expect("missing icon");
+
+            assert_eq!(icon.len(), (size * size * 4) as usize);
+        }
+    }
+
+    #[test]
+    fn icons_sample_the_nearest_pixel() {
+        let image_size = UVec2::new(4, 2);
+        let data = self::gradient(image_size);
+        let icon = frame_icon(&data, image_size, UVec2::new(2, 0), UVec2::splat(2), 4).expect("missing icon");
+
+        // Every source pixel of the second frame covers a 2x2 block of the icon.
+        assert_eq!(self::pixel(&icon, 4, 0, 0), [2, 0, 0, 255]);
+        assert_eq!(self::pixel(&icon, 4, 1, 1), [2, 0, 0, 255]);
+        assert_eq!(self::pixel(&icon, 4, 2, 0), [3, 0, 0, 255]);
+        assert_eq!(self::pixel(&icon, 4, 3, 3), [3, 1, 0, 255]);
+    }
+
+    #[test]
+    fn icons_center_non_square_frames() {
+        let image_size = UVec2::new(4, 2);
+        let data = self::gradient(image_size);
+        let icon = frame_icon(&data, image_size, UVec2::ZERO, image_size, 8).expect("missing icon");
+
+        // The frame is twice as wide as it is tall, so it fills the middle half of the icon.
+        assert_eq!(self::pixel(&icon, 8, 0, 0), [0; 4]);
+        assert_eq!(self::pixel(&icon, 8, 7, 1), [0; 4]);
+        assert_eq!(self::pixel(&icon, 8, 0, 2), [0, 0, 0, 255]);
+        assert_eq!(self::pixel(&icon, 8, 7, 5), [3, 1, 0, 255]);
+        assert_eq!(self::pixel(&icon, 8, 7, 6), [0; 4]);
+    }
+
+    #[test]
+    fn invalid_frames_are_rejected() {
+        let image_size = UVec2::new(4, 2);
+        let data = self::gradient(image_size);
+
+        assert_eq!(frame_icon(&data, image_size, UVec2::new(3, 0), UVec2::splat(2), 4), None);
+        assert_eq!(frame_icon(&data, image_size, UVec2::ZERO, UVec2::ZERO, 4), None);
+        assert_eq!(frame_icon(&data, image_size, UVec2::ZERO, UVec2::splat(2), 0), None);
+        assert_eq!(frame_icon(&data[.. 8], image_size, UVec2::ZERO, UVec2::splat(2), 4), None);
+    }
+}
@@ -15,21 +15,347 @@
 // You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
 // see <https://www.gnu.org/licenses/>.
 
+use std::collections::VecDeque;
 use std::fmt::Debug;
 
 use bevy::prelude::*;
 
+use crate::coords::ScreenPos;
+use crate::events::WallSide;
+use crate::{
+    ATLAS_FRAMES, COOLDOWN_TINT_COLOR, CORNER_ESCAPE_DELAY, CORNER_ESCAPE_DURATION, CORNER_TRAP_HITS,
+    CORNER_TRAP_WINDOW, DESPAWN_PUFF_DURATION, DIZZY_DURATION, EDGE_PATROL_EVALUATION_DELAY, EVASION_DURATION,
+    EXIT_HOLD_COLOR, FLICK_SPIN_DURATION, GLANCE_DISTANCE, GLANCE_LEAN_MAX, GLANCE_LEAN_MIN, GLOOM_TINT_COLOR,
+    MAX_SPIN_FRAMES, PATROL_SUSPEND_DELAY, SQUASH_DURATION, THROW_SAMPLES,
+};
+
 /// Marker component for values that belong to the cube baby.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Component)]
 pub struct CubeBaby;
 
-/// Represents a delay in seconds for when the cube baby may be pushed.
-#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd, Component, Deref, DerefMut)]
-pub struct PushDelay(pub f64);
+/// Marker component for the cube baby shown in the primary window, which every direct interaction applies to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Component)]
+pub struct PrimaryBaby;
 
-impl PushDelay {
-    /// A delay of zero seconds.
-    pub const ZERO: Self = Self(0.0);
+/// Marker component for a cube baby that is resting on the floor under gravity.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Component)]
+pub struct Grounded;
+
+/// Marker component for a cube baby that has used its double jump since it last rested on the floor.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Component)]
+pub struct DoubleJumped;
+
+/// The window that an extra cube baby is shown in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Component, Deref, DerefMut)]
+pub struct BabyWindow(pub Entity);
+
+/// Tracks the cube baby's progress through the first-run attention sequence.
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Onboarding {
+    /// The amount of time in seconds since the sequence began.
+    pub elapsed: f32,
+    /// The position that the sequence began at.
+    pub origin: ScreenPos,
+}
+
+/// Marker component for the window and camera that display the first-run hint.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Component)]
+pub struct OnboardingHint;
+
+/// Tracks the cube baby's evasion of a screen capture.
+#[derive(Clone, Debug, PartialEq, Component)]
+pub struct Evading {
+    /// The corner that the cube baby is hiding in.
+    pub target: ScreenPos,
+    /// The amount of time left before the cube baby returns to normal.
+    pub timer: Timer,
+}
+
+impl Evading {
+    /// Creates a new [`Evading`] that hides in the given corner.
+    pub fn new(target: ScreenPos) -> Self {
+        Self { target, timer: Timer::from_seconds(EVASION_DURATION, TimerMode::Once) }
+    }
+}
+
+/// Tracks the cube baby while it is being petted by a still or slowly stroking cursor.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Component)]
+pub struct Petting {
+    /// The amount of time in seconds since the petting began.
+    pub elapsed: f32,
+}
+
+/// Tracks the cube baby while it spins faster after being flicked.
+#[derive(Clone, Debug, PartialEq, Component)]
+pub struct Flicked {
+    /// The amount of time left before the cube baby spins normally again.
+    pub timer: Timer,
+}
+
+impl Default for Flicked {
+    fn default() -> Self {
+        Self { timer: Timer::from_seconds(FLICK_SPIN_DURATION, TimerMode::Once) }
+    }
+}
+
+/// Tracks the cube baby while it recovers from being made dizzy by a shaking cursor.
+#[derive(Clone, Debug, PartialEq, Component)]
+pub struct Dizzy {
+    /// The amount of time left before the cube baby recovers.
+    pub timer: Timer,
+    /// The number of frames that the sprite has yet to spin through, including partial frames.
+    pub spin: f32,
+}
+
+impl Default for Dizzy {
+    fn default() -> Self {
+        Self { timer: Timer::from_seconds(DIZZY_DURATION, TimerMode::Once), spin: 0.0 }
+    }
+}
+
+/// Tracks the cube baby's hits against two edges at once, used to free it when trapped vibrating in a corner.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Component)]
+pub struct CornerTrap {
+    /// The number of corner hits in a row, each within the trap window of the last.
+    pub hits: u32,
+    /// The number of seconds since the last corner hit.
+    pub since_hit: f32,
+    /// The number of seconds left before the cube baby may escape again.
+    pub cooldown: f32,
+    /// The number of seconds left of the extra drag applied after escaping.
+    pub escaping: f32,
+}
+
+impl CornerTrap {
+    /// Counts the trap's timers down by the given number of seconds, recording whether a corner was hit.
+    ///
+    /// Returns `true` if the cube baby has been trapped for long enough that it should escape.
+    pub fn tick(&mut self, delta: f32, cornered: bool) -> bool {
+        self.since_hit += delta;
+        self.cooldown = (self.cooldown - delta).max(0.0);
+        self.escaping = (self.escaping - delta).max(0.0);
+
+        if !cornered {
+            if self.since_hit > CORNER_TRAP_WINDOW {
+                self.hits = 0;
+            }
+
+            return false;
+        }
+
+        self.hits += 1;
+        self.since_hit = 0.0;
+
+        // Escapes are rate-limited, so that a cube baby that was deliberately parked in a corner is left there.
+        if self.hits < CORNER_TRAP_HITS || self.cooldown > 0.0 {
+            return false;
+        }
+
+        self.hits = 0;
+        self.cooldown = CORNER_ESCAPE_DELAY;
+        self.escaping = CORNER_ESCAPE_DURATION;
+
+        true
+    }
+
+    /// Returns `true` if the cube baby has recently escaped a corner.
+    #[inline]
+    #[must_use]
+    pub fn is_escaping(&self) -> bool {
+        self.escaping > 0.0
+    }
+}
+
+/// Tracks the cube baby while it springs back from being squashed against a wall.
+#[derive(Clone, Debug, PartialEq, Component)]
+pub struct Squash {
+    /// The wall that the cube baby was squashed against.
+    pub side: WallSide,
+    /// The fraction of its size that the cube baby was squashed by.
+    pub amount: f32,
+    /// The amount of time left before the cube baby has sprung back.
+    pub timer: Timer,
+}
+
+impl Squash {
+    /// Creates a new [`Squash`] against the given wall.
+    #[must_use]
+    pub fn new(side: WallSide, amount: f32) -> Self {
+        Self { side, amount, timer: Timer::from_seconds(SQUASH_DURATION, TimerMode::Once) }
+    }
+}
+
+/// Tracks the cube baby while it is being held by the cursor.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Component)]
+pub struct Held {
+    /// The cursor's position within the window when it was grabbed, in physical pixels.
+    pub offset: Vec2,
+}
+
+/// Tracks the cube baby's recent positions while it is being held, used to throw it upon release.
+#[derive(Clone, Debug, Default, PartialEq, Component)]
+pub struct DragHistory {
+    /// The recorded positions alongside the time in seconds at which they were recorded, from oldest to newest.
+    samples: VecDeque<(f64, ScreenPos)>,
+}
+
+impl DragHistory {
+    /// Records a position at the given time in seconds, forgetting the oldest sample if the history is full.
+    pub fn push(&mut self, now: f64, position: ScreenPos) {
+        if self.samples.len() == THROW_SAMPLES {
+            self.samples.pop_front();
+        }
+
+        self.samples.push_back((now, position));
+    }
+
+    /// Returns the average velocity over the given number of seconds before the newest sample.
+    #[must_use]
+    pub fn velocity(&self, duration: f64) -> Vec2 {
+        let Some(&(newest_time, newest_position)) = self.samples.back() else { return Vec2::ZERO };
+        let Some(&(oldest_time, oldest_position)) =
+            self.samples.iter().find(|(time, _)| newest_time - *time <= duration)
+        else {
+            return Vec2::ZERO;
+        };
+
+        let elapsed = newest_time - oldest_time;
+
+        if elapsed <= 0.0 { Vec2::ZERO } else { (newest_position - oldest_position) / elapsed as f32 }
+    }
+}
+
+/// Marker component for the border drawn around the window when it has a solid background.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Component)]
+pub struct WindowBorder;
+
+/// The layered tints applied to the cube baby's sprite, so that systems which tint it never overwrite each other.
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Tint {
+    /// How far the cube baby is dimmed while its push delays count down, from zero to one.
+    pub cooldown: f32,
+    /// How far the cube baby is tinted while the quit key is held, from zero to one.
+    pub exit_hold: f32,
+    /// How far the cube baby is tinted by gloomy weather, from zero to one.
+    pub gloom: f32,
+    /// The cube baby's opacity, which is lowered to show click-through mode.
+    pub opacity: f32,
+}
+
+impl Tint {
+    /// Returns the color that these tints combine into, with later layers drawn over earlier ones.
+    ///
+    /// With every layer at rest, this is exactly white.
+    #[must_use]
+    pub fn color(&self) -> Color {
+        let mut color = Color::WHITE;
+
+        let layers =
+            [(GLOOM_TINT_COLOR, self.gloom), (COOLDOWN_TINT_COLOR, self.cooldown), (EXIT_HOLD_COLOR, self.exit_hold)];
+
+        for (tint, amount) in layers {
+            if amount > 0.0 {
+                color = color.mix(&tint, amount.min(1.0));
+            }
+        }
+
+        color.with_alpha(self.opacity)
+    }
+}
+
+impl Default for Tint {
+    fn default() -> Self {
+        Self { cooldown: 0.0, exit_hold: 0.0, gloom: 0.0, opacity: 1.0 }
+    }
+}
+
+/// The layered offsets applied to the cube baby's sprite within its window, so that systems which shift it never
+/// overwrite each other.
+///
+/// Offsets are in logical pixels, with the y axis pointing up like the sprite's transform.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Component)]
+pub struct SpriteOffset {
+    /// How far the cube baby leans away from an approaching cursor.
+    pub glance: Vec2,
+    /// How far the cube baby is pressed against the wall that squashed it.
+    pub squash: Vec2,
+}
+
+impl SpriteOffset {
+    /// Returns how far the cube baby leans away from a cursor that is the given number of logical pixels from its
+    /// window, where the direction points from the cursor to the window's center in screen space.
+    ///
+    /// The lean grows as the cursor approaches, and stops once the cursor touches the window.
+    #[must_use]
+    pub fn glance(direction: Vec2, gap: f32) -> Vec2 {
+        let Some(direction) = direction.try_normalize() else { return Vec2::ZERO };
+
+        if gap <= 0.0 || gap >= GLANCE_DISTANCE {
+            return Vec2::ZERO;
+        }
+
+        let lean = GLANCE_LEAN_MIN.lerp(GLANCE_LEAN_MAX, 1.0 - (gap / GLANCE_DISTANCE));
+
+        // The screen's y axis points down, while the sprite's points up.
+        Vec2::new(direction.x, -direction.y) * lean
+    }
+
+    /// Returns how far a cube baby squashed by the given amount is pressed against the given wall, so that it stays
+    /// flush with the wall while it springs back.
+    #[must_use]
+    pub fn squash(side: WallSide, amount: f32, size: f32) -> Vec2 {
+        let direction = match side {
+            WallSide::Left => Vec2::NEG_X,
+            WallSide::Right => Vec2::X,
+            WallSide::Top => Vec2::Y,
+            WallSide::Bottom => Vec2::NEG_Y,
+        };
+
+        direction * amount * size / 2.0
+    }
+
+    /// Returns the offset that these layers combine into.
+    ///
+    /// A lean never pulls the cube baby off of the wall that it is pressed against, so the glance is dropped along any
+    /// axis that the squash is acting on. With every layer at rest, this is exactly zero.
+    #[must_use]
+    pub fn translation(&self) -> Vec2 {
+        let glance = Vec2::select(self.squash.cmpne(Vec2::ZERO), Vec2::ZERO, self.glance);
+
+        glance + self.squash
+    }
+}
+
+/// Represents the delays in seconds for when the cube baby may next be pushed by each input source.
+///
+/// Each source only waits on its own delay, so pushing with one source never blocks the others.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Component)]
+pub struct PushDelays {
+    /// The delay for the mouse and touch input.
+    pub mouse: f64,
+    /// The delay for the keyboard.
+    pub keyboard: f64,
+    /// The delay for gamepads.
+    pub gamepad: f64,
+}
+
+impl PushDelays {
+    /// Delays of zero seconds.
+    pub const ZERO: Self = Self { mouse: 0.0, keyboard: 0.0, gamepad: 0.0 };
+
+    /// Returns the longest delay of any input source.
+    #[inline]
+    #[must_use]
+    pub fn longest(&self) -> f64 {
+        self.mouse.max(self.keyboard).max(self.gamepad)
+    }
+
+    /// Counts every delay down by the given number of seconds.
+    pub fn tick(&mut self, delta: f64) {
+        for delay in [&mut self.mouse, &mut self.keyboard, &mut self.gamepad] {
+            *delay = (*delay - delta).max(0.0);
+        }
+    }
 }
 
 /// Represents the distance traveled since the cube baby last had its sprite updated.
@@ -39,25 +365,67 @@ pub struct Distance(pub f32);
 impl Distance {
     /// A distance of zero pixels.
     pub const ZERO: Self = Self(0.0);
+
+    /// Spins the given atlas index by as many whole frames as this distance covers, keeping the remainder for later.
+    ///
+    /// The spin rolls the way that the given velocity is travelling, following whichever axis it is mostly moving
+    /// along.
+    #[must_use]
+    pub fn spin(&mut self, index: usize, spin_distance: f32, velocity: Vec2) -> usize {
+        if self.0 < spin_distance {
+            return index;
+        }
+
+        // Skipping too many frames at once would make the spin look like it's jumping around at random.
+        let frames = (self.0 / spin_distance).floor().min(MAX_SPIN_FRAMES) as isize;
+        let direction = if velocity.x.abs() >= velocity.y.abs() { velocity.x } else { velocity.y };
+        let frames = if direction < 0.0 { -frames } else { frames };
+
+        self.0 %= spin_distance;
+
+        (index as isize + frames).rem_euclid(ATLAS_FRAMES as isize) as usize
+    }
 }
 
-/// Represents a persistent position.
+/// The number of seconds until the cube baby next wanders, if a delay has been chosen yet.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Component, Deref, DerefMut)]
+pub struct WanderDelay(pub Option<f32>);
+
+/// Represents a persistent position of the window's top-left corner.
 #[repr(transparent)]
 #[derive(Clone, Copy, Debug, Default, PartialEq, Component, Deref, DerefMut)]
-pub struct Position(pub Vec2);
+pub struct Position(pub ScreenPos);
 
 impl Position {
     /// A position of `(0, 0)`.
-    pub const ZERO: Self = Self(Vec2::ZERO);
+    pub const ZERO: Self = Self(ScreenPos::ZERO);
 
     /// Creates a new [`Position`].
     #[inline]
     pub const fn new(x: f32, y: f32) -> Self {
-        Self(Vec2::new(x, y))
+        Self(ScreenPos::new(x, y))
+    }
+}
+
+/// Represents the window's positions before and after the latest fixed timestep, used to smooth its presentation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Component)]
+pub struct FixedPositions {
+    /// The position before the latest fixed timestep.
+    pub previous: ScreenPos,
+    /// The position after the latest fixed timestep.
+    pub current: ScreenPos,
+}
+
+impl FixedPositions {
+    /// Creates a new [`FixedPositions`] resting at the given position.
+    #[inline]
+    #[must_use]
+    pub const fn at(position: Position) -> Self {
+        Self { previous: position.0, current: position.0 }
     }
 }
 
-/// Represents a persistent velocity.
+/// Represents a persistent velocity, in physical pixels per second.
 #[repr(transparent)]
 #[derive(Clone, Copy, Debug, Default, PartialEq, Component, Deref, DerefMut)]
 pub struct Velocity(pub Vec2);
@@ -72,3 +440,187 @@ impl Velocity {
         Self(Vec2::new(x, y))
     }
 }
+
+/// Tracks the cube baby's progress along the configured patrol route.
+#[derive(Clone, Debug, PartialEq, Eq, Component)]
+pub struct Patrolling {
+    /// The index of the waypoint currently being approached.
+    pub index: usize,
+    /// The amount of time to wait at each waypoint.
+    pub dwell: Timer,
+    /// The amount of time that patrolling is suspended for after being pushed.
+    pub suspension: Timer,
+}
+
+impl Patrolling {
+    /// Creates a new [`Patrolling`] that waits for the given number of seconds at each waypoint.
+    pub fn new(dwell: f32) -> Self {
+        let mut suspension = Timer::from_seconds(PATROL_SUSPEND_DELAY, TimerMode::Once);
+
+        // Patrolling should not start out suspended.
+        suspension.tick(suspension.duration());
+
+        Self { index: 0, dwell: Timer::from_seconds(dwell, TimerMode::Once), suspension }
+    }
+
+    /// Returns `true` if patrolling is currently suspended.
+    #[inline]
+    #[must_use]
+    pub fn is_suspended(&self) -> bool {
+        !self.suspension.finished()
+    }
+
+    /// Suspends patrolling, restarting the current waypoint's dwell time.
+    pub fn suspend(&mut self) {
+        self.suspension.reset();
+        self.dwell.reset();
+    }
+}
+
+/// Tracks an extra cube baby while it puffs away, before its window is closed.
+#[derive(Clone, Debug, PartialEq, Component)]
+pub struct Despawning {
+    /// The window that the cube baby is shown in.
+    pub window: Entity,
+    /// The camera that renders the cube baby into its window.
+    pub camera: Entity,
+    /// The amount of time left before the cube baby has puffed away.
+    pub timer: Timer,
+}
+
+impl Despawning {
+    /// Creates a new [`Despawning`] for a cube baby shown in the given window by the given camera.
+    #[must_use]
+    pub fn new(window: Entity, camera: Entity) -> Self {
+        Self { window, camera, timer: Timer::from_seconds(DESPAWN_PUFF_DURATION, TimerMode::Once) }
+    }
+}
+
+/// Tracks the cube baby while it has flopped down to sleep near the edge of the display.
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Flopped {
+    /// The position that the cube baby is flopping down at.
+    pub target: ScreenPos,
+}
+
+/// Tracks the cube baby while it patrols along the display edge farthest from the cursor.
+#[derive(Clone, Debug, PartialEq, Component)]
+pub struct EdgePatrolling {
+    /// The edge being patrolled.
+    pub edge: WallSide,
+    /// Which way along the edge the cube baby is heading, either `1.0` or `-1.0`.
+    pub heading: f32,
+    /// The amount of time left before the farthest edge is chosen again.
+    pub evaluation: Timer,
+}
+
+impl EdgePatrolling {
+    /// Creates a new [`EdgePatrolling`] along the given edge.
+    #[must_use]
+    pub fn new(edge: WallSide) -> Self {
+        Self { edge, heading: 1.0, evaluation: Timer::from_seconds(EDGE_PATROL_EVALUATION_DELAY, TimerMode::Repeating) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPIN_DISTANCE: f32 = 20.0;
+
+    #[test]
+    fn resting_sprite_offsets_are_zero() {
+        assert_eq!(SpriteOffset::default().translation(), Vec2::ZERO);
+        assert_eq!(SpriteOffset::glance(Vec2::X, GLANCE_DISTANCE), Vec2::ZERO);
+        assert_eq!(SpriteOffset::glance(Vec2::X, 0.0), Vec2::ZERO);
+        assert_eq!(SpriteOffset::glance(Vec2::ZERO, GLANCE_DISTANCE / 2.0), Vec2::ZERO);
+    }
+
+    #[test]
+    fn glances_lean_away_from_the_cursor() {
+        // A cursor above the window in screen space is below the sprite's origin, so the lean points down.
+        let far = SpriteOffset::glance(Vec2::new(0.0, 10.0), GLANCE_DISTANCE - 1.0);
+        let near = SpriteOffset::glance(Vec2::new(0.0, 10.0), 1.0);
+
+        assert!(far.x.abs() < f32::EPSILON && far.y < 0.0);
+        assert!(near.length() > far.length());
+        assert!(near.length() <= GLANCE_LEAN_MAX && far.length() >= GLANCE_LEAN_MIN);
+    }
+
+    #[test]
+    fn sprite_offsets_compose_without_fighting() {
+        let glance = Vec2::new(1.5, -1.0);
+        let squash = SpriteOffset::squash(WallSide::Left, 0.2, 64.0);
+        let offset = SpriteOffset { glance, squash };
+
+        // The squash keeps the cube baby against its wall, while the lean still applies along the other axis.
+        assert_eq!(squash, Vec2::new(-6.4, 0.0));
+        assert_eq!(offset.translation(), Vec2::new(-6.4, -1.0));
+
+        // Each layer is restored independently of the other.
+        assert_eq!(SpriteOffset { squash: Vec2::ZERO, ..offset }.translation(), glance);
+        assert_eq!(SpriteOffset { glance: Vec2::ZERO, ..offset }.translation(), squash);
+    }
+
+    #[test]
+    fn spin_follows_horizontal_travel() {
+        assert_eq!(Distance(SPIN_DISTANCE).spin(3, SPIN_DISTANCE, Vec2::new(100.0, 0.0)), 4);
+        assert_eq!(Distance(SPIN_DISTANCE).spin(3, SPIN_DISTANCE, Vec2::new(-100.0, 0.0)), 2);
+    }
+
+    #[test]
+    fn spin_follows_the_larger_axis() {
+        assert_eq!(Distance(SPIN_DISTANCE).spin(3, SPIN_DISTANCE, Vec2::new(10.0, 100.0)), 4);
+        assert_eq!(Distance(SPIN_DISTANCE).spin(3, SPIN_DISTANCE, Vec2::new(10.0, -100.0)), 2);
+        assert_eq!(Distance(SPIN_DISTANCE).spin(3, SPIN_DISTANCE, Vec2::new(-100.0, 10.0)), 2);
+    }
+
+    #[test]
+    fn spin_wraps_around_the_atlas() {
+        let last = ATLAS_FRAMES as usize - 1;
+
+        assert_eq!(Distance(SPIN_DISTANCE).spin(0, SPIN_DISTANCE, Vec2::NEG_X), last);
+        assert_eq!(Distance(SPIN_DISTANCE).spin(last, SPIN_DISTANCE, Vec2::X), 0);
+        assert_eq!(Distance(SPIN_DISTANCE * 2.0).spin(0, SPIN_DISTANCE, Vec2::NEG_X), last - 1);
+    }
+
+    #[test]
+    fn spin_keeps_the_remainder() {
+        let mut distance = Distance(SPIN_DISTANCE * 1.5);
+
+        assert_eq!(distance.spin(0, SPIN_DISTANCE, Vec2::X), 1);
+        assert_eq!(distance.0, SPIN_DISTANCE * 0.5);
+
+        let mut distance = Distance(SPIN_DISTANCE * 0.5);
+
+        assert_eq!(distance.spin(5, SPIN_DISTANCE, Vec2::X), 5);
+        assert_eq!(distance.0, SPIN_DISTANCE * 0.5);
+    }
+
+    #[test]
+    fn spin_skips_a_limited_number_of_frames() {
+        let mut distance = Distance(SPIN_DISTANCE * 100.0);
+
+        assert_eq!(distance.spin(0, SPIN_DISTANCE, Vec2::X), MAX_SPIN_FRAMES as usize);
+        assert!(distance.0 < SPIN_DISTANCE);
+    }
+
+    #[test]
+    fn huge_distances_still_advance_the_atlas() {
+        let mut distance = Distance(1.0e12);
+        let mut index = distance.spin(0, SPIN_DISTANCE, Vec2::X);
+
+        // The distance is drained down to less than a single frame, however large it grew.
+        assert!(distance.0 < SPIN_DISTANCE);
+
+        let start = index;
+
+        // So small increments still register, and add up to exactly one more frame.
+        for _ in 0 .. 40 {
+            distance.0 += 0.5;
+            index = distance.spin(index, SPIN_DISTANCE, Vec2::X);
+        }
+
+        assert_eq!(index, (start + 1) % ATLAS_FRAMES as usize);
+    }
+}
@@ -0,0 +1,229 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright © 2025 Jaxydog
+//
+// This file is part of Desktop Cube Baby.
+//
+// Desktop Cube Baby is free software: you can redistribute it and/or modify it under the terms of the GNU General
+// Public License as published by the Free Software Foundation, either version 3 of the License, or (at your option)
+// any later version.
+//
+// Desktop Cube Baby is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the
+// implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more
+// details.
+//
+// You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
+// see <https://www.gnu.org/licenses/>.
+
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+
+use bevy::prelude::*;
+
+/// A position on the desktop, in physical pixels.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deref, DerefMut)]
+pub struct ScreenPos(pub Vec2);
+
+impl ScreenPos {
+    /// A position of `(0, 0)`.
+    pub const ZERO: Self = Self(Vec2::ZERO);
+
+    /// Creates a new [`ScreenPos`].
+    #[inline]
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self(Vec2::new(x, y))
+    }
+
+    /// Creates a new [`ScreenPos`] from whole physical pixels.
+    #[inline]
+    pub fn from_physical(position: IVec2) -> Self {
+        Self(position.as_vec2())
+    }
+
+    /// Returns the nearest whole physical pixel, as used by window positions.
+    #[inline]
+    #[must_use]
+    pub fn to_physical(self) -> IVec2 {
+        self.0.round().as_ivec2()
+    }
+
+    /// Returns the distance in physical pixels between this position and the given position.
+    #[inline]
+    #[must_use]
+    pub fn distance(self, other: Self) -> f32 {
+        self.0.distance(other.0)
+    }
+
+    /// Returns the position the given fraction of the way between this position and the given position.
+    #[inline]
+    #[must_use]
+    pub fn lerp(self, other: Self, fraction: f32) -> Self {
+        Self(self.0.lerp(other.0, fraction))
+    }
+
+    /// Converts this position into a position within a window whose top-left corner is at the given origin.
+    #[inline]
+    #[must_use]
+    pub fn to_window(self, window_origin: Self, scale_factor: f32) -> WindowPos {
+        WindowPos(physical_to_logical(self - window_origin, scale_factor))
+    }
+}
+
+impl Add<Vec2> for ScreenPos {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Vec2) -> Self::Output {
+        Self(self.0 + rhs)
+    }
+}
+
+impl AddAssign<Vec2> for ScreenPos {
+    #[inline]
+    fn add_assign(&mut self, rhs: Vec2) {
+        self.0 += rhs;
+    }
+}
+
+impl Sub<Vec2> for ScreenPos {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Vec2) -> Self::Output {
+        Self(self.0 - rhs)
+    }
+}
+
+impl SubAssign<Vec2> for ScreenPos {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Vec2) {
+        self.0 -= rhs;
+    }
+}
+
+impl Sub for ScreenPos {
+    type Output = Vec2;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.0 - rhs.0
+    }
+}
+
+/// A position within a window, in logical pixels.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deref, DerefMut)]
+pub struct WindowPos(pub Vec2);
+
+impl WindowPos {
+    /// Converts this position into a position on the desktop, given the window's top-left corner.
+    #[inline]
+    #[must_use]
+    pub fn to_screen(self, window_origin: ScreenPos, scale_factor: f32) -> ScreenPos {
+        window_origin + logical_to_physical(self.0, scale_factor)
+    }
+}
+
+impl Sub for WindowPos {
+    type Output = Vec2;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.0 - rhs.0
+    }
+}
+
+/// A position or size within a texture, in texture pixels.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deref, DerefMut)]
+pub struct TexturePx(pub UVec2);
+
+impl TexturePx {
+    /// A size of zero pixels.
+    pub const ZERO: Self = Self(UVec2::ZERO);
+
+    /// Creates a new [`TexturePx`].
+    #[inline]
+    pub const fn new(x: u32, y: u32) -> Self {
+        Self(UVec2::new(x, y))
+    }
+}
+
+/// Converts a displacement in logical pixels into physical pixels.
+#[inline]
+#[must_use]
+pub fn logical_to_physical(displacement: Vec2, scale_factor: f32) -> Vec2 {
+    displacement * scale_factor
+}
+
+/// Converts a displacement in physical pixels into logical pixels.
+#[inline]
+#[must_use]
+pub fn physical_to_logical(displacement: Vec2, scale_factor: f32) -> Vec2 {
+    displacement / scale_factor
+}
+
+/// Returns the size of a window with the given logical side length in physical pixels.
+#[inline]
+#[must_use]
+pub fn window_extent(window_size: f32, scale_factor: f32) -> Vec2 {
+    logical_to_physical(Vec2::splat(window_size), scale_factor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logical_and_physical_round_trip() {
+        for scale_factor in [1.0, 1.25, 1.5, 2.0] {
+            let logical = Vec2::new(64.0, -12.5);
+            let physical = logical_to_physical(logical, scale_factor);
+
+            assert_eq!(physical, logical * scale_factor);
+            assert!((physical_to_logical(physical, scale_factor) - logical).abs().max_element() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn unit_scale_is_identity() {
+        let displacement = Vec2::new(123.4, -567.8);
+
+        assert_eq!(logical_to_physical(displacement, 1.0), displacement);
+        assert_eq!(physical_to_logical(displacement, 1.0), displacement);
+    }
+
+    #[test]
+    fn screen_to_window_is_relative_to_the_origin() {
+        let origin = ScreenPos::new(100.0, 200.0);
+        let position = ScreenPos::new(132.0, 264.0);
+
+        assert_eq!(position.to_window(origin, 1.0), WindowPos(Vec2::new(32.0, 64.0)));
+        assert_eq!(position.to_window(origin, 2.0), WindowPos(Vec2::new(16.0, 32.0)));
+    }
+
+    #[test]
+    fn window_and_screen_round_trip() {
+        let origin = ScreenPos::new(-1920.0, 40.0);
+
+        for scale_factor in [1.0, 1.5, 2.0] {
+            let position = WindowPos(Vec2::new(12.0, 34.0));
+            let screen = position.to_screen(origin, scale_factor);
+
+            assert_eq!(screen, origin + (position.0 * scale_factor));
+            assert!((screen.to_window(origin, scale_factor) - position).abs().max_element() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn window_extent_is_scaled() {
+        assert_eq!(window_extent(64.0, 1.0), Vec2::splat(64.0));
+        assert_eq!(window_extent(64.0, 1.5), Vec2::splat(96.0));
+    }
+
+    #[test]
+    fn physical_positions_round_to_the_nearest_pixel() {
+        assert_eq!(ScreenPos::new(10.4, -10.6).to_physical(), IVec2::new(10, -11));
+        assert_eq!(ScreenPos::from_physical(IVec2::new(-5, 7)), ScreenPos::new(-5.0, 7.0));
+    }
+}
@@ -0,0 +1,115 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright © 2025 Jaxydog
+//
+// This file is part of Desktop Cube Baby.
+//
+// Desktop Cube Baby is free software: you can redistribute it and/or modify it under the terms of the GNU General
+// Public License as published by the Free Software Foundation, either version 3 of the License, or (at your option)
+// any later version.
+//
+// Desktop Cube Baby is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the
+// implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more
+// details.
+//
+// You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
+// see <https://www.gnu.org/licenses/>.
+
+use std::fmt::Debug;
+
+use bevy::prelude::*;
+
+use crate::coords::ScreenPos;
+use crate::input::Chord;
+
+/// A source of signals that a screen capture is about to begin, and that the cube baby should get out of the way.
+pub trait EvasionTrigger: Debug + Send + Sync + 'static {
+    /// Returns `true` if the cube baby should begin evading.
+    ///
+    /// Every trigger is polled once per frame, even if another trigger has already fired.
+    fn poll(&mut self, key_input: &ButtonInput<KeyCode>) -> bool;
+}
+
+/// Triggers an evasion when a configured key chord is pressed while the window is focused.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HotkeyTrigger(pub Chord);
+
+impl EvasionTrigger for HotkeyTrigger {
+    fn poll(&mut self, key_input: &ButtonInput<KeyCode>) -> bool {
+        self.0.just_pressed(key_input)
+    }
+}
+
+/// Triggers an evasion when the platform's screenshot interface is launched.
+#[cfg(feature = "screenshot_probe")]
+#[derive(Debug)]
+pub struct ProcessTrigger {
+    /// The process list, which is only refreshed every [`ProcessTrigger::PROBE_DELAY`].
+    system: sysinfo::System,
+    /// The time of the last probe.
+    last_probe: Option<std::time::Instant>,
+    /// Whether a screenshot process was running during the last probe.
+    running: bool,
+}
+
+#[cfg(feature = "screenshot_probe")]
+impl ProcessTrigger {
+    /// The amount of time between probes, since listing processes is fairly expensive.
+    pub const PROBE_DELAY: std::time::Duration = std::time::Duration::from_millis(250);
+    /// The names of the processes that provide the platform's screenshot interface.
+    pub const PROCESS_NAMES: &[&str] = if cfg!(target_os = "windows") {
+        &["ScreenClippingHost.exe", "SnippingTool.exe"]
+    } else if cfg!(target_os = "macos") {
+        &["screencaptureui"]
+    } else {
+        &[]
+    };
+
+    /// Creates a new [`ProcessTrigger`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self { system: sysinfo::System::new(), last_probe: None, running: false }
+    }
+}
+
+#[cfg(feature = "screenshot_probe")]
+impl Default for ProcessTrigger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "screenshot_probe")]
+impl EvasionTrigger for ProcessTrigger {
+    fn poll(&mut self, _: &ButtonInput<KeyCode>) -> bool {
+        if Self::PROCESS_NAMES.is_empty() || self.last_probe.is_some_and(|v| v.elapsed() < Self::PROBE_DELAY) {
+            return false;
+        }
+
+        self.system.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+        self.last_probe = Some(std::time::Instant::now());
+
+        let was_running = self.running;
+
+        self.running = self.system.processes().values().any(|v| Self::PROCESS_NAMES.iter().any(|n| v.name() == *n));
+
+        // Only fire as the interface opens, rather than for as long as it stays open.
+        self.running && !was_running
+    }
+}
+
+/// Returns the position of the display corner closest to the given window position.
+///
+/// The bounds are given as the minimum and maximum positions that the window's edges may reach.
+#[must_use]
+pub fn nearest_corner(position: ScreenPos, extent: Vec2, minimum: ScreenPos, maximum: ScreenPos) -> ScreenPos {
+    // Prevent inverted bounds if the window is larger than the available space.
+    let maximum = (maximum - extent).max(minimum.0);
+    let center = position + (extent / 2.0);
+    let midpoint = (minimum.0 + maximum + extent) / 2.0;
+
+    ScreenPos::new(
+        if center.x < midpoint.x { minimum.x } else { maximum.x },
+        if center.y < midpoint.y { minimum.y } else { maximum.y },
+    )
+}
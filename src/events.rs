@@ -0,0 +1,100 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright © 2025 Jaxydog
+//
+// This file is part of Desktop Cube Baby.
+//
+// Desktop Cube Baby is free software: you can redistribute it and/or modify it under the terms of the GNU General
+// Public License as published by the Free Software Foundation, either version 3 of the License, or (at your option)
+// any later version.
+//
+// Desktop Cube Baby is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the
+// implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more
+// details.
+//
+// You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
+// see <https://www.gnu.org/licenses/>.
+
+use bevy::prelude::*;
+
+/// An event sent whenever the cube baby is pushed by the user.
+#[derive(Clone, Copy, Debug, PartialEq, Event)]
+pub struct Pushed {
+    /// The cube baby that was pushed.
+    pub baby: Entity,
+    /// The impulse that was applied to the cube baby's velocity.
+    pub impulse: Vec2,
+}
+
+/// An event sent to knock the cube baby in a random direction, as if a key had been pressed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Event)]
+pub struct Knock;
+
+/// An event sent whenever an action is chosen from the cube baby's context menu or by its key binding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Event)]
+pub enum MenuAction {
+    /// Knock the cube baby in a random direction.
+    Knock,
+    /// Move the cube baby to the center of the display.
+    Center,
+    /// Pause or resume the cube baby.
+    Pause,
+    /// Toggle between the cube baby's passive and skittish behaviors.
+    Skittish,
+    /// Toggle between the cube baby's passive and following behaviors.
+    Follow,
+    /// Toggle between the cube baby's passive and orbiting behaviors.
+    Orbit,
+    /// Spawn an extra cube baby beside the primary one.
+    Spawn,
+    /// Despawn the most recently spawned extra cube baby.
+    Despawn,
+    /// Exit the application.
+    Quit,
+}
+
+/// An event sent whenever a cube baby bounces off of one of the display's walls.
+#[derive(Clone, Copy, Debug, PartialEq, Event)]
+pub struct WallBounce {
+    /// The cube baby that bounced.
+    pub baby: Entity,
+    /// The wall that was hit.
+    pub side: WallSide,
+    /// The speed at which the wall was hit, along the wall's normal.
+    pub speed: f32,
+}
+
+/// One of the display's walls.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WallSide {
+    /// The left wall.
+    Left,
+    /// The right wall.
+    Right,
+    /// The top wall.
+    Top,
+    /// The bottom wall.
+    Bottom,
+}
+
+impl WallSide {
+    /// Returns `true` if this wall is hit through horizontal movement.
+    #[inline]
+    #[must_use]
+    pub const fn is_horizontal(self) -> bool {
+        matches!(self, Self::Left | Self::Right)
+    }
+
+    /// Returns `true` if this wall is hit through vertical movement.
+    #[inline]
+    #[must_use]
+    pub const fn is_vertical(self) -> bool {
+        matches!(self, Self::Top | Self::Bottom)
+    }
+}
+
+/// An event sent to recreate the primary window with new settings.
+///
+/// The cube baby's state is preserved across the swap, and the application will not exit while it is in progress.
+#[derive(Clone, Debug, Event)]
+pub struct RecreateWindow(pub Window);
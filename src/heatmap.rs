@@ -0,0 +1,241 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright © 2025 Jaxydog
+//
+// This file is part of Desktop Cube Baby.
+//
+// Desktop Cube Baby is free software: you can redistribute it and/or modify it under the terms of the GNU General
+// Public License as published by the Free Software Foundation, either version 3 of the License, or (at your option)
+// any later version.
+//
+// Desktop Cube Baby is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the
+// implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more
+// details.
+//
+// You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
+// see <https://www.gnu.org/licenses/>.
+
+use bevy::prelude::*;
+use image::{Rgba, RgbaImage};
+
+/// The name of the file that the heatmap is persisted to.
+pub const FILE_NAME: &str = "heatmap.bin";
+/// The name of the file that the heatmap is rendered to.
+pub const IMAGE_FILE_NAME: &str = "heatmap.png";
+/// The number of cells along each axis of the heatmap.
+pub const GRID_SIZE: UVec2 = UVec2::new(64, 36);
+/// The size of one side of each cell when rendered, in pixels.
+pub const CELL_SIZE: u32 = 16;
+
+/// The bytes that every persisted heatmap begins with.
+const MAGIC: &[u8; 4] = b"DCBH";
+/// The colors that the heatmap ramps through, from least to most visited.
+const RAMP: [[u8; 4]; 5] = [[0, 0, 0, 255], [32, 16, 128, 255], [192, 32, 64, 255], [255, 192, 0, 255], [255; 4]];
+
+/// Tracks the amount of time that the cube baby has spent over each part of the display.
+///
+/// Cells cover proportional areas of the display bounds, so the heatmap carries over as-is if the bounds change. Time
+/// is accumulated in double precision, since a heatmap may keep growing for months on end.
+#[derive(Clone, Debug, PartialEq, Resource)]
+pub struct Heatmap {
+    /// The number of seconds spent within each cell, in row-major order.
+    cells: Vec<f64>,
+}
+
+impl Heatmap {
+    /// Creates a new empty [`Heatmap`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self { cells: vec![0.0; (GRID_SIZE.x * GRID_SIZE.y) as usize] }
+    }
+
+    /// Records time spent at the given position, as a fraction of the display bounds.
+    pub fn record(&mut self, fraction: Vec2, seconds: f64) {
+        if !fraction.is_finite() {
+            return;
+        }
+
+        let cell = (fraction.clamp(Vec2::ZERO, Vec2::ONE) * GRID_SIZE.as_vec2()).as_uvec2().min(GRID_SIZE - 1);
+
+        self.cells[((cell.y * GRID_SIZE.x) + cell.x) as usize] += seconds;
+    }
+
+    /// Returns the number of seconds spent within the given cell.
+    #[must_use]
+    pub fn get(&self, cell: UVec2) -> f64 {
+        self.cells.get(((cell.y * GRID_SIZE.x) + cell.x) as usize).copied().unwrap_or(0.0)
+    }
+
+    /// Returns the total number of seconds recorded.
+    #[must_use]
+    pub fn total(&self) -> f64 {
+        self.cells.iter().sum()
+    }
+
+    /// Encodes the heatmap into bytes for persistence.
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(12 + (self.cells.len() * 8));
+
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&GRID_SIZE.x.to_le_bytes());
+        bytes.extend_from_slice(&GRID_SIZE.y.to_le_bytes());
+        bytes.extend(self.cells.iter().flat_map(|v| v.to_le_bytes()));
+
+        bytes
+    }
+
+    /// Decodes a persisted heatmap, returning `None` if it is malformed or has a different grid size.
+    #[must_use]
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        const CELL_COUNT: usize = (GRID_SIZE.x * GRID_SIZE.y) as usize;
+
+        let (header, cells) = bytes.split_at_checked(12)?;
+        let (magic, size) = header.split_at(4);
+
+        if magic != MAGIC || size != [GRID_SIZE.x.to_le_bytes(), GRID_SIZE.y.to_le_bytes()].concat() {
+            return None;
+        }
+
+        if cells.len() != CELL_COUNT * 8 {
+            return None;
+        }
+
+        let cells: Vec<f64> =
+            cells.chunks_exact(8).map(|v| f64::from_le_bytes(std::array::from_fn(|i| v[i]))).collect();
+
+        cells.iter().all(|v| v.is_finite() && *v >= 0.0).then_some(Self { cells })
+    }
+
+    /// Renders the heatmap into an image, with each cell drawn as a square of the given size.
+    #[must_use]
+    pub fn render(&self, cell_size: u32) -> RgbaImage {
+        let maximum = self.cells.iter().copied().fold(0.0, f64::max);
+
+        RgbaImage::from_fn(GRID_SIZE.x * cell_size, GRID_SIZE.y * cell_size, |x, y| {
+            let seconds = self.get(UVec2::new(x / cell_size, y / cell_size));
+
+            // Rarely visited cells would be invisible on a linear scale.
+            self::ramp(if maximum > 0.0 { (seconds / maximum).sqrt() as f32 } else { 0.0 })
+        })
+    }
+}
+
+impl Default for Heatmap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the color of the heatmap's ramp at the given point between `0.0` and `1.0`.
+#[must_use]
+pub fn ramp(t: f32) -> Rgba<u8> {
+    let t = t.clamp(0.0, 1.0) * (RAMP.len() - 1) as f32;
+    let index = (t.floor() as usize).min(RAMP.len() - 2);
+    let (from, to) = (RAMP[index], RAMP[index + 1]);
+    let blend = t - index as f32;
+
+    Rgba(std::array::from_fn(|i| {
+        (f32::from(from[i]) + ((f32::from(to[i]) - f32::from(from[i])) * blend)).round() as u8
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_are_bucketed_proportionally() {
+        let mut heatmap = Heatmap::new();
+
+        heatmap.record(Vec2::new(0.0, 0.0), 1.0);
+        heatmap.record(Vec2::new(0.5, 0.5), 2.0);
+        heatmap.record(Vec2::new(1.0, 1.0), 3.0);
+
+        assert_eq!(heatmap.get(UVec2::ZERO), 1.0);
+        assert_eq!(heatmap.get(GRID_SIZE / 2), 2.0);
+        // The far edges belong to the last cells, rather than falling off of the grid.
+        assert_eq!(heatmap.get(GRID_SIZE - 1), 3.0);
+        assert_eq!(heatmap.total(), 6.0);
+    }
+
+    #[test]
+    fn out_of_bounds_positions_are_clamped() {
+        let mut heatmap = Heatmap::new();
+
+        heatmap.record(Vec2::new(-2.0, 0.0), 1.0);
+        heatmap.record(Vec2::new(5.0, 5.0), 1.0);
+        heatmap.record(Vec2::new(f32::NAN, 0.5), 1.0);
+
+        assert_eq!(heatmap.get(UVec2::ZERO), 1.0);
+        assert_eq!(heatmap.get(GRID_SIZE - 1), 1.0);
+        assert_eq!(heatmap.total(), 2.0);
+    }
+
+    #[test]
+    fn long_sessions_keep_accumulating() {
+        let mut heatmap = Heatmap::new();
+
+        // A year of one second samples, all in one place.
+        heatmap.record(Vec2::ZERO, 60.0 * 60.0 * 24.0 * 365.0);
+        heatmap.record(Vec2::ZERO, 1.0);
+
+        assert_eq!(heatmap.get(UVec2::ZERO), (60.0 * 60.0 * 24.0 * 365.0) + 1.0);
+    }
+
+    #[test]
+    fn heatmaps_round_trip_through_bytes() {
+        let mut heatmap = Heatmap::new();
+
+        heatmap.record(Vec2::new(0.25, 0.75), 12.5);
+        heatmap.record(Vec2::new(0.9, 0.1), 0.125);
+
+        assert_eq!(Heatmap::from_bytes(&heatmap.to_bytes()), Some(heatmap));
+    }
+
+    #[test]
+    fn malformed_heatmaps_are_rejected() {
+        let bytes = Heatmap::new().to_bytes();
+
+        assert_eq!(Heatmap::from_bytes(&bytes[.. bytes.len() - 1]), None);
+        assert_eq!(Heatmap::from_bytes(&bytes[.. 8]), None);
+        assert_eq!(Heatmap::from_bytes(&[&b"XXXX"[..], &bytes[4 ..]].concat()), None);
+
+        let mut negative = Heatmap::new();
+
+        negative.cells[0] = -1.0;
+
+        assert_eq!(Heatmap::from_bytes(&negative.to_bytes()), None);
+    }
+
+    #[test]
+    fn rendering_covers_every_cell() {
+        let mut heatmap = Heatmap::new();
+
+        heatmap.record(Vec2::ZERO, 10.0);
+
+        let image = heatmap.render(4);
+
+        assert_eq!(image.dimensions(), (GRID_SIZE.x * 4, GRID_SIZE.y * 4));
+        // The most visited cell is drawn at the top of the ramp, and unvisited cells at the bottom.
+        assert_eq!(*image.get_pixel(0, 0), Rgba(RAMP[RAMP.len() - 1]));
+        assert_eq!(*image.get_pixel(3, 3), Rgba(RAMP[RAMP.len() - 1]));
+        assert_eq!(*image.get_pixel(4, 0), Rgba(RAMP[0]));
+    }
+
+    #[test]
+    fn empty_heatmaps_render_blank() {
+        let image = Heatmap::new().render(1);
+
+        assert!(image.pixels().all(|v| *v == Rgba(RAMP[0])));
+    }
+
+    #[test]
+    fn ramp_is_clamped() {
+        assert_eq!(ramp(-1.0), Rgba(RAMP[0]));
+        assert_eq!(ramp(0.0), Rgba(RAMP[0]));
+        assert_eq!(ramp(1.0), Rgba(RAMP[RAMP.len() - 1]));
+        assert_eq!(ramp(2.0), Rgba(RAMP[RAMP.len() - 1]));
+        assert_eq!(ramp(0.25), Rgba(RAMP[1]));
+    }
+}
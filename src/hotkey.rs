@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright © 2025 Jaxydog
+//
+// This file is part of Desktop Cube Baby.
+//
+// Desktop Cube Baby is free software: you can redistribute it and/or modify it under the terms of the GNU General
+// Public License as published by the Free Software Foundation, either version 3 of the License, or (at your option)
+// any later version.
+//
+// Desktop Cube Baby is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the
+// implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more
+// details.
+//
+// You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
+// see <https://www.gnu.org/licenses/>.
+
+use std::fmt::Display;
+
+use bevy::prelude::*;
+use global_hotkey::hotkey::{Code, HotKey, Modifiers};
+use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
+
+use crate::input::{Chord, ChordKey};
+
+/// A key chord registered with the platform, so that it may be detected while the window is unfocused.
+///
+/// This must be created on the main thread, and is therefore stored as a non-send resource.
+pub struct GlobalHotkey {
+    /// The platform's hotkey manager.
+    manager: GlobalHotKeyManager,
+    /// The registered hotkey.
+    hotkey: HotKey,
+}
+
+impl GlobalHotkey {
+    /// Registers the given chord as a global hotkey.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the chord cannot be represented as a global hotkey, or if the platform
+    /// refused to register it, such as when another application has already claimed it.
+    pub fn register(chord: &Chord) -> Result<Self, GlobalHotkeyError> {
+        let hotkey = self::to_hotkey(chord).ok_or(GlobalHotkeyError::Unsupported)?;
+        let manager = GlobalHotKeyManager::new().map_err(GlobalHotkeyError::Platform)?;
+
+        manager.register(hotkey).map_err(GlobalHotkeyError::Platform)?;
+
+        Ok(Self { manager, hotkey })
+    }
+
+    /// Returns `true` if the hotkey has been pressed since this was last called.
+    #[must_use]
+    pub fn just_pressed(&self) -> bool {
+        // Drain every pending event, so that presses do not build up while nothing is listening.
+        GlobalHotKeyEvent::receiver()
+            .try_iter()
+            .filter(|v| v.id() == self.hotkey.id() && v.state() == HotKeyState::Pressed)
+            .count()
+            > 0
+    }
+
+    /// Unregisters the hotkey, releasing it for use by other applications.
+    pub fn unregister(&self) {
+        if let Err(error) = self.manager.unregister(self.hotkey) {
+            warn!("failed to unregister the global hotkey: {error}");
+        }
+    }
+}
+
+/// An error that may occur when registering a global hotkey.
+#[derive(Debug)]
+pub enum GlobalHotkeyError {
+    /// The chord does not contain exactly one non-modifier key that the platform understands.
+    Unsupported,
+    /// The platform failed to register the hotkey.
+    Platform(global_hotkey::Error),
+}
+
+impl Display for GlobalHotkeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unsupported => write!(f, "chords must contain exactly one non-modifier key"),
+            Self::Platform(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for GlobalHotkeyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Unsupported => None,
+            Self::Platform(error) => Some(error),
+        }
+    }
+}
+
+/// Converts the given chord into a global hotkey, returning [`None`] if it cannot be represented as one.
+fn to_hotkey(chord: &Chord) -> Option<HotKey> {
+    let mut modifiers = Modifiers::empty();
+    let mut code = None;
+
+    for key in &chord.0 {
+        match *key {
+            ChordKey::Control => modifiers |= Modifiers::CONTROL,
+            ChordKey::Shift => modifiers |= Modifiers::SHIFT,
+            ChordKey::Alt => modifiers |= Modifiers::ALT,
+            ChordKey::Super => modifiers |= Modifiers::SUPER,
+            // Bevy's key codes share their names with the platform-agnostic codes used by the hotkey manager.
+            ChordKey::Key(key_code) if code.is_none() => code = Some(format!("{key_code:?}").parse::<Code>().ok()?),
+            ChordKey::Key(_) => return None,
+        }
+    }
+
+    Some(HotKey::new(Some(modifiers).filter(|v| !v.is_empty()), code?))
+}
@@ -0,0 +1,422 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright © 2025 Jaxydog
+//
+// This file is part of Desktop Cube Baby.
+//
+// Desktop Cube Baby is free software: you can redistribute it and/or modify it under the terms of the GNU General
+// Public License as published by the Free Software Foundation, either version 3 of the License, or (at your option)
+// any later version.
+//
+// Desktop Cube Baby is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the
+// implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more
+// details.
+//
+// You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
+// see <https://www.gnu.org/licenses/>.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{FAST_FORWARD_SPEED, SLOW_MOTION_SPEED};
+
+/// A single key within a [`Chord`], or a mouse button bound to an action.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChordKey {
+    /// Either control key.
+    Control,
+    /// Either shift key.
+    Shift,
+    /// Either alt key.
+    Alt,
+    /// Either super key.
+    Super,
+    /// A specific key.
+    Key(KeyCode),
+    /// A mouse button, which is only ever pressed over one of the application's windows.
+    Mouse(MouseButton),
+}
+
+impl ChordKey {
+    /// Returns the key codes that satisfy this key, or [`None`] if it is a mouse button.
+    #[must_use]
+    pub const fn key_codes(self) -> Option<[KeyCode; 2]> {
+        match self {
+            Self::Control => Some([KeyCode::ControlLeft, KeyCode::ControlRight]),
+            Self::Shift => Some([KeyCode::ShiftLeft, KeyCode::ShiftRight]),
+            Self::Alt => Some([KeyCode::AltLeft, KeyCode::AltRight]),
+            Self::Super => Some([KeyCode::SuperLeft, KeyCode::SuperRight]),
+            Self::Key(key_code) => Some([key_code, key_code]),
+            Self::Mouse(_) => None,
+        }
+    }
+
+    /// Returns the mouse button that satisfies this key, or [`None`] if it is a keyboard key.
+    #[must_use]
+    pub const fn mouse_button(self) -> Option<MouseButton> {
+        if let Self::Mouse(mouse_button) = self { Some(mouse_button) } else { None }
+    }
+
+    /// Returns `true` if this key is currently pressed.
+    #[must_use]
+    pub fn pressed(self, input: &ButtonInput<KeyCode>) -> bool {
+        self.key_codes().is_some_and(|v| input.any_pressed(v))
+    }
+
+    /// Returns `true` if this key was pressed this frame.
+    #[must_use]
+    pub fn just_pressed(self, input: &ButtonInput<KeyCode>) -> bool {
+        self.key_codes().is_some_and(|v| input.any_just_pressed(v))
+    }
+
+    /// Returns `true` if this key was released this frame.
+    #[must_use]
+    pub fn just_released(self, input: &ButtonInput<KeyCode>) -> bool {
+        self.key_codes().is_some_and(|v| input.any_just_released(v))
+    }
+
+    /// Returns `true` if this mouse button is currently pressed.
+    #[must_use]
+    pub fn mouse_pressed(self, input: &ButtonInput<MouseButton>) -> bool {
+        self.mouse_button().is_some_and(|v| input.pressed(v))
+    }
+
+    /// Returns `true` if this mouse button was pressed this frame.
+    #[must_use]
+    pub fn mouse_just_pressed(self, input: &ButtonInput<MouseButton>) -> bool {
+        self.mouse_button().is_some_and(|v| input.just_pressed(v))
+    }
+}
+
+impl FromStr for ChordKey {
+    type Err = ParseChordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let key_code = match s.trim().to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => return Ok(Self::Control),
+            "shift" => return Ok(Self::Shift),
+            "alt" | "option" => return Ok(Self::Alt),
+            "super" | "meta" | "win" | "cmd" | "command" => return Ok(Self::Super),
+            name if name.starts_with("mouse") => {
+                return self::parse_mouse_button(name)
+                    .map(Self::Mouse)
+                    .ok_or_else(|| ParseChordError(s.trim().to_string()));
+            }
+            name => self::parse_key_code(name),
+        };
+
+        key_code.map(Self::Key).ok_or_else(|| ParseChordError(s.trim().to_string()))
+    }
+}
+
+impl Display for ChordKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Control => f.write_str("ctrl"),
+            Self::Shift => f.write_str("shift"),
+            Self::Alt => f.write_str("alt"),
+            Self::Super => f.write_str("super"),
+            // Key code names are accepted when parsing, such as `keya` and `arrowup`.
+            Self::Key(key_code) => f.write_str(&format!("{key_code:?}").to_ascii_lowercase()),
+            Self::Mouse(MouseButton::Other(number)) => write!(f, "mouse{number}"),
+            Self::Mouse(mouse_button) => write!(f, "mouse{}", format!("{mouse_button:?}").to_ascii_lowercase()),
+        }
+    }
+}
+
+/// An action that may be bound to keys or mouse buttons.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Action {
+    /// Knock the cube baby, charging the knock while held.
+    Knock,
+    /// Exit the application once held for long enough.
+    Quit,
+    /// Pause or resume the cube baby.
+    Pause,
+    /// Move the cube baby to the center of the display.
+    Center,
+    /// Nudge the cube baby upwards.
+    NudgeUp,
+    /// Nudge the cube baby downwards.
+    NudgeDown,
+    /// Nudge the cube baby to the left.
+    NudgeLeft,
+    /// Nudge the cube baby to the right.
+    NudgeRight,
+    /// Slow the cube baby's physics down.
+    SlowMotion,
+    /// Return the cube baby's physics to their normal speed.
+    NormalSpeed,
+    /// Speed the cube baby's physics up.
+    FastForward,
+    /// Pick up the cube baby while held over its window, throwing it upon release.
+    Grab,
+    /// Open the cube baby's context menu.
+    Menu,
+}
+
+impl Action {
+    /// Every action.
+    pub const ALL: [Self; 13] = [
+        Self::Knock,
+        Self::Quit,
+        Self::Pause,
+        Self::Center,
+        Self::NudgeUp,
+        Self::NudgeDown,
+        Self::NudgeLeft,
+        Self::NudgeRight,
+        Self::SlowMotion,
+        Self::NormalSpeed,
+        Self::FastForward,
+        Self::Grab,
+        Self::Menu,
+    ];
+
+    /// Returns the name used for this action within the settings file.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Knock => "knock",
+            Self::Quit => "quit",
+            Self::Pause => "pause",
+            Self::Center => "center",
+            Self::NudgeUp => "nudge_up",
+            Self::NudgeDown => "nudge_down",
+            Self::NudgeLeft => "nudge_left",
+            Self::NudgeRight => "nudge_right",
+            Self::SlowMotion => "slow_motion",
+            Self::NormalSpeed => "normal_speed",
+            Self::FastForward => "fast_forward",
+            Self::Grab => "grab",
+            Self::Menu => "menu",
+        }
+    }
+
+    /// Returns the keys bound to this action by default.
+    #[must_use]
+    pub const fn default_keys(self) -> &'static [ChordKey] {
+        match self {
+            Self::Knock => &[ChordKey::Key(KeyCode::Space)],
+            Self::Quit => &[ChordKey::Key(KeyCode::Escape)],
+            Self::Pause => &[ChordKey::Key(KeyCode::KeyP)],
+            Self::Center => &[ChordKey::Key(KeyCode::KeyC), ChordKey::Key(KeyCode::Home)],
+            Self::NudgeUp => &[ChordKey::Key(KeyCode::ArrowUp), ChordKey::Key(KeyCode::KeyW)],
+            Self::NudgeDown => &[ChordKey::Key(KeyCode::ArrowDown), ChordKey::Key(KeyCode::KeyS)],
+            Self::NudgeLeft => &[ChordKey::Key(KeyCode::ArrowLeft), ChordKey::Key(KeyCode::KeyA)],
+            Self::NudgeRight => &[ChordKey::Key(KeyCode::ArrowRight), ChordKey::Key(KeyCode::KeyD)],
+            Self::SlowMotion => &[ChordKey::Key(KeyCode::Digit1)],
+            Self::NormalSpeed => &[ChordKey::Key(KeyCode::Digit2)],
+            Self::FastForward => &[ChordKey::Key(KeyCode::Digit3)],
+            Self::Grab => &[ChordKey::Mouse(MouseButton::Left)],
+            Self::Menu => &[ChordKey::Mouse(MouseButton::Right)],
+        }
+    }
+
+    /// Returns the direction that this action nudges the cube baby in, if it is a nudge.
+    #[must_use]
+    pub const fn nudge_direction(self) -> Option<Vec2> {
+        match self {
+            Self::NudgeUp => Some(Vec2::NEG_Y),
+            Self::NudgeDown => Some(Vec2::Y),
+            Self::NudgeLeft => Some(Vec2::NEG_X),
+            Self::NudgeRight => Some(Vec2::X),
+            _ => None,
+        }
+    }
+
+    /// Returns the simulation speed that this action switches to, if it changes the speed.
+    #[must_use]
+    pub const fn simulation_speed(self) -> Option<f32> {
+        match self {
+            Self::SlowMotion => Some(SLOW_MOTION_SPEED),
+            Self::NormalSpeed => Some(1.0),
+            Self::FastForward => Some(FAST_FORWARD_SPEED),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = ParseActionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().find(|v| v.name() == s.trim()).ok_or_else(|| ParseActionError(s.trim().to_string()))
+    }
+}
+
+/// A combination of keys that must be held together.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Chord(pub Vec<ChordKey>);
+
+impl Chord {
+    /// Returns `true` if every key in this chord is held and at least one of them was pressed this frame.
+    #[must_use]
+    pub fn just_pressed(&self, input: &ButtonInput<KeyCode>) -> bool {
+        !self.0.is_empty() && self.0.iter().all(|v| v.pressed(input)) && self.0.iter().any(|v| v.just_pressed(input))
+    }
+}
+
+impl FromStr for Chord {
+    type Err = ParseChordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Chords are matched against the keyboard alone, so a mouse button within one could never be pressed.
+        s.split('+')
+            .map(|v| {
+                v.parse().and_then(|key: ChordKey| match key {
+                    ChordKey::Mouse(_) => Err(ParseChordError(v.trim().to_string())),
+                    key => Ok(key),
+                })
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl TryFrom<String> for Chord {
+    type Error = ParseChordError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// An error returned when a key within a chord could not be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseChordError(pub String);
+
+impl Display for ParseChordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown key '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ParseChordError {}
+
+/// An error returned when an action could not be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseActionError(pub String);
+
+impl Display for ParseActionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown action '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ParseActionError {}
+
+/// Parses a lowercase mouse button name into a mouse button, such as `mouseleft` or `mouse8`.
+fn parse_mouse_button(name: &str) -> Option<MouseButton> {
+    Some(match name.strip_prefix("mouse")? {
+        "left" => MouseButton::Left,
+        "right" => MouseButton::Right,
+        "middle" => MouseButton::Middle,
+        "back" => MouseButton::Back,
+        "forward" => MouseButton::Forward,
+        number => MouseButton::Other(number.parse().ok()?),
+    })
+}
+
+/// Parses a lowercase key name into a key code.
+fn parse_key_code(name: &str) -> Option<KeyCode> {
+    const LETTERS: [KeyCode; 26] = [
+        KeyCode::KeyA,
+        KeyCode::KeyB,
+        KeyCode::KeyC,
+        KeyCode::KeyD,
+        KeyCode::KeyE,
+        KeyCode::KeyF,
+        KeyCode::KeyG,
+        KeyCode::KeyH,
+        KeyCode::KeyI,
+        KeyCode::KeyJ,
+        KeyCode::KeyK,
+        KeyCode::KeyL,
+        KeyCode::KeyM,
+        KeyCode::KeyN,
+        KeyCode::KeyO,
+        KeyCode::KeyP,
+        KeyCode::KeyQ,
+        KeyCode::KeyR,
+        KeyCode::KeyS,
+        KeyCode::KeyT,
+        KeyCode::KeyU,
+        KeyCode::KeyV,
+        KeyCode::KeyW,
+        KeyCode::KeyX,
+        KeyCode::KeyY,
+        KeyCode::KeyZ,
+    ];
+    const DIGITS: [KeyCode; 10] = [
+        KeyCode::Digit0,
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+        KeyCode::Digit6,
+        KeyCode::Digit7,
+        KeyCode::Digit8,
+        KeyCode::Digit9,
+    ];
+    const FUNCTION_KEYS: [KeyCode; 12] = [
+        KeyCode::F1,
+        KeyCode::F2,
+        KeyCode::F3,
+        KeyCode::F4,
+        KeyCode::F5,
+        KeyCode::F6,
+        KeyCode::F7,
+        KeyCode::F8,
+        KeyCode::F9,
+        KeyCode::F10,
+        KeyCode::F11,
+        KeyCode::F12,
+    ];
+
+    // Accept both the short names and the names used by key codes, such as `a` and `keya`.
+    let name = name.strip_prefix("key").filter(|v| v.len() == 1).unwrap_or(name);
+    let name = name.strip_prefix("digit").filter(|v| v.len() == 1).unwrap_or(name);
+    let name = name.strip_prefix("arrow").unwrap_or(name);
+
+    if let [byte] = name.as_bytes() {
+        return match byte {
+            b'a' ..= b'z' => Some(LETTERS[usize::from(byte - b'a')]),
+            b'0' ..= b'9' => Some(DIGITS[usize::from(byte - b'0')]),
+            b'=' | b'+' => Some(KeyCode::Equal),
+            b'-' => Some(KeyCode::Minus),
+            _ => None,
+        };
+    }
+
+    if let Some(number) = name.strip_prefix('f').and_then(|v| v.parse::<usize>().ok()) {
+        return number.checked_sub(1).and_then(|v| FUNCTION_KEYS.get(v)).copied();
+    }
+
+    Some(match name {
+        "space" => KeyCode::Space,
+        "enter" | "return" => KeyCode::Enter,
+        "escape" | "esc" => KeyCode::Escape,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "up" => KeyCode::ArrowUp,
+        "down" => KeyCode::ArrowDown,
+        "left" => KeyCode::ArrowLeft,
+        "right" => KeyCode::ArrowRight,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "insert" => KeyCode::Insert,
+        "delete" => KeyCode::Delete,
+        "printscreen" => KeyCode::PrintScreen,
+        "plus" | "equal" => KeyCode::Equal,
+        "minus" => KeyCode::Minus,
+        _ => return None,
+    })
+}
@@ -19,24 +19,76 @@
 #![cfg_attr(any(not(debug_assertions), feature = "visible_console"), windows_subsystem = "windows")]
 
 use std::process::ExitCode;
+use std::time::SystemTime;
 
 use bevy::asset::embedded_asset;
 use bevy::asset::io::embedded::EmbeddedAssetRegistry;
+use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy::ecs::entity::{EntityHashMap, EntityHashSet};
 use bevy::image::ImageSampler;
+use bevy::input::gamepad::GamepadRumbleRequest;
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
 use bevy::log::{Level, LogPlugin};
 use bevy::prelude::*;
+use bevy::render::camera::{ClearColorConfig, RenderTarget};
+use bevy::render::view::RenderLayers;
 use bevy::window::{
-    CompositeAlphaMode, EnabledButtons, ExitCondition, PresentMode, PrimaryWindow, WindowLevel, WindowResolution,
+    CompositeAlphaMode, EnabledButtons, ExitCondition, PresentMode, PrimaryWindow, WindowLevel, WindowRef,
+    WindowResolution,
 };
 use bevy::winit::{UpdateMode, WinitSettings, WinitWindows};
 
-use self::components::{CubeBaby, Distance, Position, PushDelay, Velocity};
-use self::resources::{DisplayProperties, TextureMetadata};
+use self::components::{
+    BabyWindow, CornerTrap, CubeBaby, Despawning, Distance, Dizzy, DoubleJumped, DragHistory, EdgePatrolling, Evading,
+    FixedPositions, Flicked, Flopped, Grounded, Held, Onboarding, OnboardingHint, Patrolling, Petting, Position,
+    PrimaryBaby, PushDelays, SpriteOffset, Squash, Tint, Velocity, WanderDelay, WindowBorder,
+};
+use self::coords::{ScreenPos, TexturePx, WindowPos};
+use self::evasion::HotkeyTrigger;
+use self::events::{Knock, MenuAction, Pushed, RecreateWindow, WallBounce};
+use self::heatmap::Heatmap;
+use self::input::Action;
+use self::peers::PeerMessage;
+use self::persistence::SavedState;
+use self::push::{ShakeTracker, TouchGesture, TouchTracker};
+use self::resources::{
+    AlphaMask, BehaviorMode, ClickThrough, DisplayProperties, EvasionTriggers, ExitHold, ExtraBabies, ExtraBaby,
+    ForegroundWindow, GlobalCursor, Gravity, Keybindings, Mood, MoodModifiers, PatrolRoute, PeerBabies, PeerSocket,
+    PresentationThrottle, PushModel, QualityGovernor, Rng, SafeMode, ShutdownSignal, SimulationSpeed, TextureMetadata,
+    Wind, WindowSnapshot,
+};
+use self::settings::{Background, Settings};
 use self::states::{ApplicationLoadingMarker, DisplayLoadingMarker, LoadingState, TextureLoadingMarker};
 
+pub mod bounds;
+pub mod branding;
 pub mod components;
+pub mod coords;
+pub mod evasion;
+pub mod events;
+pub mod heatmap;
+#[cfg(feature = "global_hotkey")]
+pub mod hotkey;
+pub mod input;
+#[cfg(feature = "context_menu")]
+pub mod menu;
+pub mod notification;
+pub mod onboarding;
+pub mod peers;
+pub mod persistence;
+pub mod physics;
+pub mod platform;
+pub mod push;
 pub mod resources;
+pub mod rumble;
+pub mod settings;
 pub mod states;
+pub mod steering;
+pub mod storage;
+pub mod stuck;
+pub mod tiredness;
+#[cfg(feature = "weather")]
+pub mod weather;
 
 /// The number of frames in the baby's texture atlas animation.
 pub const ATLAS_FRAMES: u32 = 8;
@@ -44,22 +96,224 @@ pub const ATLAS_FRAMES: u32 = 8;
 pub const SPRITE_SCALE: f32 = 2.0;
 /// The size of one side of the spawned window.
 pub const WINDOW_SIZE: f32 = 32.0 * SPRITE_SCALE;
+/// The smallest size that the window may be resized to.
+pub const MINIMUM_WINDOW_SIZE: f32 = 32.0;
+/// The largest size that the window may be resized to.
+pub const MAXIMUM_WINDOW_SIZE: f32 = 256.0;
+/// The amount that the window is resized by for each line scrolled.
+pub const RESIZE_STEP: f32 = 8.0;
+/// The number of pixels scrolled by precise scrolling devices, such as touchpads, that count as one line.
+pub const RESIZE_PIXELS_PER_LINE: f32 = 16.0;
+/// The distance in logical pixels that a touch may travel before it counts as a swipe rather than a tap.
+pub const TOUCH_TAP_DISTANCE: f32 = 8.0;
+/// The speed in logical pixels per second that a tap pushes the cube baby as if the touch were moving at.
+pub const TOUCH_TAP_SPEED: f32 = 1024.0;
 /// The strength that the cube baby is pushed at when touched by the cursor.
 pub const PUSH_STRENGTH: f32 = 16.0;
+/// The strength that the cube baby is pushed at for each logical pixel per second of cursor speed.
+pub const PUSH_SPEED_SCALE: f32 = 0.5;
+/// The alpha above which the cube baby's pixels count as solid for cursor collision.
+pub const COLLISION_ALPHA_THRESHOLD: u8 = 127;
+/// The amount of time in seconds that the knock key must be held for before a knock starts charging.
+pub const KNOCK_CHARGE_DELAY: f32 = 0.3;
+/// The amount of time in seconds that the space bar must be held for to fully charge a knock, after it starts charging.
+pub const KNOCK_CHARGE_DURATION: f32 = 1.5;
+/// The number of frames per second that the cube baby spins at while a knock is fully charged.
+pub const KNOCK_CHARGE_SPIN_RATE: f32 = 24.0;
 /// The amount of time in seconds between possible cube baby pushes.
 pub const PUSH_DELAY: f64 = 0.25;
+/// The simulation speed used while in slow motion.
+pub const SLOW_MOTION_SPEED: f32 = 0.25;
+/// The simulation speed used while fast-forwarding.
+pub const FAST_FORWARD_SPEED: f32 = 3.0;
+/// The largest step in seconds that the cube baby's motion is integrated over at once.
+pub const MAX_STEP_DELTA: f32 = 0.1;
+/// The time in seconds between frames beyond which the application is assumed to have stalled or slept.
+pub const STALL_DELTA: f32 = 2.0;
+/// The smallest cursor movement that counts towards shaking, in logical pixels.
+pub const SHAKE_MINIMUM_DELTA: f32 = 2.0;
+/// The number of seconds within which the cursor's direction reversals are counted.
+pub const DIZZY_SHAKE_WINDOW: f64 = 1.0;
+/// The number of direction reversals within the shake window that make the cube baby dizzy.
+pub const DIZZY_REVERSALS: usize = 6;
+/// The number of seconds that the cube baby stays dizzy for.
+pub const DIZZY_DURATION: f32 = 2.0;
+/// The rate at which a dizzy cube baby's velocity decays, per second.
+pub const DIZZY_DRAG: f32 = 8.0;
+/// The number of atlas frames that a dizzy cube baby spins through per second.
+pub const DIZZY_SPIN_RATE: f32 = 24.0;
+/// The number of seconds that the cursor must rest on the cube baby before it counts as petting.
+pub const PET_DWELL_DURATION: f32 = 1.0;
+/// The fastest that the cursor may move over the cube baby while petting it, in logical pixels per second.
+pub const PET_MAX_SPEED: f32 = 64.0;
+/// The widest angle of the cube baby's happy wiggle, in radians.
+pub const PET_WIGGLE_ANGLE: f32 = 0.15;
+/// How quickly the cube baby wiggles while being petted, in radians per second.
+pub const PET_WIGGLE_RATE: f32 = 12.0;
+/// The vertical speed below which a cube baby on the floor comes to rest under gravity, in logical pixels per second.
+pub const GRAVITY_REST_SPEED: f32 = 32.0;
+/// The number of seconds within which consecutive corner hits count towards the cube baby being trapped.
+pub const CORNER_TRAP_WINDOW: f32 = 0.25;
+/// The number of consecutive corner hits after which the cube baby is considered trapped in the corner.
+pub const CORNER_TRAP_HITS: u32 = 8;
+/// The speed that a trapped cube baby is knocked out of its corner with, in pixels per second.
+pub const CORNER_ESCAPE_SPEED: f32 = 128.0;
+/// The amount of extra drag applied whilst escaping a corner, so that the cube baby settles soon after.
+pub const CORNER_ESCAPE_DRAG: f32 = 2.0;
+/// The number of seconds that the extra drag lasts for after escaping a corner.
+pub const CORNER_ESCAPE_DURATION: f32 = 1.0;
+/// The number of seconds that must pass between escapes from a corner.
+pub const CORNER_ESCAPE_DELAY: f32 = 10.0;
+/// The number of seconds after the user pushes the cube baby during which it will not wander.
+pub const WANDER_PUSH_GRACE: f32 = 2.0;
+/// The distance from an edge within which a fleeing cube baby turns to dart along it, in logical pixels.
+pub const FLEE_WALL_DISTANCE: f32 = 16.0;
+/// The largest horizontal speed randomly added to each jump, in logical pixels per second.
+pub const JUMP_SPREAD: f32 = 64.0;
 /// The amount of drag applied whilst sliding.
 pub const SLIDE_DRAG: f32 = 0.25;
 /// The distance required before updating the cube baby's sprite.
 pub const SLIDE_SPIN_DISTANCE: f32 = 10.0;
+/// The most atlas frames that a cube baby's sprite may advance by in a single update.
+pub const MAX_SPIN_FRAMES: f32 = 2.0;
+/// The slowest wall impact that squashes the cube baby, in pixels per second.
+pub const SQUASH_MINIMUM_SPEED: f32 = 256.0;
+/// The wall impact speed at which the cube baby is squashed the most, in pixels per second.
+pub const SQUASH_FULL_SPEED: f32 = 2048.0;
+/// The most that the cube baby is squashed by a wall impact, as a fraction of its size.
+pub const SQUASH_AMOUNT: f32 = 0.2;
+/// The number of seconds that the cube baby takes to spring back after being squashed.
+pub const SQUASH_DURATION: f32 = 0.15;
+/// The opacity of the cube baby while click-through mode is enabled.
+pub const CLICK_THROUGH_OPACITY: f32 = 0.5;
+/// The amount of time in seconds that escape must be held for to exit the application.
+pub const EXIT_HOLD_DURATION: f32 = 2.0;
+/// The color that the cube baby is tinted towards while escape is held.
+pub const EXIT_HOLD_COLOR: Color = Color::srgb(1.0, 0.2, 0.2);
+/// The color that the cube baby is dimmed towards right after being pushed.
+pub const COOLDOWN_TINT_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+/// The exponent of the curve that the cooldown tint fades along, where larger values brighten sooner.
+pub const COOLDOWN_TINT_EXPONENT: f32 = 2.0;
+/// The color that the cube baby is tinted towards by gloomy weather.
+pub const GLOOM_TINT_COLOR: Color = Color::srgb(0.55, 0.65, 1.0);
+/// The maximum speed that the cube baby travels at while patrolling.
+pub const PATROL_SPEED: f32 = 48.0;
+/// The maximum acceleration that the cube baby steers with while patrolling.
+pub const PATROL_ACCELERATION: f32 = 96.0;
+/// The distance from a waypoint at which the cube baby begins slowing down.
+pub const PATROL_SLOWING_RADIUS: f32 = 32.0;
+/// The distance from a waypoint at which the cube baby is considered to have arrived.
+pub const PATROL_ARRIVAL_RADIUS: f32 = 2.0;
+/// The distance ahead along the edge that the cube baby steers towards while patrolling an edge.
+pub const EDGE_PATROL_LOOKAHEAD: f32 = 32.0;
+/// How much farther from the cursor another edge must be before an edge patrol switches to it, in logical pixels.
+pub const EDGE_PATROL_HYSTERESIS: f32 = 128.0;
+/// The number of seconds between each choice of the edge farthest from the cursor.
+pub const EDGE_PATROL_EVALUATION_DELAY: f32 = 3.0;
+/// The fastest that the cube baby may move while still counting as idle, before it patrols an edge.
+pub const EDGE_PATROL_IDLE_SPEED: f32 = 4.0;
+/// The fraction of the primary cube baby's velocity that an extra cube baby inherits as it spawns.
+pub const SPAWN_INHERITED_VELOCITY: f32 = 0.5;
+/// The speed that an extra cube baby is pushed away from the primary cube baby with as it spawns, in logical pixels per
+/// second.
+pub const SPAWN_SEPARATION_SPEED: f32 = 128.0;
+/// The number of seconds that an extra cube baby takes to puff away as it despawns.
+pub const DESPAWN_PUFF_DURATION: f32 = 0.25;
+/// How much larger an extra cube baby grows as it puffs away, as a fraction of its size.
+pub const DESPAWN_PUFF_GROWTH: f32 = 0.5;
+/// The number of seconds that a sleeping cube baby stays awake for after being pushed, before it flops back down.
+pub const TIRED_WAKE_DURATION: f64 = 10.0 * 60.0;
+/// The amount of time in seconds that patrolling is suspended for after the cube baby is pushed.
+pub const PATROL_SUSPEND_DELAY: f32 = 60.0;
+/// The amount of time in seconds that the cube baby hides in a corner for when evading a screen capture.
+pub const EVASION_DURATION: f32 = 10.0;
+/// The maximum speed that the cube baby travels at while evading.
+pub const EVASION_SPEED: f32 = 480.0;
+/// The maximum acceleration that the cube baby steers with while evading.
+pub const EVASION_ACCELERATION: f32 = 1920.0;
+/// The distance from a corner at which the cube baby begins slowing down while evading.
+pub const EVASION_SLOWING_RADIUS: f32 = 48.0;
+/// The distance from a corner at which the cube baby freezes in place while evading.
+pub const EVASION_ARRIVAL_RADIUS: f32 = 2.0;
+/// The maximum acceleration that the cube baby steers with while following the cursor.
+pub const FOLLOW_ACCELERATION: f32 = 512.0;
+/// The distance beyond the follow distance at which the cube baby begins slowing down while following the cursor.
+pub const FOLLOW_SLOWING_RADIUS: f32 = 64.0;
+/// The amount of time in seconds between polls of the foreground window's bounds.
+pub const FOREGROUND_WINDOW_POLL_DELAY: f32 = 0.1;
+/// How close the cursor must come to the cube baby's window before it glances nervously, in logical pixels.
+pub const GLANCE_DISTANCE: f32 = 150.0;
+/// How far the cube baby leans away from the cursor as it comes within the glance distance, in logical pixels.
+pub const GLANCE_LEAN_MIN: f32 = 1.0;
+/// How far the cube baby leans away from the cursor just before it touches the window, in logical pixels.
+pub const GLANCE_LEAN_MAX: f32 = 2.0;
+/// How quickly the cube baby's lean eases towards its target, as a fraction per second.
+pub const GLANCE_RATE: f32 = 8.0;
+/// The acceleration that nudges the cube baby out from under a resting cursor.
+pub const PERSONAL_SPACE_ACCELERATION: f32 = 1024.0;
+/// The amount of time in seconds that following is suspended for after the cube baby is pushed.
+pub const FOLLOW_SUSPEND_DELAY: f32 = 1.5;
+/// The amount of time in seconds that corner snapping is suspended for after the cube baby is pushed.
+pub const CORNER_SNAP_SUSPEND_DELAY: f32 = 1.0;
+/// The stiffness of the spring that pulls the cube baby around its orbit of the cursor.
+pub const ORBIT_STIFFNESS: f32 = 8.0;
+/// The damping of the spring that pulls the cube baby around its orbit of the cursor.
+pub const ORBIT_DAMPING: f32 = 4.0;
+/// The smallest cursor movement that keeps the cube baby orbiting, in physical pixels.
+pub const ORBIT_MOVE_DISTANCE: f32 = 1.0;
+/// The amount of time in seconds that the cursor may rest for before the cube baby's orbit begins to decay.
+pub const ORBIT_IDLE_DELAY: f32 = 2.0;
+/// The amount of time in seconds that the cube baby's orbit takes to fully decay.
+pub const ORBIT_DECAY_DURATION: f32 = 1.0;
+/// The amount of time in seconds between heatmap samples.
+pub const HEATMAP_SAMPLE_DELAY: f64 = 1.0;
+/// The amount of time in seconds between heatmap saves.
+pub const HEATMAP_SAVE_DELAY: f64 = 60.0;
+/// The amount of time in seconds between saves of the cube baby's state.
+pub const STATE_SAVE_DELAY: f64 = 30.0;
+/// The number of positions remembered while the cube baby is held.
+pub const THROW_SAMPLES: usize = 16;
+/// The amount of time in seconds before release over which the cube baby's throw velocity is averaged.
+pub const THROW_SAMPLE_WINDOW: f64 = 0.1;
+/// The multiplier applied to the cube baby's velocity when it is thrown.
+pub const THROW_SCALE: f32 = 1.0;
+/// The maximum speed that the cube baby may be thrown at.
+pub const THROW_MAX_SPEED: f32 = 2048.0 * SPRITE_SCALE;
+/// The stick distance below which gamepad stick input is ignored, to absorb stick drift.
+pub const GAMEPAD_DEADZONE: f32 = 0.2;
+/// The rescaled stick distance that a gamepad stick must be flicked past to push the cube baby.
+pub const GAMEPAD_FLICK_THRESHOLD: f32 = 0.75;
+/// The strength that the cube baby is pushed at by a gamepad stick flicked all the way.
+pub const GAMEPAD_FLICK_STRENGTH: f32 = 512.0 * SPRITE_SCALE;
+/// The number of seconds that the cube baby spins faster for after being flicked by the cursor.
+pub const FLICK_SPIN_DURATION: f32 = 1.5;
+/// How many times faster the cube baby spins after being flicked by the cursor.
+pub const FLICK_SPIN_MULTIPLIER: f32 = 3.0;
+/// The amount of time in seconds between checks for whether the window has moved onto another display.
+pub const DISPLAY_CHECK_DELAY: f64 = 1.0;
+/// The amount of time in seconds between checks for whether the settings file has been changed.
+pub const SETTINGS_CHECK_DELAY: f64 = 2.0;
+/// The amount of time in seconds between possible gamepad rumbles.
+pub const RUMBLE_DELAY: f64 = 0.15;
+/// The amount of time in seconds between broadcasts to other running instances.
+pub const PEER_BROADCAST_DELAY: f64 = 0.2;
+/// The amount of time in seconds after which a silent peer is forgotten.
+pub const PEER_TIMEOUT: f64 = 1.0;
+/// The amount of time in seconds that frame times must stay high before the quality tier is lowered.
+pub const QUALITY_DEGRADE_DWELL: f64 = 5.0;
+/// The amount of time in seconds that frame times must stay low before the quality tier is raised.
+pub const QUALITY_RESTORE_DWELL: f64 = 15.0;
 
 /// Returns a new settings object for the primary window of this application.
 #[inline]
-pub fn window_settings() -> Window {
+pub fn window_settings(settings: &Settings) -> Window {
+    let window_size = settings.window.clamped_size();
+
     Window {
         present_mode: PresentMode::AutoNoVsync,
-        resolution: WindowResolution::new(WINDOW_SIZE, WINDOW_SIZE),
-        title: env!("CARGO_BIN_NAME").to_string(),
+        resolution: WindowResolution::new(window_size, window_size),
+        title: branding::window_title(settings.window.title.as_deref()),
+        name: Some(branding::APPLICATION_ID.to_string()),
         composite_alpha_mode: if cfg!(target_os = "linux") {
             CompositeAlphaMode::PreMultiplied
         } else if cfg!(target_os = "macos") {
@@ -68,15 +322,15 @@ pub fn window_settings() -> Window {
             CompositeAlphaMode::Auto
         },
         resize_constraints: WindowResizeConstraints {
-            min_width: WINDOW_SIZE,
-            min_height: WINDOW_SIZE,
-            max_width: WINDOW_SIZE,
-            max_height: WINDOW_SIZE,
+            min_width: window_size,
+            min_height: window_size,
+            max_width: window_size,
+            max_height: window_size,
         },
         resizable: false,
         enabled_buttons: EnabledButtons { minimize: false, maximize: false, close: false },
         decorations: false,
-        transparent: true,
+        transparent: settings.window.background.is_transparent(),
         focused: true,
         window_level: WindowLevel::AlwaysOnTop,
         visible: false,
@@ -98,29 +352,129 @@ pub fn main() -> ExitCode {
         Level::WARN
     };
 
+    // Safe mode skips every optional subsystem, so that problems with any of them may be worked around.
+    let mut safe_mode = SafeMode::from_args();
+
+    // Install the shutdown handlers as early as possible, so that a shutdown during startup still exits cleanly.
+    if safe_mode.allows("shutdown signals") {
+        match ShutdownSignal::install() {
+            Ok(shutdown_signal) => {
+                application.insert_resource(shutdown_signal);
+                application.add_systems(Update, {
+                    // Handle exiting, and therefore saving, as soon as the operating system asks.
+                    update_shutdown_signal
+                });
+            }
+            Err(error) => {
+                application.add_systems(Startup, move || warn!("unable to handle shutdown signals: {error}"));
+            }
+        }
+    }
+
+    // Load the user's settings before anything else, since they may affect how the application is initialized.
+    let mut settings = if safe_mode.allows("settings") {
+        match Settings::load() {
+            Ok(settings) => settings,
+            Err(error) => {
+                application.add_systems(Startup, move || warn!("using default settings: {error}"));
+
+                Settings::default()
+            }
+        }
+    } else {
+        Settings::default()
+    };
+
+    // Probe for transparency support now, since the window cannot be made transparent after it is created.
+    if safe_mode.allows("platform probes") {
+        settings.window.background = settings.window.background.resolve();
+        settings.window.remote_friendly |= platform::is_remote_session();
+    }
+
     // Initialize required components on startup.
     application.add_plugins(
         DefaultPlugins
             .set(WindowPlugin {
-                primary_window: Some(self::window_settings()),
-                exit_condition: ExitCondition::OnPrimaryClosed,
+                primary_window: Some(self::window_settings(&settings)),
+                // This is handled by `post_update_exit_on_primary_closed` so that the window may be recreated.
+                exit_condition: ExitCondition::DontExit,
                 close_when_requested: true,
             })
             .set(LogPlugin { level: log_level, ..LogPlugin::default() }),
     );
+
+    self::build_application(&mut application, settings, safe_mode);
+
+    // Return an exit code that is representative of the execution's result.
+    match application.run() {
+        AppExit::Success => ExitCode::SUCCESS,
+        AppExit::Error(non_zero) => ExitCode::from(non_zero.get()),
+    }
+}
+
+/// Registers every resource, event, and system of the application, skipping each optional subsystem that safe mode
+/// does not allow.
+///
+/// The default plugins, or an equivalent set of plugins, must have already been added.
+pub fn build_application(application: &mut App, settings: Settings, mut safe_mode: SafeMode) {
     application.insert_resource(WinitSettings {
         focused_mode: UpdateMode::Continuous,
         unfocused_mode: UpdateMode::Continuous,
     });
+    application.insert_resource(settings.clone());
+
+    // Resolve key bindings, keeping the defaults for any that are invalid.
+    let (keybindings, warnings) = Keybindings::load(&settings.keybindings);
+
+    for warning in warnings {
+        application.add_systems(Startup, move || warn!("{warning}"));
+    }
+
+    application.insert_resource(keybindings);
     application.add_systems(Startup, self::startup_initialize);
+    application.add_event::<Pushed>();
+    application.add_event::<Knock>();
+    application.add_event::<WallBounce>();
+    application.add_event::<RecreateWindow>();
 
     // Handle display property loading.
     application.init_state::<LoadingState<DisplayLoadingMarker>>();
-    application.init_resource::<DisplayProperties>();
+    application.insert_resource(DisplayProperties {
+        margins: settings.bounds.margin.into(),
+        use_work_area: !settings.bounds.full_display,
+        window_size: settings.window.clamped_size(),
+        ..default()
+    });
     application.add_systems(Update, {
         // Attempt to update the display properties until fully loaded.
         self::update_display_loading.run_if(in_state(LoadingState::<DisplayLoadingMarker>::loading()))
     });
+    application.add_systems(Update, {
+        // Reload the display properties whenever the window ends up on another display.
+        self::update_display_tracking.run_if(in_state(LoadingState::<DisplayLoadingMarker>::finished()))
+    });
+
+    // Handle recreating the primary window.
+    if safe_mode.allows("settings") {
+        application.add_systems(Update, {
+            // Handle recreating the primary window whenever the window size setting is changed.
+            update_settings_watching.run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+        });
+    }
+    application.add_systems(Update, {
+        // Handle swapping the primary window, waiting for any in-progress swap to finish first.
+        update_window_recreation
+            .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+            .run_if(not(resource_exists::<WindowSnapshot>))
+    });
+    application.add_systems(OnEnter(LoadingState::<DisplayLoadingMarker>::finished()), {
+        // Restore the cube baby's state once the new window's display has loaded.
+        on_window_recreated.run_if(resource_exists::<WindowSnapshot>)
+    });
+    application.add_systems(PostUpdate, {
+        // Exit once the primary window is closed, unless it is being recreated.
+        post_update_exit_on_primary_closed.run_if(not(resource_exists::<WindowSnapshot>))
+    });
 
     // Handle texture asset loading.
     application.init_state::<LoadingState<TextureLoadingMarker>>();
@@ -143,43 +497,615 @@ pub fn main() -> ExitCode {
         self::on_application_load_finished
     });
 
+    // Handle setting the window's icon.
+    if safe_mode.allows("window icon") {
+        application.add_systems(Update, {
+            // Apply the icon to the primary window, including after it has been recreated.
+            update_window_icon.run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+        });
+    }
+
+    // Handle the first-run attention sequence and hint.
+    if safe_mode.allows("onboarding") {
+        application.add_systems(OnEnter(LoadingState::<ApplicationLoadingMarker>::finished()), {
+            // Begin onboarding once the cube baby has been spawned.
+            on_application_load_finished_onboarding.after(on_application_load_finished)
+        });
+        application.add_systems(Update, {
+            // Handle performing the attention sequence and dismissing the hint.
+            (update_onboarding_dismissal, update_onboarding, update_onboarding_persistence)
+                .chain()
+                .before(update_window_movement)
+                .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+        });
+    }
+
     // Handle rendering and window motion.
-    application.insert_resource(ClearColor(Color::NONE));
+    application.insert_resource(ClearColor(settings.window.background.clear_color()));
+    // Global cursor polling is not available everywhere, so check for it once for everything that relies on it.
+    let cursor_available = safe_mode.allows("global cursor") && platform::global_cursor_position(1.0).is_some();
+
+    if !cursor_available && !safe_mode.is_enabled() {
+        application.add_systems(Startup, || {
+            info!(
+                "global cursor polling is unavailable on this platform, so pushes rely on cursor events and \
+                 summoning, repelling, personal space, and every behavior but passive are disabled"
+            );
+        });
+    }
+    // Fall back to cursor events if the platform does not allow the cursor to be polled.
+    if cursor_available && settings.push.global_cursor {
+        application.init_resource::<GlobalCursor>();
+        application.add_systems(FixedUpdate, {
+            // Handle polling the cursor's position on the desktop.
+            fixed_update_global_cursor
+                .before(fixed_update_mouse_collision)
+                .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+        });
+    }
+    application.insert_resource(settings.push.model);
+    application.add_systems(FixedUpdate, {
+        // Handle counting down every push delay before any pushes are applied.
+        fixed_update_push_delays
+            .before(fixed_update_mouse_collision)
+            .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+            .run_if(is_not_stalled)
+    });
+    application.add_systems(Update, {
+        // Handle making the cube baby dizzy when the cursor is shaken over it, and its recovery.
+        (update_dizzy_detection, update_dizziness)
+            .chain()
+            .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+            .run_if(is_unpaused)
+    });
+    application.add_systems(FixedUpdate, {
+        // Handle cursor-to-window collision, which yields while the cube baby is dizzy.
+        fixed_update_mouse_collision
+            .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+            .run_if(is_unpaused)
+    });
+    application.add_systems(FixedUpdate, {
+        // Handle touch-to-window collision.
+        fixed_update_touch_collision
+            .after(fixed_update_mouse_collision)
+            .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+            .run_if(is_unpaused)
+    });
+    application.add_systems(Update, {
+        // Handle exiting when the cube baby is double-clicked.
+        update_double_click_exit
+            .before(update_grabbing)
+            .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+            .run_if(|settings: Res<Settings>| settings.window.double_click_exit > 0.0)
+    });
+    application.insert_resource(ClickThrough(settings.window.click_through));
+    application.add_systems(Update, {
+        // Handle toggling and applying click-through mode.
+        (update_click_through_toggle, update_click_through.run_if(resource_changed::<ClickThrough>))
+            .chain()
+            .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+    });
+    application.insert_resource(Gravity(settings.gravity.enabled));
+    application.add_systems(Update, {
+        // Handle toggling gravity.
+        update_gravity_toggle.run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+    });
     application.add_systems(FixedUpdate, {
-        // Handle cursor-to-window collision.
-        fixed_update_mouse_collision.run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+        // Handle pulling the cube babies down while gravity is enabled.
+        fixed_update_gravity
+            .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+            .run_if(in_state(LoadingState::<DisplayLoadingMarker>::finished()))
+            .run_if(is_unpaused)
+            .run_if(|gravity: Res<Gravity>| gravity.0)
+    });
+    application.add_systems(Update, {
+        // Handle jumping with the knock key while gravity is enabled.
+        update_jumping
+            .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+            .run_if(is_unpaused)
+            .run_if(|gravity: Res<Gravity>| gravity.0)
+    });
+    application.add_systems(Update, {
+        // Handle dimming the cube babies while their push delays count down.
+        update_cooldown_tint.run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+    });
+    application.add_systems(PostUpdate, {
+        // Handle combining every tint into the sprite's color, after all of them have been updated.
+        post_update_sprite_tint.run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+    });
+    application.add_systems(PostUpdate, {
+        // Handle combining every offset into the sprite's translation, after all of them have been updated.
+        post_update_sprite_offset.run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+    });
+    application.add_systems(PreUpdate, {
+        // Handle clearing out anything that built up while paused, before anything else sees it.
+        pre_update_resume_hygiene.run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
     });
+    application.init_resource::<Rng>();
+    if settings.wander.enabled && safe_mode.allows("wandering") {
+        application.add_systems(Update, {
+            // Handle the cube baby occasionally pushing itself.
+            update_wandering.run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished())).run_if(is_unpaused)
+        });
+    }
+    application.init_resource::<Mood>();
+    application.init_resource::<MoodModifiers>();
+    application.add_systems(Update, {
+        // Handle combining every mood modifier, then tinting the cube babies to match their mood.
+        (update_mood.run_if(resource_changed::<MoodModifiers>), update_mood_tint)
+            .chain()
+            .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+    });
+    if settings.tiredness.enabled && safe_mode.allows("tiredness") {
+        application.add_systems(Update, {
+            // Handle tiring the cube babies out as the session goes on.
+            update_session_tiredness
+                .before(update_mood)
+                .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+                .run_if(in_state(LoadingState::<DisplayLoadingMarker>::finished()))
+        });
+        application.add_systems(FixedUpdate, {
+            // Handle steering sleeping cube babies to where they flopped down.
+            fixed_update_flopping
+                .before(fixed_update_window_movement)
+                .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+                .run_if(in_state(LoadingState::<DisplayLoadingMarker>::finished()))
+                .run_if(is_unpaused)
+        });
+    }
+    // Handle nudging the cube babies' mood with the weather at the configured location.
+    #[cfg(feature = "weather")]
+    if settings.weather.enabled && safe_mode.allows("weather") {
+        let receiver = weather::WeatherReceiver::spawn(settings.weather.latitude, settings.weather.longitude);
+
+        application.insert_resource(receiver);
+        application.add_systems(Update, {
+            // Handle applying each weather report as it arrives.
+            update_weather.before(update_mood).run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+        });
+    }
+    #[cfg(not(feature = "weather"))]
+    if settings.weather.enabled {
+        application.add_systems(Startup, || warn!("weather moods require the `weather` feature"));
+    }
+    if settings.wind.enabled && safe_mode.allows("wind") {
+        application.init_resource::<Wind>();
+        application.add_systems(FixedUpdate, {
+            // Handle shifting the wind and pushing the cube babies with it.
+            fixed_update_wind
+                .before(fixed_update_window_movement)
+                .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+                .run_if(is_unpaused)
+        });
+    }
+    // The foreground window can only be found on Windows, so this is compiled out everywhere else.
+    #[cfg(target_os = "windows")]
+    if settings.bounds.foreground_window && safe_mode.allows("foreground window") {
+        application.init_resource::<ForegroundWindow>();
+        application.add_systems(FixedUpdate, {
+            // Handle tracking the foreground window, so that the cube babies may land on it.
+            fixed_update_foreground_window
+                .before(fixed_update_window_movement)
+                .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+        });
+    }
+    #[cfg(not(target_os = "windows"))]
+    if settings.bounds.foreground_window {
+        application.add_systems(Startup, || info!("foreground window collisions are unavailable on this platform"));
+    }
+    if settings.corner_snap.enabled && safe_mode.allows("corner snapping") {
+        application.add_systems(FixedUpdate, {
+            // Handle pulling slow cube babies into nearby corners.
+            fixed_update_corner_snapping
+                .before(fixed_update_window_movement)
+                .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+                .run_if(is_unpaused)
+        });
+    }
+    application.init_resource::<ExitHold>();
+    application.add_systems(Update, {
+        // Handle exiting when escape is held.
+        update_exit_hold.run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+    });
+    application.add_systems(Update, {
+        // Handle grabbing and dragging the cube baby.
+        update_grabbing
+            .before(update_window_movement)
+            .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+            .run_if(in_state(LoadingState::<DisplayLoadingMarker>::finished()))
+            .run_if(is_unpaused)
+    });
+    application.add_systems(Update, {
+        // Handle resizing the cube baby by scrolling over it.
+        update_scroll_resizing
+            .before(update_window_movement)
+            .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+            .run_if(in_state(LoadingState::<DisplayLoadingMarker>::finished()))
+    });
+    application.insert_resource(settings.behavior.mode);
+    application.add_systems(Update, {
+        // Handle cycling through the behaviors.
+        update_behavior_toggle.run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+    });
+    // Every feature that watches the cursor shares its polling, so they are all disabled along with it.
+    if cursor_available && settings.push.global_cursor {
+        if settings.summon.enabled && safe_mode.allows("summoning") {
+            // Global mouse polling is not available everywhere, so check for it before committing to it.
+            if platform::global_mouse_button_pressed(settings.summon.button.into()).is_some() {
+                application.add_systems(Update, {
+                    // Handle summoning the cube baby to the cursor.
+                    update_summoning
+                        .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+                        .run_if(in_state(LoadingState::<DisplayLoadingMarker>::finished()))
+                        .run_if(is_unpaused)
+                });
+            } else {
+                application.add_systems(Startup, || warn!("summoning is unavailable on this platform"));
+            }
+        }
+        if settings.repel.enabled && safe_mode.allows("repelling") {
+            application.add_systems(FixedUpdate, {
+                // Handle repelling the cube baby from a nearby cursor.
+                fixed_update_repelling
+                    .after(fixed_update_global_cursor)
+                    .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+                    .run_if(in_state(LoadingState::<DisplayLoadingMarker>::finished()))
+                    .run_if(is_unpaused)
+            });
+        }
+        if settings.personal_space.enabled && safe_mode.allows("personal space") {
+            application.add_systems(FixedUpdate, {
+                // Handle nudging the cube baby out from under a resting cursor.
+                fixed_update_personal_space
+                    .after(fixed_update_global_cursor)
+                    .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+                    .run_if(in_state(LoadingState::<DisplayLoadingMarker>::finished()))
+                    .run_if(is_unpaused)
+            });
+        }
+        if settings.glance.enabled && safe_mode.allows("glancing") {
+            application.add_systems(Update, {
+                // Handle leaning the cube babies away from an approaching cursor.
+                update_glancing
+                    .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+                    .run_if(in_state(LoadingState::<DisplayLoadingMarker>::finished()))
+                    .run_if(is_unpaused)
+            });
+        }
+        if settings.edge_patrol.enabled && safe_mode.allows("edge patrolling") {
+            application.add_systems(FixedUpdate, {
+                // Handle patrolling the edge farthest from the cursor while idle, which only makes sense without
+                // gravity and while no other behavior is steering.
+                fixed_update_edge_patrolling
+                    .after(fixed_update_global_cursor)
+                    .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+                    .run_if(in_state(LoadingState::<DisplayLoadingMarker>::finished()))
+                    .run_if(is_unpaused)
+                    .run_if(|gravity: Res<Gravity>| !gravity.0)
+                    .run_if(|mode: Res<BehaviorMode>| *mode == BehaviorMode::Passive)
+            });
+        }
+        if safe_mode.allows("behaviors") {
+            application.add_systems(FixedUpdate, {
+                // Handle following, orbiting, and fleeing from the cursor, depending on the current behavior.
+                (
+                    fixed_update_following.run_if(|mode: Res<BehaviorMode>| *mode == BehaviorMode::Following),
+                    fixed_update_orbiting.run_if(|mode: Res<BehaviorMode>| *mode == BehaviorMode::Orbiting),
+                    fixed_update_fleeing.run_if(|mode: Res<BehaviorMode>| *mode == BehaviorMode::Skittish),
+                )
+                    .after(fixed_update_global_cursor)
+                    .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+                    .run_if(in_state(LoadingState::<DisplayLoadingMarker>::finished()))
+                    .run_if(is_unpaused)
+            });
+        }
+    } else if cursor_available
+        && (settings.summon.enabled
+            || settings.repel.enabled
+            || settings.personal_space.enabled
+            || settings.glance.enabled
+            || settings.edge_patrol.enabled
+            || settings.behavior.mode != BehaviorMode::Passive)
+    {
+        application.add_systems(Startup, || {
+            warn!(
+                "summoning, repelling, personal space, glancing, edge patrolling, and every behavior but passive \
+                 require `push.global_cursor`"
+            );
+        });
+    }
     application.add_systems(Update, {
         // Handle space-bar knocking.
-        update_spacebar_knocking.run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+        update_spacebar_knocking
+            .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+            .run_if(is_unpaused)
+    });
+
+    // Handle knocking through a global hotkey, which works even while the window is unfocused.
+    #[cfg(feature = "global_hotkey")]
+    if let Some(chord) = settings.knock.global_hotkey.as_ref().filter(|_| safe_mode.allows("global hotkey")) {
+        match hotkey::GlobalHotkey::register(chord) {
+            Ok(global_hotkey) => {
+                application.insert_non_send_resource(global_hotkey);
+                application.add_systems(Update, {
+                    // Forward hotkey presses to the knocking system.
+                    update_global_hotkey.before(update_spacebar_knocking)
+                });
+                application.add_systems(PostUpdate, {
+                    // Release the hotkey before exiting.
+                    post_update_global_hotkey_release.after(post_update_exit_on_primary_closed)
+                });
+            }
+            Err(error) => {
+                application.add_systems(Startup, move || warn!("failed to register the global hotkey: {error}"));
+            }
+        }
+    }
+
+    // Handle the right-click context menu.
+    #[cfg(feature = "context_menu")]
+    if safe_mode.allows("context menu") {
+        if !menu::is_supported() {
+            application.add_systems(Startup, || info!("context menus are unavailable on this platform"));
+        } else {
+            match menu::ContextMenu::new() {
+                Ok(context_menu) => {
+                    application.insert_non_send_resource(context_menu);
+                    application.add_systems(Update, {
+                        // Handle opening the menu.
+                        update_context_menu
+                            .before(update_menu_actions)
+                            .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+                    });
+                }
+                Err(error) => {
+                    application.add_systems(Startup, move || warn!("failed to create the context menu: {error}"));
+                }
+            }
+        }
+    }
+
+    application.add_event::<MenuAction>();
+    application.init_resource::<SimulationSpeed>();
+    application.add_systems(Update, {
+        // Handle bound action keys and performing actions.
+        (update_action_keys, update_menu_actions.before(update_spacebar_knocking))
+            .chain()
+            .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+    });
+    application.add_systems(Update, {
+        // Handle switching between slow motion, normal speed, and fast-forward.
+        update_simulation_speed.run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+    });
+    application.add_systems(Update, {
+        // Handle arrow-key nudging.
+        update_arrow_nudging.run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished())).run_if(is_unpaused)
+    });
+    application.add_systems(Update, {
+        // Handle gamepad pushing.
+        update_gamepad_pushing
+            .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+            .run_if(is_unpaused)
+    });
+    if safe_mode.allows("patrolling") {
+        application.add_systems(FixedUpdate, {
+            // Handle steering along the patrol route.
+            fixed_update_patrolling.run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+        });
+    }
+    if safe_mode.allows("evasion") {
+        application.add_systems(Startup, self::startup_evasion_triggers);
+        application.add_systems(Update, {
+            // Handle evading screen captures.
+            update_evasion_triggers.run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+        });
+        application.add_systems(FixedUpdate, {
+            // Handle hiding in a corner while evading.
+            fixed_update_evasion.run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+        });
+    }
+    if safe_mode.allows("extra babies") {
+        application.init_resource::<ExtraBabies>();
+        application.add_systems(Update, {
+            // Handle spawning and despawning extra cube babies, and puffing away the ones being despawned.
+            (update_extra_babies, update_despawning)
+                .chain()
+                .before(update_window_movement)
+                .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+                .run_if(in_state(LoadingState::<DisplayLoadingMarker>::finished()))
+        });
+    }
+    application.insert_resource(if settings.window.remote_friendly {
+        PresentationThrottle::new(settings.window.remote_update_rate)
+    } else {
+        PresentationThrottle::default()
+    });
+    application.add_systems(Update, {
+        // Handle limiting how often the window is presented.
+        update_presentation_throttle.before(update_window_movement).before(update_sprite_rotation)
+    });
+    application.add_systems(FixedUpdate, {
+        // Handle moving the cube babies, waiting for the display to reload if one was dropped onto another.
+        fixed_update_window_movement
+            .after(fixed_update_touch_collision)
+            .after(fixed_update_gravity)
+            .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+            .run_if(in_state(LoadingState::<DisplayLoadingMarker>::finished()))
+            .run_if(is_unpaused)
+            .run_if(is_not_stalled)
+    });
+    application.add_systems(Update, {
+        // Handle presenting the windows between fixed timesteps.
+        update_window_movement
+            .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+            .run_if(in_state(LoadingState::<DisplayLoadingMarker>::finished()))
+            .run_if(is_unpaused)
     });
     application.add_systems(Update, {
-        // Handle moving the window.
-        update_window_movement.run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+        // Handle petting the cube baby with a resting cursor.
+        update_petting.run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished())).run_if(is_unpaused)
     });
     application.add_systems(Update, {
         // Handle rotating the cube baby.
-        update_sprite_rotation.run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+        update_sprite_rotation
+            .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+            .run_if(is_unpaused)
+    });
+    application.add_systems(Update, {
+        // Handle squashing the cube babies against walls that they hit hard, and springing them back.
+        (update_squash_impacts, update_squash)
+            .chain()
+            .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+            .run_if(is_unpaused)
     });
+    if safe_mode.allows("rumble") {
+        application.add_systems(Update, {
+            // Handle rumbling gamepads when the cube baby bounces off of a wall.
+            update_bounce_rumble
+                .after(update_window_movement)
+                .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+                .run_if(|settings: Res<Settings>| settings.rumble.enabled)
+        });
+    }
 
-    // Return an exit code that is representative of the execution's result.
-    match application.run() {
-        AppExit::Success => ExitCode::SUCCESS,
-        AppExit::Error(non_zero) => ExitCode::from(non_zero.get()),
+    // Handle collisions with the cube babies of other running instances.
+    if safe_mode.allows("peers") {
+        application.init_resource::<PeerBabies>();
+        application.add_systems(Startup, {
+            // Bind to a peer port if enabled.
+            startup_bind_peer_socket.run_if(|settings: Res<Settings>| settings.peers.enabled)
+        });
+        application.add_systems(Update, {
+            // Handle advertising and discovering peers, then colliding with them.
+            (update_peer_broadcast, update_peer_discovery, update_peer_collision)
+                .chain()
+                .before(update_window_movement)
+                .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+                .run_if(resource_exists::<PeerSocket>)
+        });
+    }
+
+    // Handle rescuing the cube baby if it gets stuck.
+    if safe_mode.allows("stuck rescue") {
+        application.add_systems(Update, {
+            // Handle detecting when the cube baby is stuck, and pushing it free.
+            update_stuck_rescue
+                .before(update_window_movement)
+                .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+                .run_if(is_unpaused)
+        });
+    }
+
+    // Handle tracking where the cube baby spends its time.
+    if safe_mode.allows("heatmap") {
+        application.add_systems(Startup, {
+            // Load the previously recorded heatmap.
+            startup_load_heatmap.run_if(|settings: Res<Settings>| settings.heatmap.enabled)
+        });
+        application.add_systems(Update, {
+            // Handle sampling the cube baby's position and exporting the heatmap.
+            (update_heatmap_sampling, update_heatmap_export)
+                .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+                .run_if(resource_exists::<Heatmap>)
+        });
+        application.add_systems(PostUpdate, {
+            // Periodically save the heatmap, and before exiting.
+            post_update_heatmap_persistence.after(post_update_exit_on_primary_closed).run_if(resource_exists::<Heatmap>)
+        });
+    }
+
+    // Handle remembering where the cube baby was across restarts.
+    if safe_mode.allows("state persistence") {
+        application.add_systems(Startup, self::startup_load_saved_state);
+        application.add_systems(PostUpdate, {
+            // Periodically save the cube baby's state, and before exiting.
+            post_update_state_persistence
+                .after(post_update_exit_on_primary_closed)
+                .run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+        });
+    }
+
+    // Handle automatic quality degradation under load.
+    if safe_mode.allows("quality governor") {
+        application.add_plugins(FrameTimeDiagnosticsPlugin);
+        application.insert_resource(QualityGovernor::new(settings.quality.tier));
+        application.add_systems(Update, {
+            // Handle moving between quality tiers.
+            update_quality_governor.run_if(in_state(LoadingState::<ApplicationLoadingMarker>::finished()))
+        });
+    }
+
+    // Handle safe mode, which must be registered last so that every skipped subsystem is known.
+    if safe_mode.is_enabled() {
+        application.add_systems(Startup, startup_log_safe_mode);
+        application.add_systems(Update, update_safe_mode_exit);
+    }
+
+    application.insert_resource(safe_mode);
+}
+
+/// Exits the application once the operating system has asked it to shut down, so that its state is saved in time.
+pub fn update_shutdown_signal(shutdown_signal: Res<ShutdownSignal>, mut exit_events: EventWriter<AppExit>) {
+    if shutdown_signal.is_requested() {
+        exit_events.send(AppExit::Success);
+    }
+}
+
+/// Logs every optional subsystem that was skipped by safe mode.
+pub fn startup_log_safe_mode(safe_mode: Res<SafeMode>) {
+    warn!("running in safe mode, skipped: {}", safe_mode.skipped().join(", "));
+}
+
+/// Exits the application when the quit key is pressed in safe mode.
+pub fn update_safe_mode_exit(
+    key_input: Res<ButtonInput<KeyCode>>,
+    keybindings: Res<Keybindings>,
+    mut exit_events: EventWriter<AppExit>,
+) {
+    if keybindings.just_pressed(Action::Quit, &key_input) {
+        exit_events.send(AppExit::Success);
     }
 }
 
 /// Initializes components on startup.
-pub fn startup_initialize(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands.spawn(Camera2d);
+pub fn startup_initialize(mut commands: Commands, asset_server: Res<AssetServer>, settings: Res<Settings>) {
+    commands.spawn(Camera2d).with_children(|parent| {
+        for border in self::window_borders(&settings, settings.window.clamped_size()) {
+            parent.spawn(border);
+        }
+    });
+    commands.insert_resource(PatrolRoute::new(&settings.patrol.waypoints));
 
     commands.insert_resource(TextureMetadata {
         image_handle: asset_server.load(concat!("embedded://", env!("CARGO_CRATE_NAME"), "/cube_baby.png")),
         layout_handle: Handle::default(),
-        size: UVec2::ZERO,
+        size: TexturePx::ZERO,
+        padding: TexturePx::new(settings.texture.padding[0], settings.texture.padding[1]),
+        offset: TexturePx::new(settings.texture.offset[0], settings.texture.offset[1]),
     });
 }
 
+/// Registers each of the configured sources of screen capture evasion.
+pub fn startup_evasion_triggers(mut commands: Commands, settings: Res<Settings>) {
+    let mut triggers = EvasionTriggers::default();
+
+    if let Some(chord) = settings.evasion.hotkey.clone() {
+        triggers.push(Box::new(HotkeyTrigger(chord)));
+    }
+
+    #[cfg(feature = "screenshot_probe")]
+    if settings.evasion.probe {
+        triggers.push(Box::new(evasion::ProcessTrigger::new()));
+    }
+
+    #[cfg(not(feature = "screenshot_probe"))]
+    if settings.evasion.probe {
+        warn!("screenshot probing requires the `screenshot_probe` feature");
+    }
+
+    commands.insert_resource(triggers);
+}
+
 /// Attempts to load the current display's properties on application load.
 pub fn update_display_loading(
     primary_window: Single<Entity, With<PrimaryWindow>>,
@@ -188,15 +1114,182 @@ pub fn update_display_loading(
     mut display_state: ResMut<NextState<LoadingState<DisplayLoadingMarker>>>,
 ) {
     if let Some(current_monitor) = winit_windows.get_window(*primary_window).and_then(|v| v.current_monitor()) {
-        display_properties.position = IVec2::new(current_monitor.position().x, current_monitor.position().y);
-        display_properties.resolution = UVec2::new(current_monitor.size().width, current_monitor.size().height);
+        display_properties.load_monitor(&current_monitor);
+        display_properties.load_work_area();
 
         display_state.set(LoadingState::finished());
     }
 }
 
+/// Periodically checks whether the window is on a different display than the one that was loaded, and if so reloads it.
+pub fn update_display_tracking(
+    time: Res<Time<Real>>,
+    primary_window: Single<Entity, With<PrimaryWindow>>,
+    winit_windows: NonSend<WinitWindows>,
+    display_properties: Res<DisplayProperties>,
+    mut display_state: ResMut<NextState<LoadingState<DisplayLoadingMarker>>>,
+    mut last_check: Local<Option<f64>>,
+) {
+    if last_check.is_some_and(|v| time.elapsed_secs_f64() - v < DISPLAY_CHECK_DELAY) {
+        return;
+    }
+
+    *last_check = Some(time.elapsed_secs_f64());
+
+    let Some(current_monitor) = winit_windows.get_window(*primary_window).and_then(|v| v.current_monitor()) else {
+        return;
+    };
+    let mut current_properties = *display_properties;
+
+    current_properties.load_monitor(&current_monitor);
+
+    if current_properties != *display_properties {
+        display_state.set(LoadingState::loading());
+    }
+}
+
+/// Periodically checks whether the settings file has been changed, and if so applies its new window size.
+///
+/// Only the window size is applied while running, by recreating the window, since the other settings are only read as
+/// the application starts.
+pub fn update_settings_watching(
+    time: Res<Time<Real>>,
+    mut settings: ResMut<Settings>,
+    mut recreate_events: EventWriter<RecreateWindow>,
+    mut last_check: Local<Option<f64>>,
+    mut last_modified: Local<Option<SystemTime>>,
+) {
+    if last_check.is_some_and(|v| time.elapsed_secs_f64() - v < SETTINGS_CHECK_DELAY) {
+        return;
+    }
+
+    *last_check = Some(time.elapsed_secs_f64());
+
+    let Some(modified) = Settings::path().and_then(|v| v.metadata().ok()).and_then(|v| v.modified().ok()) else {
+        return;
+    };
+
+    // The first check only records the file's state, since the settings were just loaded.
+    if last_modified.replace(modified).is_none_or(|v| v == modified) {
+        return;
+    }
+
+    let new_settings = match Settings::load() {
+        Ok(new_settings) => new_settings,
+        Err(error) => {
+            warn!("ignoring the changed settings: {error}");
+
+            return;
+        }
+    };
+
+    if new_settings.window.clamped_size() == settings.window.clamped_size() {
+        return;
+    }
+
+    info!("resizing the window to {} pixels", new_settings.window.clamped_size());
+
+    settings.window.size = new_settings.window.size;
+
+    recreate_events.send(RecreateWindow(self::window_settings(&settings)));
+}
+
+/// Recreates the primary window when requested, snapshotting the cube baby's state and reloading the display.
+pub fn update_window_recreation(
+    mut commands: Commands,
+    primary_window: Single<Entity, With<PrimaryWindow>>,
+    query: Single<(&Position, &Velocity, &PushDelays, &Distance, &Sprite, Option<&Patrolling>), With<PrimaryBaby>>,
+    mut recreate_events: EventReader<RecreateWindow>,
+    mut display_state: ResMut<NextState<LoadingState<DisplayLoadingMarker>>>,
+) {
+    // Only the latest request matters, since it would immediately replace any earlier ones.
+    let Some(RecreateWindow(window)) = recreate_events.read().last() else { return };
+    let (position, velocity, push_delays, distance, sprite, patrolling) = query.into_inner();
+
+    commands.insert_resource(WindowSnapshot::capture(
+        window.resolution.width(),
+        position,
+        velocity,
+        push_delays,
+        distance,
+        sprite,
+        patrolling,
+    ));
+
+    // Keep the new window hidden at the previous position until its display has been loaded.
+    let mut window = window.clone();
+
+    window.position.set(position.to_physical());
+    window.visible = false;
+
+    // Both commands are applied together, so there is never a moment without a primary window.
+    commands.entity(*primary_window).despawn();
+    commands.spawn((window, PrimaryWindow));
+
+    display_state.set(LoadingState::loading());
+}
+
+/// Restores the cube baby's snapshotted state once the recreated window's display has been loaded.
+pub fn on_window_recreated(
+    mut commands: Commands,
+    primary_window: Single<Entity, With<PrimaryWindow>>,
+    mut windows: Query<&mut Window>,
+    mut display_properties: ResMut<DisplayProperties>,
+    texture_metadata: Res<TextureMetadata>,
+    click_through: Res<ClickThrough>,
+    snapshot: Res<WindowSnapshot>,
+    primary_baby: Single<
+        (Entity, &mut Velocity, &mut PushDelays, &mut Distance, &mut Sprite),
+        (With<PrimaryBaby>, Without<WindowBorder>),
+    >,
+    mut babies: Query<(&mut Position, &mut Transform, Option<&BabyWindow>), With<CubeBaby>>,
+    mut borders: Query<(&mut Sprite, &mut Transform), (With<WindowBorder>, Without<CubeBaby>)>,
+) {
+    let (entity, mut velocity, mut push_delays, mut distance, mut sprite) = primary_baby.into_inner();
+    let Ok((mut position, ..)) = babies.get_mut(entity) else { return };
+
+    let patrolling = snapshot.restore(&mut position, &mut velocity, &mut push_delays, &mut distance, &mut sprite);
+
+    if let Some(patrolling) = patrolling {
+        commands.entity(entity).insert(patrolling);
+    }
+
+    // New windows are always created at the default size, so every window is resized even if the size is unchanged.
+    self::apply_window_size(
+        snapshot.window_size,
+        *primary_window,
+        &mut display_properties,
+        &texture_metadata,
+        &mut windows,
+        &mut babies,
+        &mut borders,
+    );
+
+    let Ok((position, ..)) = babies.get(entity) else { return };
+    let Ok(mut window) = windows.get_mut(*primary_window) else { return };
+
+    // New windows also always accept mouse input.
+    window.cursor_options.hit_test = !click_through.0;
+
+    window.position.set(position.to_physical());
+    window.visible = true;
+
+    commands.remove_resource::<WindowSnapshot>();
+}
+
+/// Exits the application once the primary window has been closed.
+pub fn post_update_exit_on_primary_closed(
+    primary_windows: Query<(), With<PrimaryWindow>>,
+    mut exit_events: EventWriter<AppExit>,
+) {
+    if primary_windows.is_empty() {
+        exit_events.send(AppExit::Success);
+    }
+}
+
 /// Attempts to load the assets related to all required textures on application load.
 pub fn update_texture_loading(
+    mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut image_assets: ResMut<Assets<Image>>,
     mut layout_assets: ResMut<Assets<TextureAtlasLayout>>,
@@ -208,9 +1301,18 @@ pub fn update_texture_loading(
 
         image.sampler = ImageSampler::nearest();
 
-        texture_metadata.size = image.size();
+        texture_metadata.size = TexturePx(image.size());
 
-        let layout = TextureAtlasLayout::from_grid(texture_metadata.frame_size(), ATLAS_FRAMES, 1, None, None);
+        let layout = texture_metadata.layout();
+
+        // Cache which pixels are solid now, so that collisions never need to read the image data.
+        if image.texture_descriptor.format.block_copy_size(None) != Some(4) {
+            warn!("unable to create a collision mask from a texture of format {:?}", image.texture_descriptor.format);
+        } else if let Some(alpha_mask) =
+            AlphaMask::from_rgba(&image.data, image.size(), &layout.textures, COLLISION_ALPHA_THRESHOLD)
+        {
+            commands.insert_resource(alpha_mask);
+        }
 
         texture_metadata.layout_handle = layout_assets.add(layout);
 
@@ -230,121 +1332,3089 @@ pub fn update_application_loading(
 }
 
 /// Finishes initializing the application once all prerequisite loading has finished.
+///
+/// The cube baby is placed where it was last saved if that position is still on a connected display, and at the center
+/// of the current display otherwise.
 pub fn on_application_load_finished(
-    mut window: Single<&mut Window, With<PrimaryWindow>>,
+    primary_window: Single<(Entity, &mut Window), With<PrimaryWindow>>,
+    winit_windows: NonSend<WinitWindows>,
     mut commands: Commands,
     display_properties: Res<DisplayProperties>,
     texture_metadata: Res<TextureMetadata>,
+    patrol_route: Res<PatrolRoute>,
+    settings: Res<Settings>,
+    saved_state: Option<Res<SavedState>>,
 ) {
-    let texture_atlas = TextureAtlas { index: 0, layout: texture_metadata.layout_handle.clone_weak() };
-    let sprite = Sprite::from_atlas_image(texture_metadata.image_handle.clone_weak(), texture_atlas);
-    let transform = Transform::from_scale(texture_metadata.sprite_scale().xyy());
-    let position = Position(display_properties.center_position().as_vec2() - (WINDOW_SIZE / 2.0));
+    let (window_entity, mut window) = primary_window.into_inner();
+    let (mut sprite, transform) = self::baby_sprite(&texture_metadata, &display_properties);
+    let mut position = Position(display_properties.center_position() - (display_properties.window_extent() / 2.0));
+    let mut velocity = Velocity::ZERO;
 
-    commands.spawn((CubeBaby, sprite, transform, position, Velocity::ZERO, PushDelay::ZERO, Distance::ZERO));
+    if let Some(saved_state) = saved_state {
+        let center = saved_state.position + (display_properties.window_extent() / 2.0);
+        let monitors = winit_windows.get_window(window_entity).into_iter().flat_map(|v| v.available_monitors());
+        let mut monitors = monitors.filter(|v| saved_state.monitor.is_none() || v.name() == saved_state.monitor);
 
-    window.position.set(position.round().as_ivec2());
-    window.visible = true;
-}
+        // The displays may have been rearranged or disconnected since the state was saved.
+        if monitors.any(|v| {
+            let mut properties = DisplayProperties::default();
 
-/// Handles knocking the cube baby when the space bar is pressed.
-pub fn update_spacebar_knocking(
-    button_input: Res<ButtonInput<KeyCode>>,
-    mut velocity: Single<&mut Velocity, With<CubeBaby>>,
-) {
-    const MIN_STRENGTH: f32 = PUSH_STRENGTH * PUSH_STRENGTH;
-    const MAX_STRENGTH: f32 = PUSH_STRENGTH * PUSH_STRENGTH * 4.0;
+            properties.load_monitor(&v);
+            properties.contains(center)
+        }) {
+            position = Position(saved_state.position);
+            velocity = Velocity(saved_state.velocity);
 
-    if button_input.get_just_pressed().next().is_some() {
-        let x = (fastrand::f32() * 2.0) - 1.0;
-        let y = (fastrand::f32() * 2.0) - 1.0;
-        let strength = ((fastrand::f32() * MAX_STRENGTH) - MIN_STRENGTH) + MIN_STRENGTH;
-        let movement = velocity.normalize_or_zero() + Vec2::new(x, y).normalize_or_zero();
+            if let Some(texture_atlas) = sprite.texture_atlas.as_mut() {
+                texture_atlas.index = saved_state.atlas_index % ATLAS_FRAMES as usize;
+            }
+        } else {
+            info!("ignoring the saved state, since its display is no longer connected");
+        }
 
-        velocity.0 += movement * strength * SPRITE_SCALE;
+        commands.remove_resource::<SavedState>();
     }
-}
 
-/// Handles updating the cube baby's velocity based off of mouse interactions.
-pub fn fixed_update_mouse_collision(
-    time: Res<Time>,
-    query: Single<(&mut Velocity, &mut PushDelay), With<CubeBaby>>,
-    mut cursor_moved_events: EventReader<CursorMoved>,
-) {
-    let (mut velocity, mut push_delay) = query.into_inner();
+    let mut entity = commands.spawn((
+        CubeBaby,
+        PrimaryBaby,
+        sprite,
+        transform,
+        position,
+        velocity,
+        PushDelays::ZERO,
+        Distance::ZERO,
+        FixedPositions::at(position),
+        CornerTrap::default(),
+        Tint::default(),
+        SpriteOffset::default(),
+        WanderDelay::default(),
+    ));
 
-    if *push_delay > PushDelay::ZERO {
-        push_delay.0 -= time.delta_secs_f64();
-
-        return;
+    if !patrol_route.is_empty() {
+        entity.insert(Patrolling::new(settings.patrol.dwell));
     }
 
-    // We only care about the start and end positions, which are used to roughly gauge the push direction.
-    let mut event_iterator = cursor_moved_events.read().map(|v| v.position);
-    let start_position = event_iterator.next();
-    let final_position = event_iterator.last();
+    window.position.set(position.to_physical());
+    window.visible = true;
+}
+
+/// Returns the sprites that outline a window of the given size, which are only drawn over solid backgrounds.
+///
+/// Solid backgrounds are outlined so that the window reads as an intentional widget rather than a rendering glitch.
+pub fn window_borders(settings: &Settings, window_size: f32) -> Vec<(WindowBorder, Sprite, Transform)> {
+    let Background::Solid(color) = settings.window.background else { return Vec::new() };
+    let color = if color.luminance() > 0.5 { color.darker(0.25) } else { color.lighter(0.25) };
+    let edge = (window_size / 2.0) - 0.5;
 
-    if let Some((start_position, final_position)) = start_position.zip(final_position) {
-        let delta_position = final_position - start_position;
-        let mut delta_position = delta_position * PUSH_STRENGTH * SPRITE_SCALE;
+    [
+        (Vec2::new(window_size, 1.0), Vec2::new(0.0, edge)),
+        (Vec2::new(window_size, 1.0), Vec2::new(0.0, -edge)),
+        (Vec2::new(1.0, window_size), Vec2::new(-edge, 0.0)),
+        (Vec2::new(1.0, window_size), Vec2::new(edge, 0.0)),
+    ]
+    .into_iter()
+    .map(|(size, offset)| {
+        (WindowBorder, Sprite::from_color(color, size), Transform::from_translation(offset.extend(1.0)))
+    })
+    .collect()
+}
 
-        // Ensure that the cube baby is always pushed with a minimum strength.
-        if delta_position.length() < PUSH_STRENGTH * SPRITE_SCALE {
-            delta_position = delta_position.normalize_or_zero() * PUSH_STRENGTH * SPRITE_SCALE;
-        }
+/// Returns a new sprite and transform for a cube baby, sized to fit its window.
+pub fn baby_sprite(texture_metadata: &TextureMetadata, display_properties: &DisplayProperties) -> (Sprite, Transform) {
+    let texture_atlas = TextureAtlas { index: 0, layout: texture_metadata.layout_handle.clone_weak() };
+    let sprite = Sprite::from_atlas_image(texture_metadata.image_handle.clone_weak(), texture_atlas);
+    let transform = Transform::from_scale(texture_metadata.sprite_scale(display_properties.window_size).xyy());
 
-        velocity.0 += delta_position;
-        push_delay.0 = PUSH_DELAY;
-    }
+    (sprite, transform)
 }
 
-/// Updates the window's position to follow the current velocity.
-pub fn update_window_movement(
-    mut window: Single<&mut Window, With<PrimaryWindow>>,
+/// Returns where an extra cube baby spawned from the given window position should appear, along with the direction that
+/// it should be pushed away from its parent in.
+///
+/// The new window is placed right beside its parent, on the right if there is room for it and otherwise on the left,
+/// and is clamped onto the display. The bounds are given as the minimum and maximum positions that the window's edges
+/// may reach.
+#[must_use]
+pub fn spawn_placement(parent: ScreenPos, extent: Vec2, minimum: ScreenPos, maximum: ScreenPos) -> (ScreenPos, Vec2) {
+    // Prevent inverted bounds if the window is larger than the available space.
+    let maximum = (maximum.0 - extent).max(minimum.0);
+    let direction = if parent.x + extent.x <= maximum.x { Vec2::X } else { Vec2::NEG_X };
+    let position = (parent.0 + (direction * extent.x)).clamp(minimum.0, maximum);
+
+    (ScreenPos(position), direction)
+}
+
+/// Handles spawning and despawning extra cube babies when their hotkeys or menu entries are chosen.
+///
+/// Each extra cube baby pops out of the primary cube baby into a window of its own beside it, inheriting some of the
+/// primary cube baby's velocity along with a push away from it. Despawning puffs away the most recently spawned one.
+pub fn update_extra_babies(
+    mut commands: Commands,
+    key_input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    display_properties: Res<DisplayProperties>,
+    texture_metadata: Res<TextureMetadata>,
+    primary_baby: Single<(&Position, &Velocity), With<PrimaryBaby>>,
+    layers: Query<&RenderLayers, With<CubeBaby>>,
+    mut extra_babies: ResMut<ExtraBabies>,
+    mut menu_actions: EventReader<MenuAction>,
+) {
+    let spawning = &settings.spawning;
+    let mut spawns = usize::from(spawning.spawn_hotkey.as_ref().is_some_and(|v| v.just_pressed(&key_input)));
+    let mut despawns = usize::from(spawning.despawn_hotkey.as_ref().is_some_and(|v| v.just_pressed(&key_input)));
+
+    for action in menu_actions.read() {
+        match action {
+            MenuAction::Spawn => spawns += 1,
+            MenuAction::Despawn => despawns += 1,
+            _ => {}
+        }
+    }
+
+    for _ in 0 .. despawns {
+        let Some(extra_baby) = extra_babies.0.pop() else { break };
+
+        // The window is only closed once the puff has finished.
+        commands.entity(extra_baby.baby).insert(Despawning::new(extra_baby.window, extra_baby.camera)).remove::<(
+            Squash,
+            Held,
+            DragHistory,
+        )>();
+    }
+
+    let (primary_position, primary_velocity) = *primary_baby;
+    // Layers are still in use by cube babies that are puffing away, so each new cube baby takes the lowest free one.
+    let mut used_layers = layers.iter().cloned().collect::<Vec<_>>();
+
+    for _ in 0 .. spawns.min(spawning.maximum.saturating_sub(extra_babies.0.len())) {
+        let layer = (1 ..).find(|v| !used_layers.iter().any(|w| w.intersects(&RenderLayers::layer(*v))));
+        let render_layers = RenderLayers::layer(layer.expect("ran out of render layers"));
+
+        used_layers.push(render_layers.clone());
+
+        let extra_baby = self::spawn_extra_baby(
+            &mut commands,
+            &settings,
+            &display_properties,
+            &texture_metadata,
+            (*primary_position, *primary_velocity),
+            render_layers,
+        );
+
+        extra_babies.0.push(extra_baby);
+    }
+}
+
+/// Spawns an extra cube baby out of the given parent position and velocity, drawn on the given render layers.
+fn spawn_extra_baby(
+    commands: &mut Commands,
+    settings: &Settings,
+    display_properties: &DisplayProperties,
+    texture_metadata: &TextureMetadata,
+    (parent_position, parent_velocity): (Position, Velocity),
+    render_layers: RenderLayers,
+) -> ExtraBaby {
+    let (position, direction) = self::spawn_placement(
+        parent_position.0,
+        display_properties.window_extent(),
+        display_properties.minimum_position(),
+        display_properties.maximum_position(),
+    );
+    let position = Position(position);
+
+    let mut window = self::window_settings(settings);
+
+    self::resize_window(&mut window, display_properties.window_size);
+    window.position.set(position.to_physical());
+    window.focused = false;
+    window.visible = true;
+
+    let window = commands.spawn(window).id();
+    let target = RenderTarget::Window(WindowRef::Entity(window));
+    let camera = commands
+        .spawn((Camera2d, Camera { target, ..Camera::default() }, render_layers.clone()))
+        .with_children(|parent| {
+            for border in self::window_borders(settings, display_properties.window_size) {
+                parent.spawn((border, render_layers.clone()));
+            }
+        })
+        .id();
+
+    let (sprite, transform) = self::baby_sprite(texture_metadata, display_properties);
+    let separation = direction * SPAWN_SEPARATION_SPEED * display_properties.sprite_scale();
+    let velocity = Velocity(
+        (parent_velocity.0 * SPAWN_INHERITED_VELOCITY)
+            + coords::logical_to_physical(separation, display_properties.scale_factor),
+    );
+    let baby = commands
+        .spawn((
+            CubeBaby,
+            BabyWindow(window),
+            sprite,
+            transform,
+            position,
+            velocity,
+            PushDelays::ZERO,
+            Distance::ZERO,
+            FixedPositions::at(position),
+            CornerTrap::default(),
+            Tint::default(),
+            SpriteOffset::default(),
+            WanderDelay::default(),
+            render_layers,
+        ))
+        .id();
+
+    ExtraBaby { baby, window, camera }
+}
+
+/// Puffs away each despawning cube baby, closing its window once it has vanished.
+///
+/// Only the primary window closing exits the application, so closing these windows never does.
+pub fn update_despawning(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    display_properties: Res<DisplayProperties>,
+    texture_metadata: Res<TextureMetadata>,
+    mut query: Query<(Entity, &mut Despawning, &mut Transform, &mut Tint, &mut Velocity)>,
+) {
+    let scale = texture_metadata.sprite_scale(display_properties.window_size).xyy();
+
+    for (entity, mut despawning, mut transform, mut tint, mut velocity) in &mut query {
+        if despawning.timer.tick(time.delta()).finished() {
+            // The window's borders are children of its camera.
+            commands.entity(despawning.camera).despawn_recursive();
+
+            for entity in [entity, despawning.window] {
+                commands.entity(entity).despawn();
+            }
+
+            continue;
+        }
+
+        let fraction = despawning.timer.fraction();
+
+        velocity.0 = Vec2::ZERO;
+        transform.scale = scale * (1.0 + (fraction * DESPAWN_PUFF_GROWTH));
+        tint.opacity = 1.0 - fraction;
+    }
+}
+
+/// Sets the primary window's icon from the first frame of the cube baby's texture.
+pub fn update_window_icon(
+    primary_window: Single<Entity, With<PrimaryWindow>>,
+    winit_windows: NonSend<WinitWindows>,
+    image_assets: Res<Assets<Image>>,
+    texture_metadata: Res<TextureMetadata>,
+    mut last_window: Local<Option<Entity>>,
+) {
+    if *last_window == Some(*primary_window) {
+        return;
+    }
+
+    let Some(window) = winit_windows.get_window(*primary_window) else { return };
+    let Some(image) = image_assets.get(&texture_metadata.image_handle) else { return };
+
+    *last_window = Some(*primary_window);
+
+    if image.texture_descriptor.format.block_copy_size(None) != Some(4) {
+        warn!("unable to create a window icon from a texture of format {:?}", image.texture_descriptor.format);
+
+        return;
+    }
+
+    let icon = |size: u32| {
+        let data = branding::frame_icon(
+            &image.data,
+            texture_metadata.size.0,
+            texture_metadata.offset.0,
+            texture_metadata.frame_size().0,
+            size,
+        )?;
+
+        winit::window::Icon::from_rgba(data, size, size).ok()
+    };
+
+    window.set_window_icon(icon(branding::ICON_SIZE));
+
+    #[cfg(target_os = "windows")]
+    {
+        use winit::platform::windows::WindowExtWindows;
+
+        window.set_taskbar_icon(icon(branding::TASKBAR_ICON_SIZE));
+    }
+}
+
+/// Begins the attention sequence and shows the hint if this is the first time that the application has been run.
+pub fn on_application_load_finished_onboarding(
+    mut commands: Commands,
+    display_properties: Res<DisplayProperties>,
+    query: Single<(Entity, &Position), With<PrimaryBaby>>,
+) {
+    // Users who have already written a settings file are not new, even if they have never seen the hint.
+    if storage::data_exists(onboarding::DISMISSED_FILE_NAME) || Settings::path().is_some_and(|v| v.exists()) {
+        return;
+    }
+
+    let (entity, position) = query.into_inner();
+
+    commands.entity(entity).insert(Onboarding { elapsed: 0.0, origin: position.0 });
+
+    // Place the hint centered just above the cube baby, keeping it on the display.
+    let window_extent = display_properties.window_extent();
+    let hint_extent = coords::logical_to_physical(onboarding::HINT_SIZE, display_properties.scale_factor);
+    let hint_position =
+        position.0 + Vec2::new((window_extent.x - hint_extent.x) / 2.0, -hint_extent.y - window_extent.y);
+    let minimum_position = display_properties.display_minimum_position();
+    let maximum_position = display_properties.display_maximum_position() - hint_extent;
+    let hint_position = ScreenPos(hint_position.clamp(minimum_position.0, maximum_position.max(minimum_position.0)));
+
+    // The hint is shown through the window's title bar, so the window must be decorated.
+    let hint_window = commands.spawn((OnboardingHint, Window {
+        title: onboarding::HINT_TEXT.to_string(),
+        resolution: WindowResolution::new(onboarding::HINT_SIZE.x, onboarding::HINT_SIZE.y),
+        position: WindowPosition::At(hint_position.to_physical()),
+        resizable: false,
+        enabled_buttons: EnabledButtons { minimize: false, maximize: false, close: true },
+        focused: false,
+        window_level: WindowLevel::AlwaysOnTop,
+        ..Window::default()
+    }));
+    let target = RenderTarget::Window(WindowRef::Entity(hint_window.id()));
+
+    // The hint window only shows its title bar, so its camera renders none of the cube babies' layers.
+    commands.spawn((
+        OnboardingHint,
+        Camera2d,
+        Camera { target, clear_color: ClearColorConfig::Custom(Background::FALLBACK_COLOR), ..Camera::default() },
+        RenderLayers::none(),
+    ));
+}
+
+/// Dismisses the first-run hint and attention sequence upon any user interaction.
+pub fn update_onboarding_dismissal(
+    mut commands: Commands,
+    key_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    hints: Query<Entity, With<OnboardingHint>>,
+    mut query: Query<(Entity, &mut Transform), (With<PrimaryBaby>, With<Onboarding>)>,
+    mut pushed_events: EventReader<Pushed>,
+) {
+    let pushed = pushed_events.read().count() > 0;
+
+    if !pushed && key_input.get_just_pressed().next().is_none() && mouse_input.get_just_pressed().next().is_none() {
+        return;
+    }
+
+    for entity in &hints {
+        commands.entity(entity).despawn();
+    }
+
+    for (entity, mut transform) in &mut query {
+        transform.rotation = Quat::IDENTITY;
+
+        commands.entity(entity).remove::<Onboarding>();
+    }
+}
+
+/// Performs the first-run attention sequence.
+pub fn update_onboarding(
+    mut commands: Commands,
+    time: Res<Time>,
+    display_properties: Res<DisplayProperties>,
+    query: Single<(Entity, &mut Onboarding, &mut Position, &mut Velocity, &mut Transform), With<PrimaryBaby>>,
+) {
+    let (entity, mut onboarding, mut position, mut velocity, mut transform) = query.into_inner();
+
+    onboarding.elapsed += time.delta_secs();
+
+    if let Some((angle, offset)) = onboarding::attention_pose(onboarding.elapsed) {
+        transform.rotation = Quat::from_rotation_z(angle);
+        position.0 = onboarding.origin + coords::logical_to_physical(offset, display_properties.scale_factor);
+        velocity.0 = Vec2::ZERO;
+    } else {
+        transform.rotation = Quat::IDENTITY;
+        position.0 = onboarding.origin;
+
+        commands.entity(entity).remove::<Onboarding>();
+    }
+}
+
+/// Remembers that the first-run hint has been dismissed, whether by interaction or by closing its window.
+pub fn update_onboarding_persistence(
+    mut commands: Commands,
+    hints: Query<Entity, With<OnboardingHint>>,
+    mut removed_hints: RemovedComponents<OnboardingHint>,
+) {
+    if removed_hints.read().count() == 0 {
+        return;
+    }
+
+    // Closing the hint's window leaves its camera behind.
+    for entity in &hints {
+        commands.entity(entity).despawn();
+    }
+
+    if let Err(error) = storage::write_data(onboarding::DISMISSED_FILE_NAME, "") {
+        warn!("failed to remember that the hint was dismissed: {error}");
+    }
+}
+
+/// Returns the cube baby whose window is focused, falling back to the primary cube baby when none of their windows are.
+pub fn focused_baby<'a>(
+    primary_window: Entity,
+    primary_baby: Entity,
+    windows: &Query<&Window>,
+    babies: impl IntoIterator<Item = (Entity, Option<&'a BabyWindow>)>,
+) -> Entity {
+    babies
+        .into_iter()
+        .find_map(|(entity, baby_window)| {
+            let window_entity = baby_window.map_or(primary_window, |v| v.0);
+
+            windows.get(window_entity).is_ok_and(|v| v.focused).then_some(entity)
+        })
+        .unwrap_or(primary_baby)
+}
+
+/// Handles knocking the cube baby when a key or the global hotkey is pressed.
+///
+/// Pressing the knock key knocks immediately, and holding it past a short delay charges a stronger knock, which is
+/// released along with the key. Keys knock the cube baby whose window is focused, falling back to the primary cube baby
+/// when none of their windows are, such as when the global hotkey is pressed.
+pub fn update_spacebar_knocking(
+    time: Res<Time>,
+    button_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    keybindings: Res<Keybindings>,
+    settings: Res<Settings>,
+    display_properties: Res<DisplayProperties>,
+    gravity: Res<Gravity>,
+    primary_window: Single<Entity, With<PrimaryWindow>>,
+    primary_baby: Single<Entity, With<PrimaryBaby>>,
+    windows: Query<&Window>,
+    mut query: Query<
+        (Entity, &mut Velocity, &mut PushDelays, &mut Distance, &Position, Option<&BabyWindow>),
+        With<CubeBaby>,
+    >,
+    mut knock_events: EventReader<Knock>,
+    mut pushed_events: EventWriter<Pushed>,
+    mut charge: Local<Option<f32>>,
+) {
+    let focused_baby = self::focused_baby(
+        *primary_window,
+        *primary_baby,
+        &windows,
+        query.iter().map(|(entity, .., baby_window)| (entity, baby_window)),
+    );
+    let Ok((baby, mut velocity, mut push_delays, mut distance, position, _)) = query.get_mut(focused_baby) else {
+        return;
+    };
+    let mut multiplier = (knock_events.read().count() > 0).then_some(1.0);
+    let mut charged = false;
+
+    if let Some(seconds) = *charge {
+        // Holding only starts charging after a moment, so that quick taps knock just like every other key.
+        let progress = ((seconds - KNOCK_CHARGE_DELAY) / KNOCK_CHARGE_DURATION).clamp(0.0, 1.0);
+
+        if keybindings.pressed(Action::Knock, &button_input) || keybindings.mouse_pressed(Action::Knock, &mouse_input) {
+            *charge = Some(seconds + time.delta_secs());
+
+            // Spin faster as the charge builds, so that its strength is visible.
+            let spin_distance = SLIDE_SPIN_DISTANCE * display_properties.sprite_scale();
+
+            distance.0 += spin_distance * KNOCK_CHARGE_SPIN_RATE * progress * time.delta_secs();
+        } else {
+            *charge = None;
+            charged = seconds >= KNOCK_CHARGE_DELAY;
+
+            if charged {
+                multiplier = Some(1.0 + ((settings.push.charge_multiplier - 1.0) * progress));
+            }
+        }
+    }
+
+    // The knock key jumps instead while gravity is enabled.
+    let just_pressed = keybindings.just_pressed(Action::Knock, &button_input)
+        || keybindings.mouse_just_pressed(Action::Knock, &mouse_input);
+
+    if just_pressed && !gravity.0 {
+        *charge = Some(0.0);
+        multiplier = Some(multiplier.map_or(1.0, |v| v.max(1.0)));
+    }
+
+    // Bound keys are handled by their own actions.
+    if button_input.get_just_pressed().any(|v| !keybindings.is_bound(*v)) {
+        multiplier = Some(multiplier.map_or(1.0, |v| v.max(1.0)));
+    }
+
+    let Some(multiplier) = multiplier else { return };
+
+    // A charged knock is only released after the key has been held for a while, so it is never swallowed by the delay
+    // from the knock that its press caused.
+    if push_delays.keyboard > 0.0 && !charged {
+        return;
+    }
+
+    let impulse = push::random_knock(velocity.0, display_properties.sprite_scale()) * multiplier;
+    let impulse = settings.push.curve.apply_to(impulse);
+    let impulse = coords::logical_to_physical(impulse, display_properties.scale_factor);
+
+    if display_properties.presses_into_edge(impulse, position.0, settings.bounds.edge_push_deadzone) {
+        return;
+    }
+
+    velocity.0 += impulse;
+    push_delays.keyboard = PUSH_DELAY;
+    pushed_events.send(Pushed { baby, impulse });
+}
+
+/// Handles pushing the cube baby with gamepad stick flicks and knocking it with the south face button.
+///
+/// Like keys, gamepads push the cube baby whose window is focused, falling back to the primary cube baby.
+pub fn update_gamepad_pushing(
+    gamepads: Query<(Entity, &Gamepad)>,
+    settings: Res<Settings>,
+    display_properties: Res<DisplayProperties>,
+    primary_window: Single<Entity, With<PrimaryWindow>>,
+    primary_baby: Single<Entity, With<PrimaryBaby>>,
+    windows: Query<&Window>,
+    mut query: Query<(Entity, &mut Velocity, &mut PushDelays, &Position, Option<&BabyWindow>), With<CubeBaby>>,
+    mut pushed_events: EventWriter<Pushed>,
+    mut flicked: Local<EntityHashSet>,
+) {
+    // Forget gamepads that have since been disconnected.
+    flicked.retain(|v| gamepads.contains(*v));
+
+    let focused_baby = self::focused_baby(
+        *primary_window,
+        *primary_baby,
+        &windows,
+        query.iter().map(|(entity, .., baby_window)| (entity, baby_window)),
+    );
+    let Ok((baby, mut velocity, mut push_delays, position, _)) = query.get_mut(focused_baby) else { return };
+
+    for (entity, gamepad) in &gamepads {
+        let stick = push::rescale_stick(gamepad.right_stick(), GAMEPAD_DEADZONE);
+        let mut impulse = Vec2::ZERO;
+
+        // A stick only flicks once per trip past the threshold, and must return to rest before flicking again.
+        if stick == Vec2::ZERO {
+            flicked.remove(&entity);
+        } else if stick.length() >= GAMEPAD_FLICK_THRESHOLD && flicked.insert(entity) {
+            impulse += settings.push.curve.apply_to(stick * GAMEPAD_FLICK_STRENGTH);
+        }
+
+        if gamepad.just_pressed(GamepadButton::South) {
+            impulse += settings.push.curve.apply_to(push::random_knock(velocity.0, display_properties.sprite_scale()));
+        }
+
+        // Sticks are still tracked while delayed, so that a held flick does not fire once the delay ends.
+        if impulse == Vec2::ZERO || push_delays.gamepad > 0.0 {
+            continue;
+        }
+
+        let impulse = coords::logical_to_physical(impulse, display_properties.scale_factor);
+
+        if display_properties.presses_into_edge(impulse, position.0, settings.bounds.edge_push_deadzone) {
+            continue;
+        }
+
+        velocity.0 += impulse;
+        push_delays.gamepad = PUSH_DELAY;
+        pushed_events.send(Pushed { baby, impulse });
+    }
+}
+
+/// Handles sending knock events whenever the global hotkey is pressed.
+#[cfg(feature = "global_hotkey")]
+pub fn update_global_hotkey(global_hotkey: NonSend<hotkey::GlobalHotkey>, mut knock_events: EventWriter<Knock>) {
+    if global_hotkey.just_pressed() {
+        knock_events.send(Knock);
+    }
+}
+
+/// Handles unregistering the global hotkey when the application exits.
+#[cfg(feature = "global_hotkey")]
+pub fn post_update_global_hotkey_release(
+    global_hotkey: NonSend<hotkey::GlobalHotkey>,
+    mut exit_events: EventReader<AppExit>,
+) {
+    if exit_events.read().count() > 0 {
+        global_hotkey.unregister();
+    }
+}
+
+/// Handles opening the context menu when the cube baby is clicked with the menu button.
+#[cfg(feature = "context_menu")]
+pub fn update_context_menu(
+    context_menu: NonSend<menu::ContextMenu>,
+    winit_windows: NonSend<WinitWindows>,
+    primary_window: Single<Entity, With<PrimaryWindow>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    keybindings: Res<Keybindings>,
+    time: Res<Time<Virtual>>,
+    behavior_mode: Res<BehaviorMode>,
+    mut menu_actions: EventWriter<MenuAction>,
+) {
+    if keybindings.mouse_just_pressed(Action::Menu, &mouse_input) {
+        let Some(window) = winit_windows.get_window(*primary_window) else { return };
+
+        context_menu.set_paused(time.is_paused());
+        context_menu.set_behavior_mode(*behavior_mode);
+        context_menu.show(window);
+    }
+
+    menu_actions.send_batch(context_menu.actions());
+}
+
+/// Handles sending the actions whose keys or mouse buttons were pressed.
+pub fn update_action_keys(
+    key_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    keybindings: Res<Keybindings>,
+    mut menu_actions: EventWriter<MenuAction>,
+) {
+    for (action, menu_action) in [(Action::Pause, MenuAction::Pause), (Action::Center, MenuAction::Center)] {
+        if keybindings.just_pressed(action, &key_input) || keybindings.mouse_just_pressed(action, &mouse_input) {
+            menu_actions.send(menu_action);
+        }
+    }
+}
+
+/// Handles changing the simulation speed when one of its keys is pressed.
+pub fn update_simulation_speed(
+    key_input: Res<ButtonInput<KeyCode>>,
+    keybindings: Res<Keybindings>,
+    mut simulation_speed: ResMut<SimulationSpeed>,
+) {
+    let speed =
+        Action::ALL.into_iter().filter(|v| keybindings.just_pressed(*v, &key_input)).find_map(Action::simulation_speed);
+
+    if let Some(speed) = speed.filter(|v| *v != simulation_speed.0) {
+        simulation_speed.0 = speed;
+
+        info!("simulation speed set to {speed}x");
+    }
+}
+
+/// Returns `true` if the simulation is not paused, so that paused systems leave the cube baby frozen in place.
+///
+/// Pausing only stops virtual time, so the cube baby's velocity is kept and unpausing resumes its slide.
+pub fn is_unpaused(time: Res<Time<Virtual>>) -> bool {
+    !time.is_paused()
+}
+
+/// Clears out anything that built up while the simulation was paused, so that resuming never causes a burst of
+/// activity.
+///
+/// Virtual time already discards the time spent paused, so the first frame after resuming is no longer than usual.
+pub fn pre_update_resume_hygiene(
+    time: Res<Time<Virtual>>,
+    display_properties: Res<DisplayProperties>,
+    mut query: Query<(&mut Distance, &mut WanderDelay), With<CubeBaby>>,
+    mut knock_events: ResMut<Events<Knock>>,
+    mut was_paused: Local<bool>,
+) {
+    let resumed = *was_paused && !time.is_paused();
+
+    *was_paused = time.is_paused();
+
+    if !resumed {
+        return;
+    }
+
+    let spin_distance = SLIDE_SPIN_DISTANCE * display_properties.sprite_scale();
+
+    for (mut distance, mut wander_delay) in &mut query {
+        // Keep at most one frame's worth of spinning, rather than spinning through many frames at once.
+        distance.0 = distance.0.min(spin_distance);
+        // Choose a fresh delay, rather than wandering as soon as the old one runs out.
+        wander_delay.0 = None;
+    }
+
+    // Knocks that were requested while paused are stale by now.
+    knock_events.clear();
+}
+
+/// Returns `true` if the latest frame followed closely after the last, so that motion is skipped after waking from
+/// sleep.
+pub fn is_not_stalled(time: Res<Time<Real>>) -> bool {
+    !physics::is_stall(time.delta_secs())
+}
+
+/// Handles performing the actions chosen from the context menu or by key bindings.
+pub fn update_menu_actions(
+    mut time: ResMut<Time<Virtual>>,
+    primary_window: Single<(Entity, &mut Window), With<PrimaryWindow>>,
+    winit_windows: NonSend<WinitWindows>,
+    mut display_properties: ResMut<DisplayProperties>,
+    query: Single<(&mut Position, &mut Velocity, &mut Distance), With<PrimaryBaby>>,
+    mut menu_actions: EventReader<MenuAction>,
+    mut behavior_mode: ResMut<BehaviorMode>,
+    mut knock_events: EventWriter<Knock>,
+    mut exit_events: EventWriter<AppExit>,
+) {
+    let (mut position, mut velocity, mut distance) = query.into_inner();
+    let (window_entity, mut window) = primary_window.into_inner();
+
+    for action in menu_actions.read() {
+        match action {
+            MenuAction::Knock => {
+                knock_events.send(Knock);
+            }
+            MenuAction::Center => {
+                // The cube baby may have wandered onto another display since it was last loaded.
+                if let Some(current_monitor) = winit_windows.get_window(window_entity).and_then(|v| v.current_monitor())
+                {
+                    display_properties.load_monitor(&current_monitor);
+                    display_properties.load_work_area();
+                }
+
+                position.0 = display_properties.center_position() - (display_properties.window_extent() / 2.0);
+                velocity.0 = Vec2::ZERO;
+                distance.0 = 0.0;
+
+                // Present the new position right away, even if window movement is paused.
+                window.position.set(position.to_physical());
+            }
+            // Pausing virtual time freezes every simulation system at once.
+            MenuAction::Pause if time.is_paused() => time.unpause(),
+            MenuAction::Pause => time.pause(),
+            MenuAction::Skittish => {
+                *behavior_mode = behavior_mode.toggled(BehaviorMode::Skittish);
+            }
+            MenuAction::Follow => {
+                *behavior_mode = behavior_mode.toggled(BehaviorMode::Following);
+            }
+            MenuAction::Orbit => {
+                *behavior_mode = behavior_mode.toggled(BehaviorMode::Orbiting);
+            }
+            // Extra cube babies are handled along with their hotkeys, and are ignored in safe mode.
+            MenuAction::Spawn | MenuAction::Despawn => {}
+            MenuAction::Quit => {
+                exit_events.send(AppExit::Success);
+            }
+        }
+    }
+}
+
+/// Handles nudging the cube baby whose window is focused when a nudge key is pressed.
+pub fn update_arrow_nudging(
+    button_input: Res<ButtonInput<KeyCode>>,
+    keybindings: Res<Keybindings>,
+    settings: Res<Settings>,
+    display_properties: Res<DisplayProperties>,
+    primary_window: Single<Entity, With<PrimaryWindow>>,
+    primary_baby: Single<Entity, With<PrimaryBaby>>,
+    windows: Query<&Window>,
+    mut query: Query<(Entity, &mut Velocity, &mut PushDelays, &Position, Option<&BabyWindow>), With<CubeBaby>>,
+    mut pushed_events: EventWriter<Pushed>,
+) {
+    let focused_baby = self::focused_baby(
+        *primary_window,
+        *primary_baby,
+        &windows,
+        query.iter().map(|(entity, .., baby_window)| (entity, baby_window)),
+    );
+    let Ok((baby, mut velocity, mut push_delays, position, _)) = query.get_mut(focused_baby) else { return };
+
+    if push_delays.keyboard > 0.0 {
+        return;
+    }
+
+    let direction = push::nudge_direction(
+        Action::ALL
+            .into_iter()
+            .filter(|v| keybindings.just_pressed(*v, &button_input))
+            .filter_map(Action::nudge_direction),
+    );
+
+    if direction == Vec2::ZERO {
+        return;
+    }
+
+    let impulse = coords::logical_to_physical(direction * settings.push.nudge, display_properties.scale_factor);
+
+    if display_properties.presses_into_edge(impulse, position.0, settings.bounds.edge_push_deadzone) {
+        return;
+    }
+
+    velocity.0 += impulse;
+    push_delays.keyboard = PUSH_DELAY;
+    pushed_events.send(Pushed { baby, impulse });
+}
+
+/// Counts down every cube baby's push delays.
+pub fn fixed_update_push_delays(
+    time: Res<Time>,
+    simulation_speed: Res<SimulationSpeed>,
+    mut query: Query<&mut PushDelays, With<CubeBaby>>,
+) {
+    for mut push_delays in &mut query {
+        push_delays.tick(f64::from(physics::step_delta(time.delta_secs(), simulation_speed.0)));
+    }
+}
+
+/// Handles updating each cube baby's velocity based off of mouse interactions with its window.
+///
+/// Dizzy cube babies ignore the mouse until they have recovered.
+pub fn fixed_update_mouse_collision(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    display_properties: Res<DisplayProperties>,
+    push_model: Res<PushModel>,
+    texture_metadata: Res<TextureMetadata>,
+    alpha_mask: Option<Res<AlphaMask>>,
+    primary_window: Single<Entity, With<PrimaryWindow>>,
+    mut query: Query<
+        (Entity, &mut Velocity, &mut PushDelays, &Position, &Sprite, Has<Petting>, Option<&BabyWindow>),
+        (With<CubeBaby>, Without<Held>, Without<Dizzy>),
+    >,
+    global_cursor: Option<Res<GlobalCursor>>,
+    mut cursor_moved_events: EventReader<CursorMoved>,
+    mut pushed_events: EventWriter<Pushed>,
+) {
+    // Every event is read up front, so that stale cursor movement never lingers to push a cube baby later on.
+    let cursor_moved_events = cursor_moved_events.read().collect::<Vec<_>>();
+    let sprite_scale = texture_metadata.sprite_scale(display_properties.window_size);
+
+    for (entity, mut velocity, mut push_delays, position, sprite, petting, baby_window) in &mut query {
+        // A petted cube baby stays put.
+        if petting || push_delays.mouse > 0.0 {
+            continue;
+        }
+
+        // Only the sprite's opaque pixels count as touching it, so that its transparent corners may be passed through.
+        let frame_index = sprite.texture_atlas.as_ref().map_or(0, |v| v.index);
+        let is_opaque = |v: Vec2| alpha_mask.as_ref().is_none_or(|mask| mask.is_opaque(frame_index, v / sprite_scale));
+
+        let cursor_path = if let Some(global_cursor) = global_cursor.as_ref() {
+            let Some((start_position, final_position)) = global_cursor.previous().zip(global_cursor.position()) else {
+                continue;
+            };
+            let window_rect = Rect::from_corners(position.0.0, (position.0 + display_properties.window_extent()).0);
+            let touches = |v: ScreenPos| {
+                window_rect.contains(v.0)
+                    && is_opaque(coords::physical_to_logical(v.0 - position.0.0, display_properties.scale_factor))
+            };
+
+            // Count the cursor as touching the window if it was over the sprite at either end of its movement.
+            if start_position == final_position || !(touches(start_position) || touches(final_position)) {
+                continue;
+            }
+
+            let offset = window_rect.center() - final_position.0;
+            let delta_position = push::directed_delta(*push_model, final_position - start_position, offset);
+            let delta_position = coords::physical_to_logical(delta_position, display_properties.scale_factor);
+            let travelled =
+                coords::physical_to_logical(final_position - start_position, display_properties.scale_factor);
+
+            // The cursor is only polled once per update, so its path is a straight line.
+            Some((delta_position, travelled.length()))
+        } else {
+            let window_entity = baby_window.map_or(*primary_window, |v| v.0);
+            let positions = cursor_moved_events
+                .iter()
+                .filter(|v| v.window == window_entity)
+                .map(|v| WindowPos(v.position))
+                .collect::<Vec<_>>();
+            let (start_position, final_position) = (positions.first().copied(), positions.last().copied());
+
+            // The start and end positions gauge the push direction, while every position counts towards a flick.
+            start_position
+                .zip(final_position)
+                .filter(|(v, w)| positions.len() > 1 && (is_opaque(v.0) || is_opaque(w.0)))
+                .map(|(start_position, final_position)| {
+                    let offset = Vec2::splat(display_properties.window_size / 2.0) - final_position.0;
+                    let path_length = push::path_length(positions.iter().map(|v| v.0));
+
+                    (push::directed_delta(*push_model, final_position - start_position, offset), path_length)
+                })
+        };
+
+        let Some((delta_position, path_length)) = cursor_path else { continue };
+
+        // Slowly stroking the cube baby pets it rather than shoving it, and a resting hand's jitter does nothing at
+        // all.
+        if path_length < (PET_MAX_SPEED * time.delta_secs()).max(settings.push.jitter_deadzone) {
+            continue;
+        }
+
+        let push_settings = &settings.push;
+        let flick = push::flick_impulse(
+            delta_position,
+            path_length,
+            time.delta_secs(),
+            display_properties.window_size,
+            push_settings.flick_time,
+            push_settings.flick_strength,
+        );
+        let baby = (entity, &mut *velocity, &mut *push_delays, position);
+
+        if let Some(impulse) = flick {
+            // Flicks skip the push curve, so that the cube baby visibly goes flying.
+            if self::apply_pointer_impulse(impulse, &settings, &display_properties, baby, &mut pushed_events) {
+                commands.entity(entity).insert(Flicked::default());
+            }
+        } else {
+            self::apply_pointer_push(
+                delta_position,
+                time.delta_secs(),
+                &settings,
+                &display_properties,
+                baby,
+                &mut pushed_events,
+            );
+        }
+    }
+}
+
+/// Makes a cube baby dizzy when the cursor reverses direction over its window too many times in quick succession.
+pub fn update_dizzy_detection(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    primary_window: Single<Entity, With<PrimaryWindow>>,
+    query: Query<(Entity, Has<Dizzy>, Has<Held>, Option<&BabyWindow>), With<CubeBaby>>,
+    mut cursor_moved_events: EventReader<CursorMoved>,
+    mut shake_trackers: Local<EntityHashMap<ShakeTracker>>,
+) {
+    let baby_windows = query.iter().map(|(.., v)| v.map_or(*primary_window, |v| v.0)).collect::<EntityHashSet>();
+    let now = time.elapsed_secs_f64();
+    let mut reversals = EntityHashMap::<usize>::default();
+
+    shake_trackers.retain(|window, _| baby_windows.contains(window));
+
+    // Each window tracks its own shaking, so that moving between windows is never mistaken for a reversal.
+    for event in cursor_moved_events.read().filter(|v| baby_windows.contains(&v.window)) {
+        let Some(delta) = event.delta else { continue };
+
+        reversals.insert(event.window, shake_trackers.entry(event.window).or_default().record(now, delta));
+    }
+
+    for (entity, dizzy, held, baby_window) in &query {
+        let window_entity = baby_window.map_or(*primary_window, |v| v.0);
+
+        if !dizzy && !held && reversals.get(&window_entity).is_some_and(|v| *v >= DIZZY_REVERSALS) {
+            shake_trackers.remove(&window_entity);
+
+            commands.entity(entity).insert(Dizzy::default());
+        }
+    }
+}
+
+/// Slows each dizzy cube baby to a stop while spinning its sprite rapidly, until it recovers.
+pub fn update_dizziness(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Dizzy, &mut Velocity, &mut Sprite), With<CubeBaby>>,
+) {
+    for (entity, mut dizzy, mut velocity, mut sprite) in &mut query {
+        if dizzy.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Dizzy>();
+
+            continue;
+        }
+
+        velocity.0 *= (1.0 - (DIZZY_DRAG * time.delta_secs())).clamp(0.0, 1.0);
+        dizzy.spin += DIZZY_SPIN_RATE * time.delta_secs();
+
+        let frames = dizzy.spin.floor();
+
+        dizzy.spin -= frames;
+
+        if let Some(texture_atlas) = sprite.texture_atlas.as_mut() {
+            texture_atlas.index = (texture_atlas.index + frames as usize) % ATLAS_FRAMES as usize;
+        }
+    }
+}
+
+/// Handles updating each cube baby's velocity based off of touch interactions with its window.
+///
+/// Only the first active touch on each window is tracked. Taps push away from the touch point, while swipes push along
+/// the swipe.
+pub fn fixed_update_touch_collision(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    display_properties: Res<DisplayProperties>,
+    primary_window: Single<Entity, With<PrimaryWindow>>,
+    mut query: Query<
+        (Entity, &mut Velocity, &mut PushDelays, &Position, Option<&BabyWindow>),
+        (With<CubeBaby>, Without<Held>),
+    >,
+    mut touch_events: EventReader<TouchInput>,
+    mut pushed_events: EventWriter<Pushed>,
+    mut touch_trackers: Local<EntityHashMap<TouchTracker>>,
+) {
+    let baby_windows = query.iter().map(|(.., v)| v.map_or(*primary_window, |v| v.0)).collect::<EntityHashSet>();
+    let mut gestures = EntityHashMap::<(Vec2, Option<Vec2>)>::default();
+
+    touch_trackers.retain(|window, _| baby_windows.contains(window));
+
+    // Every event is recorded, even while pushes are delayed, so that the trackers never miss a touch ending.
+    for event in touch_events.read().filter(|v| baby_windows.contains(&v.window)) {
+        let (swipe, tap) = gestures.entry(event.window).or_default();
+
+        match touch_trackers.entry(event.window).or_default().record(event) {
+            Some(TouchGesture::Swipe(delta)) => *swipe += delta,
+            Some(TouchGesture::Tap(position)) => *tap = Some(position),
+            None => {}
+        }
+    }
+
+    let elapsed = time.delta_secs();
+
+    for (entity, mut velocity, mut push_delays, position, baby_window) in &mut query {
+        let Some(&(swipe, tap)) = gestures.get(&baby_window.map_or(*primary_window, |v| v.0)) else { continue };
+
+        // Touches share the mouse's delay, since both come from pointing at the window.
+        if push_delays.mouse > 0.0 {
+            continue;
+        }
+
+        let delta_position = if swipe != Vec2::ZERO {
+            swipe
+        } else if let Some(tap) = tap {
+            // Taps are treated as a touch moving outwards from where it landed at a fixed speed.
+            let offset = Vec2::splat(display_properties.window_size / 2.0) - tap;
+
+            push::directed_delta(PushModel::Radial, Vec2::X * TOUCH_TAP_SPEED * elapsed, offset)
+        } else {
+            continue;
+        };
+
+        let baby = (entity, &mut *velocity, &mut *push_delays, position);
+
+        self::apply_pointer_push(delta_position, elapsed, &settings, &display_properties, baby, &mut pushed_events);
+    }
+}
+
+/// Pushes the cube baby with a pointer that moved by the given logical amount over the given number of seconds.
+///
+/// This is shared by the mouse and touch paths, so that both push with the same strength.
+pub fn apply_pointer_push(
+    delta_position: Vec2,
+    elapsed: f32,
+    settings: &Settings,
+    display_properties: &DisplayProperties,
+    baby: (Entity, &mut Velocity, &mut PushDelays, &Position),
+    pushed_events: &mut EventWriter<Pushed>,
+) {
+    let push_settings = &settings.push;
+    let impulse = push::cursor_impulse(
+        delta_position,
+        elapsed,
+        push_settings.curve,
+        push_settings.minimum,
+        push_settings.maximum,
+    );
+
+    self::apply_pointer_impulse(impulse, settings, display_properties, baby, pushed_events);
+}
+
+/// Applies the given logical impulse from a pointer to the cube baby, returning whether it was applied.
+pub fn apply_pointer_impulse(
+    impulse: Vec2,
+    settings: &Settings,
+    display_properties: &DisplayProperties,
+    (baby, velocity, push_delays, position): (Entity, &mut Velocity, &mut PushDelays, &Position),
+    pushed_events: &mut EventWriter<Pushed>,
+) -> bool {
+    if impulse == Vec2::ZERO {
+        return false;
+    }
+
+    // Pointer movement is measured in logical pixels, but velocity is measured in physical pixels.
+    let impulse = coords::logical_to_physical(impulse, display_properties.scale_factor);
+
+    // Sweeping the pointer along an edge should not keep nudging a cube baby that is parked against it.
+    if display_properties.presses_into_edge(impulse, position.0, settings.bounds.edge_push_deadzone) {
+        return false;
+    }
+
+    velocity.0 += impulse;
+    push_delays.mouse = PUSH_DELAY;
+    pushed_events.send(Pushed { baby, impulse });
+
+    true
+}
+
+/// Polls the cursor's position on the desktop.
+pub fn fixed_update_global_cursor(display_properties: Res<DisplayProperties>, mut global_cursor: ResMut<GlobalCursor>) {
+    global_cursor.update(platform::global_cursor_position(display_properties.scale_factor));
+}
+
+/// Picks up a cube baby while the grab button is held over its window, and throws it upon release.
+pub fn update_grabbing(
+    mut commands: Commands,
+    time: Res<Time>,
+    primary_window: Single<Entity, With<PrimaryWindow>>,
+    windows: Query<&Window>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    keybindings: Res<Keybindings>,
+    display_properties: Res<DisplayProperties>,
+    mut query: Query<
+        (Entity, &mut Position, &mut Velocity, Option<(&Held, &mut DragHistory)>, Option<&BabyWindow>),
+        With<CubeBaby>,
+    >,
+    mut cursor_moved_events: EventReader<CursorMoved>,
+    mut display_state: ResMut<NextState<LoadingState<DisplayLoadingMarker>>>,
+) {
+    let cursor_moved_events = cursor_moved_events.read().collect::<Vec<_>>();
+
+    for (entity, mut position, mut velocity, held, baby_window) in &mut query {
+        let window_entity = baby_window.map_or(*primary_window, |v| v.0);
+        let Ok(window) = windows.get(window_entity) else { continue };
+
+        // The window's cursor position is only updated by these events, so it goes stale once the window has moved.
+        let cursor_position = cursor_moved_events
+            .iter()
+            .filter(|v| v.window == window_entity)
+            .last()
+            .map(|v| v.position * window.scale_factor());
+
+        if let Some((held, mut drag_history)) = held {
+            if keybindings.mouse_pressed(Action::Grab, &mouse_input) {
+                if let Some(cursor_position) = cursor_position {
+                    position.0 += cursor_position - held.offset;
+                }
+
+                velocity.0 = Vec2::ZERO;
+                drag_history.push(time.elapsed_secs_f64(), position.0);
+            } else {
+                // Slow releases barely move the cube baby, while fast flicks throw it.
+                velocity.0 =
+                    (drag_history.velocity(THROW_SAMPLE_WINDOW) * THROW_SCALE).clamp_length_max(THROW_MAX_SPEED);
+
+                commands.entity(entity).remove::<(Held, DragHistory)>();
+
+                // Reload the display if the cube baby was dropped onto another one.
+                if !display_properties.contains(position.0 + (display_properties.window_extent() / 2.0)) {
+                    display_state.set(LoadingState::loading());
+                }
+            }
+        } else if keybindings.mouse_just_pressed(Action::Grab, &mouse_input) {
+            // Only the window under the cursor knows where it is, so only that window's cube baby is picked up.
+            if let Some(offset) = window.physical_cursor_position() {
+                let mut drag_history = DragHistory::default();
+
+                drag_history.push(time.elapsed_secs_f64(), position.0);
+
+                // Being picked up ends any edge patrol, just like a push does.
+                commands.entity(entity).insert((Held { offset }, drag_history)).remove::<EdgePatrolling>();
+
+                velocity.0 = Vec2::ZERO;
+            }
+        }
+    }
+}
+
+/// Handles exiting the application when the cube baby is double-clicked with the grab button.
+pub fn update_double_click_exit(
+    time: Res<Time<Real>>,
+    settings: Res<Settings>,
+    keybindings: Res<Keybindings>,
+    mut mouse_input: ResMut<ButtonInput<MouseButton>>,
+    query: Single<(&mut Velocity, &mut PushDelays), With<PrimaryBaby>>,
+    mut exit_events: EventWriter<AppExit>,
+    mut last_click: Local<Option<f64>>,
+) {
+    if !keybindings.mouse_just_pressed(Action::Grab, &mouse_input) {
+        return;
+    }
+
+    let now = time.elapsed_secs_f64();
+
+    if !last_click.is_some_and(|v| now - v <= f64::from(settings.window.double_click_exit)) {
+        *last_click = Some(now);
+
+        return;
+    }
+
+    // Swallow the second click and hold off cursor pushes, so that the cube baby is not grabbed or flung on its way
+    // out.
+    let (mut velocity, mut push_delays) = query.into_inner();
+
+    for mouse_button in keybindings.mouse_buttons(Action::Grab) {
+        mouse_input.clear_just_pressed(mouse_button);
+    }
+
+    velocity.0 = Vec2::ZERO;
+    push_delays.mouse = PUSH_DELAY;
+    *last_click = None;
+
+    exit_events.send(AppExit::Success);
+}
+
+/// Handles exiting the application when escape is held, tinting the cube baby as the hold progresses.
+pub fn update_exit_hold(
+    time: Res<Time<Real>>,
+    key_input: Res<ButtonInput<KeyCode>>,
+    keybindings: Res<Keybindings>,
+    mut exit_hold: ResMut<ExitHold>,
+    mut tint: Single<&mut Tint, With<PrimaryBaby>>,
+    mut exit_events: EventWriter<AppExit>,
+) {
+    if keybindings.pressed(Action::Quit, &key_input) {
+        if exit_hold.tick(time.delta()) {
+            exit_events.send(AppExit::Success);
+        }
+    } else if exit_hold.progress() > 0.0 {
+        exit_hold.reset();
+    } else {
+        return;
+    }
+
+    tint.exit_hold = exit_hold.progress();
+}
+
+/// Handles toggling click-through mode when its hotkey is pressed.
+pub fn update_click_through_toggle(
+    key_input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    mut click_through: ResMut<ClickThrough>,
+) {
+    if settings.window.click_through_hotkey.as_ref().is_some_and(|v| v.just_pressed(&key_input)) {
+        click_through.0 = !click_through.0;
+    }
+}
+
+/// Applies click-through mode to the window, and shows it by fading the cube baby.
+pub fn update_click_through(
+    click_through: Res<ClickThrough>,
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+    mut tint: Single<&mut Tint, With<PrimaryBaby>>,
+) {
+    window.cursor_options.hit_test = !click_through.0;
+    tint.opacity = if click_through.0 { CLICK_THROUGH_OPACITY } else { 1.0 };
+}
+
+/// Dims each cube baby while its push delays count down, brightening it back to white once it may be pushed again.
+pub fn update_cooldown_tint(mut query: Query<(&PushDelays, &mut Tint), With<CubeBaby>>) {
+    for (push_delays, mut tint) in &mut query {
+        let remaining = (push_delays.longest() / PUSH_DELAY).clamp(0.0, 1.0) as f32;
+        let cooldown = remaining.powf(COOLDOWN_TINT_EXPONENT);
+
+        // Avoid marking the tint as changed every frame while the cube baby is at rest.
+        tint.set_if_neq(Tint { cooldown, ..*tint });
+    }
+}
+
+/// Tints each cube baby to match its mood.
+pub fn update_mood_tint(mood: Res<Mood>, mut query: Query<&mut Tint, With<CubeBaby>>) {
+    for mut tint in &mut query {
+        tint.set_if_neq(Tint { gloom: mood.gloom, ..*tint });
+    }
+}
+
+/// Applies each cube baby's layered tints to its sprite.
+pub fn post_update_sprite_tint(mut query: Query<(&Tint, &mut Sprite), (With<CubeBaby>, Changed<Tint>)>) {
+    for (tint, mut sprite) in &mut query {
+        sprite.color = tint.color();
+    }
+}
+
+/// Applies each cube baby's layered offsets to its sprite.
+pub fn post_update_sprite_offset(
+    mut query: Query<(&SpriteOffset, &mut Transform), (With<CubeBaby>, Changed<SpriteOffset>)>,
+) {
+    for (offset, mut transform) in &mut query {
+        let translation = offset.translation();
+
+        transform.translation.x = translation.x;
+        transform.translation.y = translation.y;
+    }
+}
+
+/// Handles resizing the cube babies when the mouse wheel is scrolled over any of their windows.
+pub fn update_scroll_resizing(
+    primary_window: Single<Entity, With<PrimaryWindow>>,
+    mut windows: Query<&mut Window>,
+    texture_metadata: Res<TextureMetadata>,
+    mut display_properties: ResMut<DisplayProperties>,
+    mut query: Query<(&mut Position, &mut Transform, Option<&BabyWindow>), With<CubeBaby>>,
+    mut borders: Query<(&mut Sprite, &mut Transform), (With<WindowBorder>, Without<CubeBaby>)>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+) {
+    let lines = mouse_wheel_events
+        .read()
+        .filter(|v| query.iter().any(|(_, _, baby_window)| baby_window.map_or(*primary_window, |w| w.0) == v.window))
+        .map(|v| match v.unit {
+            MouseScrollUnit::Line => v.y,
+            MouseScrollUnit::Pixel => v.y / RESIZE_PIXELS_PER_LINE,
+        })
+        .sum::<f32>();
+
+    let window_size =
+        (display_properties.window_size + (lines * RESIZE_STEP)).clamp(MINIMUM_WINDOW_SIZE, MAXIMUM_WINDOW_SIZE);
+
+    if window_size == display_properties.window_size {
+        return;
+    }
+
+    self::apply_window_size(
+        window_size,
+        *primary_window,
+        &mut display_properties,
+        &texture_metadata,
+        &mut windows,
+        &mut query,
+        &mut borders,
+    );
+}
+
+/// Resizes every cube baby, its window, and the windows' borders to the given size.
+///
+/// Every cube baby shares the same size, and each is resized around its window's center, so that it does not jump
+/// towards its corner.
+pub fn apply_window_size(
+    window_size: f32,
+    primary_window: Entity,
+    display_properties: &mut DisplayProperties,
+    texture_metadata: &TextureMetadata,
+    windows: &mut Query<&mut Window>,
+    babies: &mut Query<(&mut Position, &mut Transform, Option<&BabyWindow>), With<CubeBaby>>,
+    borders: &mut Query<(&mut Sprite, &mut Transform), (With<WindowBorder>, Without<CubeBaby>)>,
+) {
+    let previous_extent = display_properties.window_extent();
+
+    display_properties.window_size = window_size;
+
+    for (mut position, mut transform, baby_window) in babies.iter_mut() {
+        let center = position.0 + (previous_extent / 2.0);
+
+        position.0 = center - (display_properties.window_extent() / 2.0);
+        transform.scale = texture_metadata.sprite_scale(window_size).xyy();
+
+        if let Ok(mut window) = windows.get_mut(baby_window.map_or(primary_window, |v| v.0)) {
+            self::resize_window(&mut window, window_size);
+        }
+    }
+
+    // Each border stays on the side that it was spawned on.
+    let edge = (window_size / 2.0) - 0.5;
+
+    for (mut sprite, mut transform) in borders.iter_mut() {
+        let offset = transform.translation.xy();
+
+        if offset.y == 0.0 {
+            sprite.custom_size = Some(Vec2::new(1.0, window_size));
+            transform.translation.x = offset.x.signum() * edge;
+        } else {
+            sprite.custom_size = Some(Vec2::new(window_size, 1.0));
+            transform.translation.y = offset.y.signum() * edge;
+        }
+    }
+}
+
+/// Teleports the cube baby to the cursor whenever the summon button is clicked anywhere on the desktop.
+///
+/// Only the primary cube baby is summoned, since summoning every cube baby would stack them all under the cursor.
+pub fn update_summoning(
+    settings: Res<Settings>,
+    display_properties: Res<DisplayProperties>,
+    global_cursor: Res<GlobalCursor>,
+    query: Single<(&mut Position, &mut Velocity, &mut PushDelays), (With<PrimaryBaby>, Without<Held>)>,
+    mut was_pressed: Local<bool>,
+) {
+    let pressed = platform::global_mouse_button_pressed(settings.summon.button.into()).unwrap_or(false);
+    let just_pressed = pressed && !*was_pressed;
+
+    *was_pressed = pressed;
+
+    if !just_pressed {
+        return;
+    }
+
+    let Some(cursor_position) = global_cursor.position() else { return };
+    let (mut position, mut velocity, mut push_delays) = query.into_inner();
+
+    let window_extent = display_properties.window_extent();
+    let minimum_position = display_properties.minimum_position();
+    let maximum_position = display_properties.maximum_position() - window_extent;
+
+    let target = cursor_position - (window_extent / 2.0);
+
+    // Summoning near an edge should not push the window off of the display.
+    position.0 = ScreenPos(target.clamp(minimum_position.0, maximum_position.max(minimum_position.0)));
+    velocity.0 = Vec2::ZERO;
+    // The cursor lands on the window, which should not immediately count as a push.
+    push_delays.mouse = PUSH_DELAY;
+}
+
+/// Continuously pushes each cube baby away from the cursor while it is within the repel radius.
+pub fn fixed_update_repelling(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    display_properties: Res<DisplayProperties>,
+    global_cursor: Res<GlobalCursor>,
+    mut query: Query<(&mut Velocity, &Position), (With<CubeBaby>, Without<Held>)>,
+) {
+    let Some(cursor_position) = global_cursor.position() else { return };
+    let repel = &settings.repel;
+
+    for (mut velocity, position) in &mut query {
+        let center = position.0 + (display_properties.window_extent() / 2.0);
+        let offset = coords::physical_to_logical(center - cursor_position, display_properties.scale_factor);
+        let acceleration = push::repel_acceleration(offset, repel.radius, repel.strength, repel.falloff);
+
+        velocity.0 += coords::logical_to_physical(acceleration, display_properties.scale_factor) * time.delta_secs();
+    }
+}
+
+/// Nudges each cube baby out from under the cursor once the cursor has rested on top of it for long enough.
+///
+/// A resting cursor sends no movement events, so it is polled globally instead. The nudge is a gentle, continuous
+/// acceleration rather than a push, so it neither waits for nor resets the push delays.
+pub fn fixed_update_personal_space(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    display_properties: Res<DisplayProperties>,
+    global_cursor: Res<GlobalCursor>,
+    mut query: Query<(&mut Velocity, &Position), (With<CubeBaby>, Without<Held>)>,
+    mut last_cursor_position: Local<Option<ScreenPos>>,
+    mut rested: Local<f32>,
+) {
+    let Some(cursor_position) = global_cursor.position() else { return };
+
+    // Jitter of less than a pixel still counts as resting.
+    if last_cursor_position.is_some_and(|v| v.distance(cursor_position) < 1.0) {
+        *rested += time.delta_secs();
+    } else {
+        *rested = 0.0;
+    }
+
+    *last_cursor_position = Some(cursor_position);
+
+    if *rested < settings.personal_space.rest_delay {
+        return;
+    }
+
+    let window_extent = display_properties.window_extent();
+    let speed = settings.personal_space.speed * display_properties.scale_factor;
+    let acceleration = PERSONAL_SPACE_ACCELERATION * display_properties.sprite_scale();
+
+    for (mut velocity, position) in &mut query {
+        let overlapping =
+            cursor_position.cmpge(position.0.0).all() && cursor_position.cmplt(position.0.0 + window_extent).all();
+
+        if !overlapping {
+            continue;
+        }
+
+        // A cursor exactly at the center has no direction to nudge away from, so nudge upwards.
+        let direction = ((position.0 + (window_extent / 2.0)) - cursor_position).try_normalize().unwrap_or(Vec2::NEG_Y);
+        // Only the speed away from the cursor is topped up, so that the nudge never builds beyond a gentle scoot.
+        let shortfall = (speed - velocity.dot(direction)).max(0.0);
+
+        velocity.0 += direction * shortfall.min(acceleration * time.delta_secs());
+    }
+}
+
+/// Leans each cube baby slightly away from the cursor as it approaches, before it ever touches the window.
+///
+/// This is purely visual, so it only shifts the sprite within its window and never imparts any velocity.
+pub fn update_glancing(
+    time: Res<Time>,
+    display_properties: Res<DisplayProperties>,
+    global_cursor: Res<GlobalCursor>,
+    mut query: Query<(&Position, &mut SpriteOffset, Has<Held>), With<CubeBaby>>,
+) {
+    let window_extent = display_properties.window_extent();
+
+    for (position, mut offset, held) in &mut query {
+        let target = global_cursor.position().filter(|_| !held).map_or(Vec2::ZERO, |cursor_position| {
+            let nearest = cursor_position.0.clamp(position.0.0, position.0.0 + window_extent);
+            let gap = coords::physical_to_logical(nearest - cursor_position.0, display_properties.scale_factor);
+
+            SpriteOffset::glance((position.0.0 + (window_extent / 2.0)) - cursor_position.0, gap.length())
+        });
+
+        let mut glance = offset.glance.lerp(target, (GLANCE_RATE * time.delta_secs()).min(1.0));
+
+        // Settle exactly onto the target, so that the sprite is not shifted by a fraction of a pixel forever.
+        if glance.distance(target) < 0.01 {
+            glance = target;
+        }
+
+        offset.set_if_neq(SpriteOffset { glance, ..*offset });
+    }
+}
+
+/// Handles cycling through the behaviors when the behavior hotkey is pressed.
+pub fn update_behavior_toggle(
+    key_input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    mut behavior_mode: ResMut<BehaviorMode>,
+) {
+    if settings.behavior.hotkey.as_ref().is_some_and(|v| v.just_pressed(&key_input)) {
+        *behavior_mode = behavior_mode.next();
+    }
+}
+
+/// Steers each cube baby away from the cursor while it is within the flee radius.
+///
+/// Once the cursor backs off, the cube baby is left to calm down and slide to a stop.
+pub fn fixed_update_fleeing(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    display_properties: Res<DisplayProperties>,
+    global_cursor: Res<GlobalCursor>,
+    mut query: Query<(&mut Velocity, &Position), (With<CubeBaby>, Without<Held>)>,
+) {
+    let Some(cursor_position) = global_cursor.position() else { return };
+
+    let half_extent = display_properties.window_extent() / 2.0;
+    let to_physical = |v: f32| v * display_properties.scale_factor;
+    let bounds = (
+        display_properties.minimum_position(),
+        display_properties.maximum_position() - display_properties.window_extent(),
+    );
+
+    for (mut velocity, position) in &mut query {
+        let offset =
+            coords::physical_to_logical((position.0 + half_extent) - cursor_position, display_properties.scale_factor);
+
+        if offset.length() >= settings.behavior.flee_radius {
+            continue;
+        }
+
+        let acceleration = steering::flee(
+            position.0,
+            velocity.0,
+            cursor_position - half_extent,
+            bounds,
+            to_physical(FLEE_WALL_DISTANCE * display_properties.sprite_scale()),
+            to_physical(settings.behavior.flee_speed),
+            to_physical(settings.behavior.flee_acceleration),
+        );
+
+        velocity.0 += acceleration * time.delta_secs();
+    }
+}
+
+/// Lazily steers each cube baby towards the cursor, stopping once it is within the follow distance.
+///
+/// Following is briefly suspended after each push, so that a cube baby can still be flicked away.
+pub fn fixed_update_following(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    display_properties: Res<DisplayProperties>,
+    global_cursor: Res<GlobalCursor>,
+    mut query: Query<(Entity, &mut Velocity, &Position), (With<CubeBaby>, Without<Held>)>,
+    mut pushed_events: EventReader<Pushed>,
+    mut suspensions: Local<EntityHashMap<f32>>,
+) {
+    for event in pushed_events.read() {
+        suspensions.insert(event.baby, FOLLOW_SUSPEND_DELAY);
+    }
+
+    // Forget cube babies that have since been despawned, or whose suspension has ended.
+    suspensions.retain(|entity, suspension| {
+        *suspension -= time.delta_secs();
+
+        *suspension > 0.0 && query.contains(*entity)
+    });
+
+    let Some(cursor_position) = global_cursor.position() else { return };
+
+    let sprite_scale = display_properties.sprite_scale();
+    let half_extent = display_properties.window_extent() / 2.0;
+    let follow_distance = settings.behavior.follow_distance * display_properties.scale_factor;
+
+    for (entity, mut velocity, position) in &mut query {
+        let offset = (position.0 + half_extent) - cursor_position;
+
+        // Close enough, so the drag is left to bring the cube baby to a stop beside the cursor.
+        if suspensions.contains_key(&entity) || offset.length() <= follow_distance {
+            continue;
+        }
+
+        let target = (cursor_position - half_extent) + (offset.normalize_or_zero() * follow_distance);
+
+        velocity.0 += steering::arrive(
+            position.0,
+            velocity.0,
+            target,
+            settings.behavior.follow_speed * display_properties.scale_factor,
+            FOLLOW_SLOWING_RADIUS * sprite_scale,
+            FOLLOW_ACCELERATION * sprite_scale,
+        ) * time.delta_secs();
+    }
+}
+
+/// Pulls each cube baby around a circle centered on the cursor.
+///
+/// The orbit decays once the cursor has rested for a while, letting the cube baby drift to a stop, and picks back up
+/// from the cube baby's current angle once the cursor moves again. Each cube baby tracks its own orbit, so that they
+/// keep the spacing that they had when they started orbiting.
+pub fn fixed_update_orbiting(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    display_properties: Res<DisplayProperties>,
+    global_cursor: Res<GlobalCursor>,
+    mut query: Query<(Entity, &mut Velocity, &Position), (With<CubeBaby>, Without<Held>)>,
+    mut orbits: Local<EntityHashMap<steering::Orbit>>,
+) {
+    // Forget cube babies that have since been despawned.
+    orbits.retain(|v, _| query.contains(*v));
+
+    let Some(cursor_position) = global_cursor.position() else { return };
+
+    for (entity, mut velocity, position) in &mut query {
+        self::orbit_around(
+            &settings,
+            &display_properties,
+            time.delta_secs(),
+            cursor_position,
+            orbits.entry(entity).or_default(),
+            position,
+            &mut velocity,
+        );
+    }
+}
+
+/// Pulls a single cube baby around its orbit of the given cursor position.
+fn orbit_around(
+    settings: &Settings,
+    display_properties: &DisplayProperties,
+    delta: f32,
+    cursor_position: ScreenPos,
+    orbit: &mut steering::Orbit,
+    position: &Position,
+    velocity: &mut Velocity,
+) {
+    let half_extent = display_properties.window_extent() / 2.0;
+    let moved = orbit.cursor.is_none_or(|v| v.distance(cursor_position) > ORBIT_MOVE_DISTANCE);
+
+    orbit.cursor = Some(cursor_position);
+
+    if moved {
+        if orbit.engagement == 0.0 {
+            orbit.angle = ((position.0 + half_extent) - cursor_position).to_angle();
+        }
+
+        orbit.idle = 0.0;
+        orbit.engagement = 1.0;
+    } else {
+        orbit.idle += delta;
+
+        if orbit.idle > ORBIT_IDLE_DELAY {
+            orbit.engagement = (orbit.engagement - (delta / ORBIT_DECAY_DURATION)).max(0.0);
+        }
+    }
+
+    if orbit.engagement == 0.0 {
+        return;
+    }
+
+    orbit.angle = (orbit.angle + (settings.behavior.orbit_speed * delta)) % std::f32::consts::TAU;
+
+    let radius = settings.behavior.orbit_radius * display_properties.scale_factor;
+    let target = (cursor_position + (Vec2::from_angle(orbit.angle) * radius)) - half_extent;
+
+    // The edges win over the orbit, so that a cursor near an edge never pulls the cube baby off of the display.
+    let minimum_position = display_properties.minimum_position();
+    let maximum_position = display_properties.maximum_position() - display_properties.window_extent();
+    let target = ScreenPos(target.clamp(minimum_position.0, maximum_position.max(minimum_position.0)));
+
+    let acceleration = steering::spring(position.0, velocity.0, target, ORBIT_STIFFNESS, ORBIT_DAMPING);
+
+    velocity.0 += acceleration * orbit.engagement * delta;
+}
+
+/// Resizes the given window, keeping it locked to its new size.
+pub fn resize_window(window: &mut Window, window_size: f32) {
+    window.resolution.set(window_size, window_size);
+    window.resize_constraints = WindowResizeConstraints {
+        min_width: window_size,
+        min_height: window_size,
+        max_width: window_size,
+        max_height: window_size,
+    };
+}
+
+/// Steers each patrolling cube baby along the patrol route, waiting at each waypoint before continuing.
+pub fn fixed_update_patrolling(
+    time: Res<Time>,
+    patrol_route: Res<PatrolRoute>,
+    display_properties: Res<DisplayProperties>,
+    mut query: Query<
+        (Entity, &mut Velocity, &Position, &mut Patrolling),
+        (With<CubeBaby>, Without<Evading>, Without<Flopped>),
+    >,
+    mut pushed_events: EventReader<Pushed>,
+) {
+    let pushed = pushed_events.read().map(|v| v.baby).collect::<EntityHashSet>();
+
+    for (entity, mut velocity, position, mut patrolling) in &mut query {
+        if pushed.contains(&entity) {
+            patrolling.suspend();
+        }
+
+        if patrolling.is_suspended() {
+            patrolling.suspension.tick(time.delta());
+
+            continue;
+        }
+
+        let Some(target) = patrol_route.target(patrolling.index, &display_properties) else { continue };
+
+        velocity.0 += steering::arrive(
+            position.0,
+            velocity.0,
+            target,
+            PATROL_SPEED * display_properties.sprite_scale(),
+            PATROL_SLOWING_RADIUS * display_properties.sprite_scale(),
+            PATROL_ACCELERATION * display_properties.sprite_scale(),
+        ) * time.delta_secs();
+
+        if position.distance(target) <= PATROL_ARRIVAL_RADIUS * display_properties.sprite_scale()
+            && patrolling.dwell.tick(time.delta()).finished()
+        {
+            patrolling.index = patrol_route.next(patrolling.index);
+            patrolling.dwell.reset();
+        }
+    }
+}
+
+/// Steers each idle cube baby slowly along the display edge farthest from the cursor, so that it stays visible but out
+/// of the way.
+///
+/// The edge is chosen again every few seconds, and any push hands the cube baby straight back to its usual physics.
+/// Cube babies with a patrol route of their own follow that instead.
+pub fn fixed_update_edge_patrolling(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    display_properties: Res<DisplayProperties>,
+    global_cursor: Res<GlobalCursor>,
+    mut query: Query<
+        (Entity, &mut Velocity, &Position, Option<&mut EdgePatrolling>),
+        (With<CubeBaby>, Without<Patrolling>, Without<Evading>, Without<Held>, Without<Flopped>),
+    >,
+    mut pushed_events: EventReader<Pushed>,
+    mut idle: Local<EntityHashMap<f32>>,
+) {
+    let pushed = pushed_events.read().map(|v| v.baby).collect::<EntityHashSet>();
+    let Some(cursor_position) = global_cursor.position() else { return };
+
+    let sprite_scale = display_properties.sprite_scale();
+    let display_bounds = (display_properties.minimum_position(), display_properties.maximum_position());
+    let window_bounds = (display_bounds.0, display_bounds.1 - display_properties.window_extent());
+    let hysteresis = EDGE_PATROL_HYSTERESIS * display_properties.scale_factor;
+
+    // Forget cube babies that have been despawned.
+    idle.retain(|entity, _| query.contains(*entity));
+
+    for (entity, mut velocity, position, edge_patrolling) in &mut query {
+        if pushed.contains(&entity) {
+            idle.insert(entity, 0.0);
+
+            if edge_patrolling.is_some() {
+                commands.entity(entity).remove::<EdgePatrolling>();
+            }
+
+            continue;
+        }
+
+        let Some(mut edge_patrolling) = edge_patrolling else {
+            let seconds = idle.entry(entity).or_default();
+
+            if velocity.length() > EDGE_PATROL_IDLE_SPEED * sprite_scale {
+                *seconds = 0.0;
+            } else {
+                *seconds += time.delta_secs();
+            }
+
+            if *seconds >= settings.edge_patrol.idle_delay {
+                let edge = steering::far_edge(cursor_position, display_bounds, None, hysteresis);
+
+                commands.entity(entity).insert(EdgePatrolling::new(edge));
+            }
+
+            continue;
+        };
+
+        if edge_patrolling.evaluation.tick(time.delta()).just_finished() {
+            let edge = steering::far_edge(cursor_position, display_bounds, Some(edge_patrolling.edge), hysteresis);
+
+            edge_patrolling.edge = edge;
+        }
+
+        let (target, heading) = steering::edge_waypoint(
+            position.0,
+            edge_patrolling.edge,
+            window_bounds,
+            edge_patrolling.heading,
+            EDGE_PATROL_LOOKAHEAD * sprite_scale,
+        );
+
+        edge_patrolling.heading = heading;
+        velocity.0 += steering::arrive(
+            position.0,
+            velocity.0,
+            target,
+            PATROL_SPEED * sprite_scale,
+            PATROL_SLOWING_RADIUS * sprite_scale,
+            PATROL_ACCELERATION * sprite_scale,
+        ) * time.delta_secs();
+    }
+}
+
+/// Makes every cube baby evade a screen capture whenever any trigger fires.
+pub fn update_evasion_triggers(
+    mut commands: Commands,
+    key_input: Res<ButtonInput<KeyCode>>,
+    display_properties: Res<DisplayProperties>,
+    mut triggers: ResMut<EvasionTriggers>,
+    query: Query<(Entity, &Position), With<CubeBaby>>,
+) {
+    // Poll every trigger, so that none of them miss an edge.
+    let triggered = triggers.iter_mut().fold(false, |triggered, v| v.poll(&key_input) || triggered);
+
+    if !triggered {
+        return;
+    }
+
+    for (entity, position) in &query {
+        let target = evasion::nearest_corner(
+            position.0,
+            display_properties.window_extent(),
+            display_properties.minimum_position(),
+            display_properties.maximum_position(),
+        );
+
+        // Triggering again while already evading restarts the timer.
+        commands.entity(entity).insert(Evading::new(target));
+    }
+}
+
+/// Steers each evading cube baby into its corner and freezes it there, returning to normal once time runs out or it is
+/// pushed.
+pub fn fixed_update_evasion(
+    mut commands: Commands,
+    time: Res<Time>,
+    display_properties: Res<DisplayProperties>,
+    mut query: Query<(Entity, &mut Velocity, &Position, &mut Evading), With<CubeBaby>>,
+    mut pushed_events: EventReader<Pushed>,
+) {
+    let pushed = pushed_events.read().map(|v| v.baby).collect::<EntityHashSet>();
+    let sprite_scale = display_properties.sprite_scale();
+
+    for (entity, mut velocity, position, mut evading) in &mut query {
+        // Explicit pushes from the user always take priority.
+        if pushed.contains(&entity) || evading.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Evading>();
+
+            continue;
+        }
+
+        if position.distance(evading.target) <= EVASION_ARRIVAL_RADIUS * sprite_scale {
+            velocity.0 = Vec2::ZERO;
+        } else {
+            velocity.0 += steering::arrive(
+                position.0,
+                velocity.0,
+                evading.target,
+                EVASION_SPEED * sprite_scale,
+                EVASION_SLOWING_RADIUS * sprite_scale,
+                EVASION_ACCELERATION * sprite_scale,
+            ) * time.delta_secs();
+        }
+    }
+}
+
+/// Moves each cube baby according to its velocity, resolving any edges that it passes.
+///
+/// This runs on a fixed timestep, so that the cube baby moves identically regardless of the display's refresh rate.
+pub fn fixed_update_window_movement(
+    time: Res<Time>,
+    simulation_speed: Res<SimulationSpeed>,
+    settings: Res<Settings>,
+    gravity: Res<Gravity>,
+    mut query: Query<
+        (Entity, &mut Velocity, &mut Position, &mut Distance, &mut FixedPositions, &mut CornerTrap, Has<Held>),
+        With<CubeBaby>,
+    >,
+    display_properties: Res<DisplayProperties>,
+    wind: Option<Res<Wind>>,
+    mood: Res<Mood>,
+    foreground_window: Option<Res<ForegroundWindow>>,
+    mut bounce_events: EventWriter<WallBounce>,
+) {
+    let sprite_scale = display_properties.sprite_scale();
+    let max_speed = settings.push.max_speed * (display_properties.window_size / WINDOW_SIZE);
+    let max_speed = coords::logical_to_physical(Vec2::X * max_speed, display_properties.scale_factor).x;
+    let rest_speed = settings.push.rest_speed * (display_properties.window_size / WINDOW_SIZE);
+    let rest_speed = coords::logical_to_physical(Vec2::X * rest_speed, display_properties.scale_factor).x;
+    let window_extent = display_properties.window_extent();
+
+    let surroundings = physics::Surroundings {
+        delta: physics::step_delta(time.delta_secs(), simulation_speed.0),
+        minimum_position: display_properties.minimum_position(),
+        maximum_position: display_properties.maximum_position() - window_extent,
+        window_extent,
+        edges: settings.bounds.edges,
+        restitution: settings.bounds.restitution,
+        max_speed,
+        // The wind only ever pushes gently, so bringing the cube babies to rest would keep it from ever moving them.
+        rest_speed: if wind.is_some_and(|v| v.force != Vec2::ZERO) { 0.0 } else { rest_speed },
+        slide_drag: SLIDE_DRAG * sprite_scale * mood.drag,
+        wall_friction: settings.bounds.wall_friction * sprite_scale,
+        ground: gravity.0.then(|| physics::Ground {
+            restitution: settings.gravity.restitution,
+            friction: settings.gravity.ground_friction * sprite_scale,
+            rest_speed: GRAVITY_REST_SPEED * sprite_scale,
+        }),
+        foreground_window: foreground_window.as_deref().copied().unwrap_or_default(),
+        center_position: display_properties.center_position() - (window_extent / 2.0),
+        escape_speed: CORNER_ESCAPE_SPEED * sprite_scale,
+    };
+
+    for (baby, mut velocity, mut position, mut distance, mut fixed_positions, mut corner_trap, held) in &mut query {
+        let step = physics::step(&surroundings, &mut position.0, &mut velocity.0, &mut corner_trap, held);
+
+        distance.0 += step.travelled;
+
+        for (side, speed) in step.bounces.into_iter().flatten() {
+            bounce_events.send(WallBounce { baby, side, speed });
+        }
+
+        *fixed_positions = FixedPositions { previous: step.previous, current: position.0 };
+    }
+}
+
+/// Updates each cube baby's window position, smoothed between its last two fixed timesteps.
+pub fn update_window_movement(
+    primary_window: Single<Entity, With<PrimaryWindow>>,
+    mut windows: Query<&mut Window>,
+    fixed_time: Res<Time<Fixed>>,
+    throttle: Res<PresentationThrottle>,
+    query: Query<(&Position, &FixedPositions, Option<&BabyWindow>), With<CubeBaby>>,
+) {
+    // The physics are always integrated, but only the latest position is presented.
+    if !throttle.is_ready() {
+        return;
+    }
+
+    let fraction = fixed_time.overstep_fraction();
+
+    for (position, fixed_positions, baby_window) in &query {
+        // A position set outside of the fixed timestep, such as when dragged or centered, is presented as-is.
+        let position = if position.0 == fixed_positions.current {
+            fixed_positions.previous.lerp(fixed_positions.current, fraction)
+        } else {
+            position.0
+        };
+
+        // Extra cube babies have windows of their own, while the primary cube baby follows the primary window.
+        let window_entity = baby_window.map_or(*primary_window, |v| v.0);
+
+        // Only whole pixels are presented, so the window is left untouched until the rounded position changes.
+        let position = WindowPosition::At(position.to_physical());
+
+        let Ok(mut window) = windows.get_mut(window_entity) else { continue };
+
+        if window.position != position {
+            window.position = position;
+        }
+    }
+}
+
+/// Occasionally pushes each cube baby in a random direction, as long as the user is not interacting with it.
+pub fn update_wandering(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    display_properties: Res<DisplayProperties>,
+    mood: Res<Mood>,
+    mut rng: ResMut<Rng>,
+    mut pushed_events: EventReader<Pushed>,
+    mut query: Query<
+        (Entity, &mut Velocity, &mut WanderDelay, Has<Held>, Has<Petting>, Has<EdgePatrolling>, Has<Flopped>),
+        With<CubeBaby>,
+    >,
+) {
+    let pushed = pushed_events.read().map(|v| v.baby).collect::<EntityHashSet>();
+
+    for (entity, mut velocity, mut wander_delay, held, petting, edge_patrolling, flopped) in &mut query {
+        let mut delay =
+            wander_delay.unwrap_or_else(|| settings.wander.random_delay(&mut rng) / mood.wander) - time.delta_secs();
+
+        // Wandering right after a push would feel like the cube baby is fighting the user.
+        if pushed.contains(&entity) {
+            delay = delay.max(WANDER_PUSH_GRACE);
+        }
+
+        wander_delay.0 = Some(delay);
+
+        if delay > 0.0 || held || petting || edge_patrolling || flopped {
+            continue;
+        }
+
+        wander_delay.0 = Some(settings.wander.random_delay(&mut rng) / mood.wander);
+
+        let impulse = settings.wander.random_impulse(&mut rng);
+
+        velocity.0 += coords::logical_to_physical(impulse, display_properties.scale_factor);
+    }
+}
+
+/// Combines every mood modifier into the cube babies' mood.
+pub fn update_mood(mood_modifiers: Res<MoodModifiers>, mut mood: ResMut<Mood>) {
+    mood.set_if_neq(mood_modifiers.mood());
+}
+
+/// Tracks how long the session has lasted, tiring the cube babies out as it goes on.
+///
+/// Once the session has lasted too long, the cube babies flop down to sleep near the edge of the display and a break is
+/// suggested. A push or grab wakes a cube baby up for a while, so that sleeping never fights the user.
+pub fn update_session_tiredness(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    settings: Res<Settings>,
+    display_properties: Res<DisplayProperties>,
+    mut mood_modifiers: ResMut<MoodModifiers>,
+    query: Query<(Entity, &Position, Has<Flopped>, Has<Held>), With<CubeBaby>>,
+    mut pushed_events: EventReader<Pushed>,
+    mut last_stage: Local<Option<tiredness::SessionStage>>,
+    mut woken: Local<EntityHashMap<f64>>,
+) {
+    let now = time.elapsed_secs_f64();
+    let hours = (now / 3600.0) as f32;
+    let (tired_after, asleep_after) = (settings.tiredness.tired_after, settings.tiredness.asleep_after);
+    let stage = tiredness::stage(hours, tired_after, asleep_after);
+
+    mood_modifiers
+        .set_if_neq(MoodModifiers { tiredness: tiredness::mood(hours, tired_after, asleep_after), ..*mood_modifiers });
+
+    if last_stage.replace(stage).is_some_and(|v| v != stage) {
+        info!("the cube babies are now {stage:?} after {hours:.1} hours");
+
+        if stage == tiredness::SessionStage::Asleep {
+            notification::show("Time for a break?", "The cube baby has fallen asleep. Maybe you should rest too!");
+        }
+    }
+
+    let pushed = pushed_events.read().map(|v| v.baby).collect::<EntityHashSet>();
+
+    // Forget cube babies that have been despawned.
+    woken.retain(|entity, _| query.contains(*entity));
+
+    for (entity, position, flopped, held) in &query {
+        if pushed.contains(&entity) || held {
+            woken.insert(entity, now);
+
+            if flopped {
+                commands.entity(entity).remove::<Flopped>();
+            }
+
+            continue;
+        }
+
+        let awake = woken.get(&entity).is_some_and(|v| now - v < TIRED_WAKE_DURATION);
+
+        if stage != tiredness::SessionStage::Asleep || flopped || awake {
+            continue;
+        }
+
+        let target = tiredness::flop_target(
+            position.0,
+            display_properties.window_extent(),
+            display_properties.minimum_position(),
+            display_properties.maximum_position(),
+        );
+
+        commands.entity(entity).insert(Flopped { target });
+    }
+}
+
+/// Steers each sleeping cube baby to where it flopped down, settling there until it is woken up.
+pub fn fixed_update_flopping(
+    time: Res<Time>,
+    display_properties: Res<DisplayProperties>,
+    mut query: Query<(&mut Velocity, &Position, &Flopped), (With<CubeBaby>, Without<Held>)>,
+) {
+    let sprite_scale = display_properties.sprite_scale();
+
+    for (mut velocity, position, flopped) in &mut query {
+        velocity.0 += steering::arrive(
+            position.0,
+            velocity.0,
+            flopped.target,
+            PATROL_SPEED * sprite_scale,
+            PATROL_SLOWING_RADIUS * sprite_scale,
+            PATROL_ACCELERATION * sprite_scale,
+        ) * time.delta_secs();
+    }
+}
+
+/// Applies the latest weather report to the cube babies' mood, and blows the wind along with the weather's wind.
+#[cfg(feature = "weather")]
+pub fn update_weather(
+    settings: Res<Settings>,
+    receiver: Res<weather::WeatherReceiver>,
+    mut mood_modifiers: ResMut<MoodModifiers>,
+    wind: Option<ResMut<Wind>>,
+) {
+    let Some(report) = receiver.latest() else { return };
+
+    debug!("received weather report: {report:?}");
+
+    mood_modifiers.set_if_neq(MoodModifiers { weather: weather::mood(report.code), ..*mood_modifiers });
+
+    // The wind keeps shifting on its own afterwards, so the weather only ever sets where it is heading.
+    if let Some(mut wind) = wind {
+        wind.target = report.wind_heading() * report.wind_strength() * settings.wind.strength;
+    }
+}
+
+/// Shifts the wind, and pushes every cube baby that is not held along with it.
+pub fn fixed_update_wind(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    display_properties: Res<DisplayProperties>,
+    mut rng: ResMut<Rng>,
+    mut wind: ResMut<Wind>,
+    mut query: Query<&mut Velocity, (With<CubeBaby>, Without<Held>)>,
+) {
+    let strength = settings.wind.strength;
+
+    wind.shift(&mut rng, strength * settings.wind.variation * time.delta_secs(), strength);
+
+    let acceleration = coords::logical_to_physical(wind.force, display_properties.scale_factor);
+
+    for mut velocity in &mut query {
+        velocity.0 += acceleration * time.delta_secs();
+    }
+}
+
+/// Gently pulls every slow cube baby into the nearest corner of the display, if one is close enough.
+///
+/// Snapping is briefly suspended after each push, so that a parked cube baby can be knocked free with any push.
+pub fn fixed_update_corner_snapping(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    display_properties: Res<DisplayProperties>,
+    mut query: Query<(&mut Velocity, &Position, &CornerTrap), (With<CubeBaby>, Without<Held>)>,
+    mut pushed_events: EventReader<Pushed>,
+    mut suspension: Local<f32>,
+) {
+    if pushed_events.read().count() > 0 {
+        *suspension = CORNER_SNAP_SUSPEND_DELAY;
+    }
+
+    if *suspension > 0.0 {
+        *suspension -= time.delta_secs();
+
+        return;
+    }
+
+    let bounds = (
+        display_properties.minimum_position(),
+        display_properties.maximum_position() - display_properties.window_extent(),
+    );
+    let radius = settings.corner_snap.radius * display_properties.scale_factor;
+    let capture_speed = settings.corner_snap.capture_speed * display_properties.scale_factor;
+    let force = settings.corner_snap.force * display_properties.scale_factor;
+
+    for (mut velocity, position, corner_trap) in &mut query {
+        // A cube baby escaping a corner that it was stuck in should not be pulled straight back in.
+        if corner_trap.is_escaping() {
+            continue;
+        }
+
+        if let Some(pull) = steering::corner_pull(position.0, velocity.0, bounds, radius, capture_speed, force) {
+            velocity.0 += pull * time.delta_secs();
+        }
+    }
+}
+
+/// Periodically polls the bounds of the foreground window, ignoring any that are too small to land on.
+pub fn fixed_update_foreground_window(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    display_properties: Res<DisplayProperties>,
+    mut foreground_window: ResMut<ForegroundWindow>,
+    mut poll_delay: Local<f32>,
+) {
+    *poll_delay -= time.delta_secs();
+
+    if *poll_delay > 0.0 {
+        return;
+    }
+
+    *poll_delay = FOREGROUND_WINDOW_POLL_DELAY;
+
+    let minimum_size = settings.bounds.foreground_window_minimum_size * display_properties.scale_factor;
+    let bounds = platform::foreground_window_bounds()
+        .map(|(position, size)| (ScreenPos::from_physical(position), size.as_vec2()))
+        .filter(|(_, size)| size.cmpge(Vec2::splat(minimum_size)).all());
+
+    foreground_window.set_if_neq(ForegroundWindow(bounds));
+}
+
+/// Handles toggling gravity when its hotkey is pressed.
+pub fn update_gravity_toggle(
+    key_input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    mut gravity: ResMut<Gravity>,
+) {
+    if settings.gravity.hotkey.as_ref().is_some_and(|v| v.just_pressed(&key_input)) {
+        gravity.0 = !gravity.0;
+    }
+}
+
+/// Pulls every cube baby that is not held down towards the bottom of the display, up to the terminal speed.
+///
+/// Cube babies resting on the floor are left alone, so that they settle instead of bouncing forever.
+pub fn fixed_update_gravity(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    display_properties: Res<DisplayProperties>,
+    foreground_window: Option<Res<ForegroundWindow>>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Velocity, &Position, Has<Grounded>), (With<CubeBaby>, Without<Held>)>,
+) {
+    let window_extent = display_properties.window_extent();
+    let floor = (display_properties.maximum_position() - window_extent).y;
+    let foreground_window = foreground_window.as_deref().copied().unwrap_or_default();
+    let rest_speed = GRAVITY_REST_SPEED * display_properties.sprite_scale();
+    let acceleration = settings.gravity.acceleration * display_properties.scale_factor;
+    let terminal_speed = settings.gravity.terminal_speed * display_properties.scale_factor;
+
+    for (entity, mut velocity, position, was_grounded) in &mut query {
+        let supported = position.y >= floor || foreground_window.supports(position.0, window_extent);
+        let grounded = supported && velocity.y.abs() < rest_speed;
+
+        velocity.y =
+            if grounded { 0.0 } else { physics::fall(velocity.y, acceleration, terminal_speed, time.delta_secs()) };
+
+        if grounded && !was_grounded {
+            commands.entity(entity).insert(Grounded).remove::<DoubleJumped>();
+        } else if !grounded && was_grounded {
+            commands.entity(entity).remove::<Grounded>();
+        }
+    }
+}
+
+/// Makes the cube baby whose window is focused jump when the knock key is pressed while it rests on the floor.
+///
+/// Presses in mid-air do nothing, unless double jumps are enabled and the cube baby has not yet used its one.
+pub fn update_jumping(
+    mut commands: Commands,
+    button_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    keybindings: Res<Keybindings>,
+    settings: Res<Settings>,
+    display_properties: Res<DisplayProperties>,
+    mut rng: ResMut<Rng>,
+    primary_window: Single<Entity, With<PrimaryWindow>>,
+    primary_baby: Single<Entity, With<PrimaryBaby>>,
+    windows: Query<&Window>,
+    mut query: Query<
+        (Entity, &mut Velocity, &mut PushDelays, Has<Grounded>, Has<DoubleJumped>, Option<&BabyWindow>),
+        (With<CubeBaby>, Without<Held>),
+    >,
+    mut pushed_events: EventWriter<Pushed>,
+) {
+    let focused_baby = self::focused_baby(
+        *primary_window,
+        *primary_baby,
+        &windows,
+        query.iter().map(|(entity, .., baby_window)| (entity, baby_window)),
+    );
+    // A held cube baby cannot jump.
+    let Ok((entity, mut velocity, mut push_delays, grounded, double_jumped, _)) = query.get_mut(focused_baby) else {
+        return;
+    };
+
+    let just_pressed = keybindings.just_pressed(Action::Knock, &button_input)
+        || keybindings.mouse_just_pressed(Action::Knock, &mouse_input);
+
+    if !just_pressed || push_delays.keyboard > 0.0 {
+        return;
+    }
+
+    if !grounded {
+        if !settings.gravity.double_jump || double_jumped {
+            return;
+        }
+
+        commands.entity(entity).insert(DoubleJumped);
+    }
+
+    let spread = ((rng.f32() * 2.0) - 1.0) * JUMP_SPREAD * display_properties.sprite_scale();
+    let jump = Vec2::new(spread, -settings.gravity.jump_speed());
+    let jump = coords::logical_to_physical(jump, display_properties.scale_factor);
+    // Any falling speed is replaced, so that a double jump rises just as high as the first.
+    let impulse = Vec2::new(jump.x, jump.y - velocity.y);
+
+    velocity.0 += impulse;
+    push_delays.keyboard = PUSH_DELAY;
+    pushed_events.send(Pushed { baby: entity, impulse });
+}
+
+/// Starts petting each cube baby once the cursor has rested on its window for long enough, and wiggles it while
+/// petted.
+///
+/// Moving the cursor quickly or off of the window stops the petting immediately.
+pub fn update_petting(
+    mut commands: Commands,
+    time: Res<Time>,
+    primary_window: Single<Entity, With<PrimaryWindow>>,
+    windows: Query<&Window>,
+    mut query: Query<
+        (Entity, &mut Transform, Option<&mut Petting>, Has<Held>, Option<&BabyWindow>),
+        (With<CubeBaby>, Without<Onboarding>),
+    >,
+    mut dwells: Local<EntityHashMap<(Option<Vec2>, f32)>>,
+) {
+    // Forget cube babies that have since been despawned.
+    dwells.retain(|v, _| query.contains(*v));
+
+    for (entity, mut transform, petting, held, baby_window) in &mut query {
+        let window_entity = baby_window.map_or(*primary_window, |v| v.0);
+        let cursor_position = windows.get(window_entity).ok().and_then(Window::cursor_position);
+        let (last_cursor_position, dwell) = dwells.entry(entity).or_default();
+        let previous_cursor_position = std::mem::replace(last_cursor_position, cursor_position);
+
+        let resting = cursor_position
+            .zip(previous_cursor_position)
+            .is_some_and(|(v, w)| v.distance(w) < PET_MAX_SPEED * time.delta_secs());
+
+        if held || !resting {
+            *dwell = 0.0;
+
+            if petting.is_some() {
+                transform.rotation = Quat::IDENTITY;
+
+                commands.entity(entity).remove::<Petting>();
+            }
+
+            continue;
+        }
+
+        *dwell += time.delta_secs();
+
+        if let Some(mut petting) = petting {
+            petting.elapsed += time.delta_secs();
+
+            transform.rotation = Quat::from_rotation_z((petting.elapsed * PET_WIGGLE_RATE).sin() * PET_WIGGLE_ANGLE);
+        } else if *dwell >= PET_DWELL_DURATION {
+            commands.entity(entity).insert(Petting::default());
+        }
+    }
+}
+
+/// Advances the presentation throttle.
+pub fn update_presentation_throttle(time: Res<Time>, mut throttle: ResMut<PresentationThrottle>) {
+    throttle.tick(time.delta());
+}
+
+/// Updates each sprite's atlas index to make its cube baby rotate as it moves.
+///
+/// A flicked cube baby spins faster until its flick wears off.
+pub fn update_sprite_rotation(
+    mut commands: Commands,
+    time: Res<Time>,
+    throttle: Res<PresentationThrottle>,
+    settings: Res<Settings>,
+    display_properties: Res<DisplayProperties>,
+    mut query: Query<(Entity, &mut Sprite, &mut Distance, &Velocity, Option<&mut Flicked>), With<CubeBaby>>,
+) {
+    for (entity, mut sprite, mut distance, velocity, flicked) in &mut query {
+        let speed = coords::physical_to_logical(velocity.0, display_properties.scale_factor).length();
+        let mut spin_distance =
+            SLIDE_SPIN_DISTANCE * display_properties.sprite_scale() / settings.spin.multiplier(speed);
+
+        if let Some(mut flicked) = flicked {
+            if flicked.timer.tick(time.delta()).finished() {
+                commands.entity(entity).remove::<Flicked>();
+            } else {
+                spin_distance /= FLICK_SPIN_MULTIPLIER;
+            }
+        }
+
+        if !throttle.is_ready() {
+            continue;
+        }
+
+        if distance.0 >= spin_distance {
+            let texture_atlas = sprite.texture_atlas.as_mut().expect("missing texture atlas");
+
+            texture_atlas.index = distance.spin(texture_atlas.index, spin_distance, velocity.0);
+        }
+    }
+}
+
+/// Moves between quality tiers based off of the average frame time, and applies the current tier.
+pub fn update_quality_governor(
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+    time: Res<Time<Real>>,
+    diagnostics: Res<DiagnosticsStore>,
+    mut governor: ResMut<QualityGovernor>,
+    mut winit_settings: ResMut<WinitSettings>,
+) {
+    // Diagnostics are recorded in milliseconds.
+    let frame_time = diagnostics.get(&FrameTimeDiagnosticsPlugin::FRAME_TIME).and_then(|v| v.average());
+
+    if let Some(frame_time) = frame_time {
+        let previous_tier = governor.tier();
+
+        if let Some(tier) = governor.observe(frame_time / 1000.0, time.delta_secs_f64()) {
+            info!("changed quality tier from {previous_tier:?} to {tier:?} (average frame time {frame_time:.1}ms)");
+        }
+    }
+
+    let tier = governor.tier();
+
+    if window.present_mode != tier.present_mode() {
+        window.present_mode = tier.present_mode();
+    }
+
+    if winit_settings.focused_mode != tier.update_mode() {
+        winit_settings.focused_mode = tier.update_mode();
+        winit_settings.unfocused_mode = tier.update_mode();
+    }
+}
+
+/// Squashes each cube baby that hits a wall hard enough, by an amount that grows with its impact speed.
+pub fn update_squash_impacts(
+    mut commands: Commands,
+    display_properties: Res<DisplayProperties>,
+    mut bounce_events: EventReader<WallBounce>,
+) {
+    let sprite_scale = display_properties.sprite_scale();
+
+    for bounce in bounce_events.read() {
+        if bounce.speed < SQUASH_MINIMUM_SPEED * sprite_scale {
+            continue;
+        }
+
+        let amount = SQUASH_AMOUNT * (bounce.speed / (SQUASH_FULL_SPEED * sprite_scale)).min(1.0);
+
+        // The cube baby may have been despawned since it bounced.
+        let Some(mut baby) = commands.get_entity(bounce.baby) else { continue };
+
+        baby.try_insert(Squash::new(bounce.side, amount));
+    }
+}
+
+/// Springs each squashed cube baby back to its normal shape.
+pub fn update_squash(
+    mut commands: Commands,
     time: Res<Time>,
-    query: Single<(&mut Velocity, &mut Position, &mut Distance), With<CubeBaby>>,
     display_properties: Res<DisplayProperties>,
+    texture_metadata: Res<TextureMetadata>,
+    mut query: Query<(Entity, &mut Transform, &mut SpriteOffset, &mut Squash), With<CubeBaby>>,
+) {
+    let scale = texture_metadata.sprite_scale(display_properties.window_size).xyy();
+
+    for (entity, mut transform, mut offset, mut squash) in &mut query {
+        // The normal scale is restored exactly, so that repeated squashes never build up any error.
+        if squash.timer.tick(time.delta()).finished() {
+            transform.scale = scale;
+            offset.squash = Vec2::ZERO;
+
+            commands.entity(entity).remove::<Squash>();
+
+            continue;
+        }
+
+        let amount = squash.amount * squash.timer.fraction_remaining();
+        let squash_scale = if squash.side.is_horizontal() {
+            Vec2::new(1.0 - amount, 1.0 + amount)
+        } else {
+            Vec2::new(1.0 + amount, 1.0 - amount)
+        };
+
+        transform.scale = scale * squash_scale.extend(1.0);
+        offset.squash = SpriteOffset::squash(squash.side, amount, display_properties.window_size);
+    }
+}
+
+/// Rumbles every connected gamepad when the cube baby bounces off of a wall.
+pub fn update_bounce_rumble(
+    time: Res<Time<Real>>,
+    settings: Res<Settings>,
+    gamepads: Query<Entity, With<Gamepad>>,
+    mut bounce_events: EventReader<WallBounce>,
+    mut rumble_requests: EventWriter<GamepadRumbleRequest>,
+    mut last_rumble: Local<Option<f64>>,
+) {
+    if gamepads.is_empty() {
+        bounce_events.clear();
+
+        return;
+    }
+
+    let mut speed = 0.0_f32;
+    let mut horizontal = false;
+    let mut vertical = false;
+
+    for event in bounce_events.read() {
+        speed = speed.max(event.speed);
+        horizontal |= event.side.is_horizontal();
+        vertical |= event.side.is_vertical();
+    }
+
+    // Only rumble if there was a bounce, and prevent corner rattles from buzzing continuously.
+    if !(horizontal || vertical) || last_rumble.is_some_and(|v| time.elapsed_secs_f64() - v < RUMBLE_DELAY) {
+        return;
+    }
+
+    let Some((intensity, duration)) = rumble::bounce_rumble(speed, horizontal && vertical, settings.rumble.intensity)
+    else {
+        return;
+    };
+
+    for gamepad in &gamepads {
+        rumble_requests.send(GamepadRumbleRequest::Add { gamepad, intensity, duration });
+    }
+
+    *last_rumble = Some(time.elapsed_secs_f64());
+}
+
+/// Binds the socket used to communicate with other running instances.
+pub fn startup_bind_peer_socket(mut commands: Commands) {
+    match PeerSocket::bind() {
+        Ok(socket) => {
+            info!("listening for other instances on port {}", socket.port());
+
+            commands.insert_resource(socket);
+        }
+        Err(error) => warn!("unable to listen for other instances: {error}"),
+    }
+}
+
+/// Periodically advertises each cube baby's window to other running instances.
+pub fn update_peer_broadcast(
+    time: Res<Time<Real>>,
+    socket: Res<PeerSocket>,
+    display_properties: Res<DisplayProperties>,
+    query: Query<(Entity, &Position, &Velocity), With<CubeBaby>>,
+    mut last_broadcast: Local<Option<f64>>,
+) {
+    if last_broadcast.is_some_and(|v| time.elapsed_secs_f64() - v < PEER_BROADCAST_DELAY) {
+        return;
+    }
+
+    for (entity, position, velocity) in &query {
+        socket.broadcast(&PeerMessage {
+            id: std::process::id(),
+            baby: entity.index(),
+            position: position.0,
+            extent: display_properties.window_extent(),
+            velocity: velocity.0,
+        });
+    }
+
+    *last_broadcast = Some(time.elapsed_secs_f64());
+}
+
+/// Records advertisements from other running instances, and forgets instances that have gone silent.
+pub fn update_peer_discovery(time: Res<Time<Real>>, socket: Res<PeerSocket>, mut peer_babies: ResMut<PeerBabies>) {
+    let now = time.elapsed_secs_f64();
+
+    for message in socket.receive() {
+        peer_babies.insert(message, now);
+    }
+
+    peer_babies.age_out(now);
+}
+
+/// Bounces each cube baby off of the cube babies of other running instances.
+pub fn update_peer_collision(
+    peer_babies: Res<PeerBabies>,
+    display_properties: Res<DisplayProperties>,
+    mut query: Query<(&mut Position, &mut Velocity), With<CubeBaby>>,
+) {
+    let window_extent = display_properties.window_extent();
+
+    for (mut position, mut velocity) in &mut query {
+        for peer in peer_babies.iter() {
+            if let Some((new_position, new_velocity)) =
+                peers::resolve_collision(position.0, window_extent, velocity.0, peer)
+            {
+                position.0 = new_position;
+                velocity.0 = new_velocity;
+            }
+        }
+    }
+}
+
+/// Pushes each cube baby towards the center of the display if it has been stuck vibrating in place.
+///
+/// Virtual time is used so that time spent paused, while the cube babies cannot move, is never mistaken for being
+/// stuck. Each rescue is announced with a desktop notification, which is rate-limited along with the rescues.
+pub fn update_stuck_rescue(
+    time: Res<Time<Virtual>>,
+    display_properties: Res<DisplayProperties>,
+    mut query: Query<(Entity, &Position, &mut Velocity, Has<Held>), With<CubeBaby>>,
+    mut histories: Local<EntityHashMap<stuck::History>>,
+    mut last_rescues: Local<EntityHashMap<f64>>,
+    mut last_sample: Local<Option<f64>>,
+) {
+    let now = time.elapsed_secs_f64();
+
+    if last_sample.is_some_and(|v| now - v < stuck::SAMPLE_DELAY) {
+        return;
+    }
+
+    *last_sample = Some(now);
+
+    // Forget any cube babies that have since been despawned.
+    histories.retain(|entity, _| query.contains(*entity));
+    last_rescues.retain(|entity, _| query.contains(*entity));
+
+    for (entity, position, mut velocity, held) in &mut query {
+        let history = histories.entry(entity).or_default();
+
+        // A held cube baby follows the cursor rather than its velocity, so it is never stuck.
+        if held {
+            history.clear();
+
+            continue;
+        }
+
+        history.push(stuck::Sample { position: position.0, speed: velocity.length() });
+
+        if !history.is_stuck() || last_rescues.get(&entity).is_some_and(|v| now - v < stuck::RESCUE_DELAY) {
+            continue;
+        }
+
+        warn!("the cube baby appears to be stuck at {:?}, pushing it free", position.0);
+        notification::show("Cube baby rescued", "The cube baby got stuck, so it was pushed back towards the middle.");
+
+        let center = display_properties.center_position() - (display_properties.window_extent() / 2.0);
+
+        velocity.0 = (center - position.0).normalize_or_zero() * stuck::RESCUE_SPEED;
+
+        history.clear();
+        last_rescues.insert(entity, now);
+    }
+}
+
+/// Loads the cube baby's previously saved state, if any was saved.
+pub fn startup_load_saved_state(mut commands: Commands) {
+    match storage::read_data(persistence::FILE_NAME) {
+        Ok(bytes) => match SavedState::from_bytes(&bytes) {
+            Some(saved_state) => commands.insert_resource(saved_state),
+            None => warn!("ignoring the saved state, since it is invalid"),
+        },
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+        Err(error) => warn!("ignoring the saved state, since it could not be read: {error}"),
+    }
+}
+
+/// Periodically saves the cube baby's state, and saves it one last time before exiting.
+///
+/// Only the primary cube baby is saved, since it is the only one that is spawned again on the next launch.
+pub fn post_update_state_persistence(
+    time: Res<Time<Real>>,
+    winit_windows: NonSend<WinitWindows>,
+    primary_windows: Query<Entity, With<PrimaryWindow>>,
+    query: Single<(&Position, &Velocity, &Sprite), With<PrimaryBaby>>,
+    mut exit_events: EventReader<AppExit>,
+    mut last_save: Local<f64>,
 ) {
-    let (mut velocity, mut position, mut distance) = query.into_inner();
+    let exiting = exit_events.read().count() > 0;
+
+    if !exiting && time.elapsed_secs_f64() - *last_save < STATE_SAVE_DELAY {
+        return;
+    }
+
+    let (position, velocity, sprite) = query.into_inner();
+    // The primary window may have already been closed when exiting.
+    let monitor = primary_windows
+        .get_single()
+        .ok()
+        .and_then(|v| winit_windows.get_window(v))
+        .and_then(|v| v.current_monitor())
+        .and_then(|v| v.name());
+    let saved_state = SavedState {
+        position: position.0,
+        velocity: velocity.0,
+        monitor,
+        atlas_index: sprite.texture_atlas.as_ref().map_or(0, |v| v.index),
+    };
+
+    if let Err(error) = storage::write_data(persistence::FILE_NAME, saved_state.to_bytes()) {
+        warn!("failed to save the cube baby's state: {error}");
+    }
+
+    *last_save = time.elapsed_secs_f64();
+}
+
+/// Loads the previously recorded heatmap, starting a new one if none exists.
+pub fn startup_load_heatmap(mut commands: Commands) {
+    let heatmap = match storage::read_data(heatmap::FILE_NAME) {
+        Ok(bytes) => Heatmap::from_bytes(&bytes).unwrap_or_else(|| {
+            warn!("starting a new heatmap, since the recorded heatmap is invalid");
+
+            Heatmap::new()
+        }),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Heatmap::new(),
+        Err(error) => {
+            warn!("starting a new heatmap, since the recorded heatmap could not be read: {error}");
+
+            Heatmap::new()
+        }
+    };
+
+    commands.insert_resource(heatmap);
+}
 
-    let minimum_position = display_properties.minimum_position().as_vec2();
-    let maximum_position = display_properties.maximum_position().as_vec2();
+/// Periodically records the cube baby's position into the heatmap.
+///
+/// Only the primary cube baby is recorded, since extra cube babies come and go and would skew the heatmap towards
+/// wherever they happened to be spawned.
+pub fn update_heatmap_sampling(
+    time: Res<Time<Real>>,
+    display_properties: Res<DisplayProperties>,
+    position: Single<&Position, With<PrimaryBaby>>,
+    mut heatmap: ResMut<Heatmap>,
+    mut last_sample: Local<Option<f64>>,
+) {
+    let now = time.elapsed_secs_f64();
+    let previous = *last_sample.get_or_insert(now);
 
-    if position.x < minimum_position.x {
-        position.x = minimum_position.x;
-        velocity.x = velocity.x.abs();
-    } else if position.x + WINDOW_SIZE > maximum_position.x {
-        position.x = maximum_position.x - WINDOW_SIZE;
-        velocity.x = -velocity.x.abs();
+    if now - previous < HEATMAP_SAMPLE_DELAY {
+        return;
     }
 
-    if position.y < minimum_position.y {
-        position.y = minimum_position.y;
-        velocity.y = velocity.y.abs();
-    } else if position.y + WINDOW_SIZE > maximum_position.y {
-        position.y = maximum_position.y - WINDOW_SIZE;
-        velocity.y = -velocity.y.abs();
+    *last_sample = Some(now);
+
+    let minimum_position = display_properties.minimum_position();
+    let maximum_position = display_properties.maximum_position();
+    let center = position.0 + (display_properties.window_extent() / 2.0);
+
+    heatmap.record((center - minimum_position) / (maximum_position - minimum_position), now - previous);
+}
+
+/// Renders the heatmap to an image in the background when the export hotkey is pressed.
+pub fn update_heatmap_export(key_input: Res<ButtonInput<KeyCode>>, settings: Res<Settings>, heatmap: Res<Heatmap>) {
+    if !settings.heatmap.export_hotkey.as_ref().is_some_and(|v| v.just_pressed(&key_input)) {
+        return;
     }
 
-    let start_position = position.0;
+    let Some(path) = storage::data_path(heatmap::IMAGE_FILE_NAME) else {
+        warn!("unable to export the heatmap without a platform data directory");
 
-    position.0 += velocity.0 * time.delta_secs();
-    velocity.0 *= (1.0 - (SLIDE_DRAG * SPRITE_SCALE * time.delta_secs())).clamp(0.0, 1.0);
-    distance.0 += start_position.distance(position.0);
+        return;
+    };
+    let heatmap = heatmap.clone();
+
+    std::thread::spawn(move || {
+        let result = path.parent().map_or(Ok(()), std::fs::create_dir_all).map_err(image::ImageError::IoError);
+
+        match result.and_then(|()| heatmap.render(heatmap::CELL_SIZE).save(&path)) {
+            Ok(()) => info!("exported heatmap to {}", path.display()),
+            Err(error) => warn!("failed to export heatmap: {error}"),
+        }
+    });
+}
+
+/// Periodically saves the heatmap, and saves it one last time before exiting.
+pub fn post_update_heatmap_persistence(
+    time: Res<Time<Real>>,
+    heatmap: Res<Heatmap>,
+    mut exit_events: EventReader<AppExit>,
+    mut last_save: Local<f64>,
+) {
+    let exiting = exit_events.read().count() > 0;
+
+    if !exiting && time.elapsed_secs_f64() - *last_save < HEATMAP_SAVE_DELAY {
+        return;
+    }
 
-    window.position.set(position.round().as_ivec2());
+    if let Err(error) = storage::write_data(heatmap::FILE_NAME, heatmap.to_bytes()) {
+        warn!("failed to save the heatmap: {error}");
+    }
+
+    *last_save = time.elapsed_secs_f64();
 }
 
-/// Updates the sprite's atlas index to make the cube baby rotate as it moves.
-pub fn update_sprite_rotation(query: Single<(&mut Sprite, &mut Distance), With<CubeBaby>>) {
-    let (mut sprite, mut distance) = query.into_inner();
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+    use crate::evasion::EvasionTrigger;
 
-    if distance.0 >= SLIDE_SPIN_DISTANCE * SPRITE_SCALE {
-        let texture_atlas = sprite.texture_atlas.as_mut().expect("missing texture atlas");
+    /// An evasion trigger that fires whenever a test asks it to.
+    #[derive(Clone, Debug, Default)]
+    struct MockTrigger {
+        /// Whether the trigger fires the next time that it is polled.
+        fire: Arc<AtomicBool>,
+        /// The number of times that the trigger has been polled.
+        polls: Arc<AtomicUsize>,
+    }
+
+    impl EvasionTrigger for MockTrigger {
+        fn poll(&mut self, _: &ButtonInput<KeyCode>) -> bool {
+            self.polls.fetch_add(1, Ordering::Relaxed);
+            self.fire.swap(false, Ordering::Relaxed)
+        }
+    }
+
+    /// Creates an application that evades whenever one of the given triggers fires.
+    fn evasion_application(triggers: &[MockTrigger]) -> App {
+        let mut application = App::new();
+        let display_properties = DisplayProperties {
+            resolution: UVec2::new(1000, 500),
+            use_work_area: false,
+            ..DisplayProperties::default()
+        };
+
+        application.add_plugins(bevy::time::TimePlugin);
+        application.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)));
+        application.add_event::<Pushed>();
+        application.init_resource::<ButtonInput<KeyCode>>();
+        application.insert_resource(display_properties);
+        application.insert_resource(EvasionTriggers(
+            triggers.iter().map(|v| Box::new(v.clone()) as Box<dyn EvasionTrigger>).collect(),
+        ));
+        application.add_systems(Update, (update_evasion_triggers, fixed_update_evasion).chain());
+        application.update();
+
+        application
+    }
+
+    /// Returns the names of every system registered to any of the application's schedules.
+    fn system_names(application: &App) -> Vec<String> {
+        let schedules = application.world().resource::<Schedules>();
+
+        schedules
+            .iter()
+            .flat_map(|(_, schedule)| schedule.graph().systems().map(|(_, system, _)| system.name().to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn safe_mode_registers_only_the_core_systems() {
+        let mut application = App::new();
+        let mut settings = Settings::default();
+
+        // Enable every optional subsystem, so that only safe mode can be what skips them.
+        settings.wander.enabled = true;
+        settings.tiredness.enabled = true;
+        settings.weather.enabled = true;
+        settings.wind.enabled = true;
+        settings.bounds.foreground_window = true;
+        settings.corner_snap.enabled = true;
+
+        application.add_plugins((MinimalPlugins, bevy::state::app::StatesPlugin));
+        build_application(&mut application, settings, SafeMode::new(true));
+
+        let names = system_names(&application);
+        let registered = |name: &str| names.iter().any(|v| v.rsplit("::").next() == Some(name));
+
+        for name in [
+            "update_texture_loading",
+            "fixed_update_mouse_collision",
+            "fixed_update_window_movement",
+            "update_window_movement",
+            "update_spacebar_knocking",
+            "update_safe_mode_exit",
+            "startup_log_safe_mode",
+        ] {
+            assert!(registered(name), "the core system {name} was not registered");
+        }
+
+        for name in [
+            "update_settings_watching",
+            "update_window_icon",
+            "update_onboarding",
+            "fixed_update_global_cursor",
+            "update_wandering",
+            "update_session_tiredness",
+            "update_weather",
+            "fixed_update_wind",
+            "fixed_update_foreground_window",
+            "fixed_update_corner_snapping",
+            "update_summoning",
+            "fixed_update_following",
+            "update_global_hotkey",
+            "update_context_menu",
+            "fixed_update_patrolling",
+            "update_evasion_triggers",
+            "update_extra_babies",
+            "update_bounce_rumble",
+            "update_peer_broadcast",
+            "update_stuck_rescue",
+            "update_heatmap_sampling",
+            "post_update_state_persistence",
+            "update_quality_governor",
+        ] {
+            assert!(!registered(name), "the optional system {name} was registered in safe mode");
+        }
+
+        let safe_mode = application.world().resource::<SafeMode>();
+
+        assert!(safe_mode.skipped().contains(&"peers"));
+        assert!(safe_mode.skipped().contains(&"wandering"));
+    }
+
+    #[test]
+    fn shutdown_signals_exit_immediately() {
+        let mut application = App::new();
+        let shutdown_signal = ShutdownSignal::default();
+
+        application.insert_resource(shutdown_signal.clone());
+        application.add_systems(Update, update_shutdown_signal);
+        application.update();
+
+        assert!(application.world().resource::<Events<AppExit>>().is_empty());
+
+        shutdown_signal.request();
+        application.update();
+
+        assert!(!application.world().resource::<Events<AppExit>>().is_empty());
+    }
+
+    #[test]
+    fn resuming_after_a_long_pause_does_not_burst() {
+        let mut application = App::new();
+
+        application.add_plugins(bevy::time::TimePlugin);
+        application.add_event::<Knock>();
+        application.insert_resource(DisplayProperties::default());
+        application.add_systems(PreUpdate, pre_update_resume_hygiene);
+
+        let spin_distance = SLIDE_SPIN_DISTANCE * DisplayProperties::default().sprite_scale();
+        let baby =
+            application.world_mut().spawn((CubeBaby, Distance(spin_distance * 40.0), WanderDelay(Some(0.1)))).id();
+
+        // Pause mid-motion, and stay paused for an hour.
+        application.world_mut().resource_mut::<Time<Virtual>>().pause();
+        application.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs(60 * 60)));
+        application.update();
+        application.world_mut().send_event(Knock);
+        application.world_mut().resource_mut::<Time<Virtual>>().unpause();
+        application.update();
 
-        texture_atlas.index = (texture_atlas.index + 1) % ATLAS_FRAMES as usize;
+        let time = application.world().resource::<Time<Virtual>>();
 
-        distance.0 -= SLIDE_SPIN_DISTANCE * SPRITE_SCALE;
-        distance.0 %= SLIDE_SPIN_DISTANCE * SPRITE_SCALE;
+        assert!(time.delta() <= time.max_delta());
+        assert_eq!(application.world().get::<Distance>(baby), Some(&Distance(spin_distance)));
+        assert_eq!(application.world().get::<WanderDelay>(baby), Some(&WanderDelay(None)));
+        assert!(application.world().resource::<Events<Knock>>().is_empty());
+    }
+
+    #[test]
+    fn every_evasion_trigger_is_polled() {
+        let triggers = [MockTrigger::default(), MockTrigger::default()];
+        let mut application = evasion_application(&triggers);
+        let baby = application.world_mut().spawn((CubeBaby, Position::default(), Velocity::default())).id();
+
+        application.update();
+
+        assert!(application.world().get::<Evading>(baby).is_none());
+
+        // The first trigger firing must not stop the second from being polled.
+        triggers[0].fire.store(true, Ordering::Relaxed);
+        application.update();
+
+        assert!(application.world().get::<Evading>(baby).is_some());
+        assert!(triggers.iter().all(|v| v.polls.load(Ordering::Relaxed) == 3));
+    }
+
+    #[test]
+    fn evasion_restores_after_ten_seconds() {
+        let trigger = MockTrigger::default();
+        let mut application = evasion_application(std::slice::from_ref(&trigger));
+        let baby = application.world_mut().spawn((CubeBaby, Position::default(), Velocity::default())).id();
+
+        trigger.fire.store(true, Ordering::Relaxed);
+        application.update();
+
+        let started = application.world().resource::<Time>().elapsed_secs();
+
+        for _ in 0 .. 1000 {
+            if application.world().get::<Evading>(baby).is_none() {
+                break;
+            }
+
+            application.update();
+        }
+
+        let elapsed = application.world().resource::<Time>().elapsed_secs() - started;
+
+        assert!((EVASION_DURATION - 0.2 ..= EVASION_DURATION + 0.2).contains(&elapsed), "restored after {elapsed}s");
+    }
+
+    #[test]
+    fn pushes_interrupt_evasion() {
+        let trigger = MockTrigger::default();
+        let mut application = evasion_application(std::slice::from_ref(&trigger));
+        let baby = application.world_mut().spawn((CubeBaby, Position::default(), Velocity::default())).id();
+
+        trigger.fire.store(true, Ordering::Relaxed);
+        application.update();
+        application.world_mut().send_event(Pushed { baby, impulse: Vec2::X });
+        application.update();
+
+        assert!(application.world().get::<Evading>(baby).is_none());
+    }
+
+    #[test]
+    fn spawning_and_despawning_leaks_nothing() {
+        let mut application = App::new();
+
+        application.add_plugins(bevy::time::TimePlugin);
+        application.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)));
+        application.add_event::<MenuAction>();
+        application.insert_resource(Settings::default());
+        application.insert_resource(DisplayProperties::default());
+        application.insert_resource(TextureMetadata {
+            image_handle: Handle::default(),
+            layout_handle: Handle::default(),
+            size: TexturePx::new(256, 32),
+            padding: TexturePx::ZERO,
+            offset: TexturePx::ZERO,
+        });
+        application.init_resource::<ButtonInput<KeyCode>>();
+        application.init_resource::<ExtraBabies>();
+        application.add_systems(Update, (update_extra_babies, update_despawning).chain());
+        application.world_mut().spawn((CubeBaby, PrimaryBaby, Position::default(), Velocity::default()));
+        application.update();
+
+        let baseline = application.world().entities().len();
+
+        for _ in 0 .. 3 {
+            for _ in 0 .. 3 {
+                application.world_mut().send_event(MenuAction::Spawn);
+            }
+
+            application.update();
+
+            let mut windows = application.world_mut().query::<&Window>();
+
+            assert_eq!(windows.iter(application.world()).count(), 3);
+            assert_eq!(application.world().resource::<ExtraBabies>().0.len(), 3);
+
+            for _ in 0 .. 3 {
+                application.world_mut().send_event(MenuAction::Despawn);
+            }
+
+            // Give every cube baby enough time to finish puffing away.
+            for _ in 0 .. 10 {
+                application.update();
+            }
+
+            let mut windows = application.world_mut().query::<&Window>();
+
+            assert_eq!(windows.iter(application.world()).count(), 0);
+            assert!(application.world().resource::<ExtraBabies>().0.is_empty());
+            assert_eq!(application.world().entities().len(), baseline);
+        }
     }
 }
@@ -0,0 +1,138 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright © 2025 Jaxydog
+//
+// This file is part of Desktop Cube Baby.
+//
+// Desktop Cube Baby is free software: you can redistribute it and/or modify it under the terms of the GNU General
+// Public License as published by the Free Software Foundation, either version 3 of the License, or (at your option)
+// any later version.
+//
+// Desktop Cube Baby is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the
+// implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more
+// details.
+//
+// You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
+// see <https://www.gnu.org/licenses/>.
+
+use muda::{CheckMenuItem, ContextMenu as _, Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use winit::raw_window_handle::{HasWindowHandle, RawWindowHandle};
+
+use crate::events::MenuAction;
+use crate::resources::BehaviorMode;
+
+/// The cube baby's native right-click menu.
+///
+/// This must be created on the main thread, and is therefore stored as a non-send resource.
+pub struct ContextMenu {
+    /// The menu itself.
+    menu: Menu,
+    /// The entry that knocks the cube baby.
+    knock: MenuItem,
+    /// The entry that moves the cube baby to the center of the display.
+    center: MenuItem,
+    /// The entry that pauses or resumes the cube baby.
+    pause: MenuItem,
+    /// The entry that toggles whether the cube baby is skittish.
+    skittish: CheckMenuItem,
+    /// The entry that toggles whether the cube baby follows the cursor.
+    follow: CheckMenuItem,
+    /// The entry that toggles whether the cube baby orbits the cursor.
+    orbit: CheckMenuItem,
+    /// The entry that spawns an extra cube baby.
+    spawn: MenuItem,
+    /// The entry that despawns the most recently spawned extra cube baby.
+    despawn: MenuItem,
+    /// The entry that exits the application.
+    quit: MenuItem,
+}
+
+impl ContextMenu {
+    /// Creates a new [`ContextMenu`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the platform failed to create the menu.
+    pub fn new() -> muda::Result<Self> {
+        let menu = Menu::new();
+        let knock = MenuItem::new("Knock", true, None);
+        let center = MenuItem::new("Center on screen", true, None);
+        let pause = MenuItem::new("Pause", true, None);
+        let skittish = CheckMenuItem::new("Skittish", true, false, None);
+        let follow = CheckMenuItem::new("Follow cursor", true, false, None);
+        let orbit = CheckMenuItem::new("Orbit cursor", true, false, None);
+        let spawn = MenuItem::new("Spawn a friend", true, None);
+        let despawn = MenuItem::new("Send a friend home", true, None);
+        let quit = MenuItem::new("Quit", true, None);
+
+        menu.append_items(&[
+            &knock,
+            &center,
+            &pause,
+            &skittish,
+            &follow,
+            &orbit,
+            &PredefinedMenuItem::separator(),
+            &spawn,
+            &despawn,
+            &PredefinedMenuItem::separator(),
+            &quit,
+        ])?;
+
+        Ok(Self { menu, knock, center, pause, skittish, follow, orbit, spawn, despawn, quit })
+    }
+
+    /// Shows the menu at the cursor's position, attached to the given window.
+    ///
+    /// On Windows, this blocks until the menu is dismissed.
+    pub fn show(&self, window: &winit::window::Window) {
+        let Ok(handle) = window.window_handle() else { return };
+
+        // Native menus are dismissed by the platform when they lose focus.
+        match handle.as_raw() {
+            #[cfg(target_os = "windows")]
+            RawWindowHandle::Win32(handle) => unsafe {
+                self.menu.show_context_menu_for_hwnd(handle.hwnd.get(), None);
+            },
+            #[cfg(target_os = "macos")]
+            RawWindowHandle::AppKit(handle) => unsafe {
+                self.menu.show_context_menu_for_nsview(handle.ns_view.as_ptr(), None);
+            },
+            _ => {}
+        }
+    }
+
+    /// Updates the pause entry to reflect whether the cube baby is paused.
+    pub fn set_paused(&self, paused: bool) {
+        self.pause.set_text(if paused { "Resume" } else { "Pause" });
+    }
+
+    /// Updates the behavior entries to reflect the cube baby's current behavior mode.
+    pub fn set_behavior_mode(&self, mode: BehaviorMode) {
+        self.skittish.set_checked(mode == BehaviorMode::Skittish);
+        self.follow.set_checked(mode == BehaviorMode::Following);
+        self.orbit.set_checked(mode == BehaviorMode::Orbiting);
+    }
+
+    /// Returns every action chosen from the menu since this was last called.
+    pub fn actions(&self) -> impl Iterator<Item = MenuAction> + '_ {
+        MenuEvent::receiver().try_iter().filter_map(|v| match v.id() {
+            id if id == self.knock.id() => Some(MenuAction::Knock),
+            id if id == self.center.id() => Some(MenuAction::Center),
+            id if id == self.pause.id() => Some(MenuAction::Pause),
+            id if id == self.skittish.id() => Some(MenuAction::Skittish),
+            id if id == self.follow.id() => Some(MenuAction::Follow),
+            id if id == self.orbit.id() => Some(MenuAction::Orbit),
+            id if id == self.spawn.id() => Some(MenuAction::Spawn),
+            id if id == self.despawn.id() => Some(MenuAction::Despawn),
+            id if id == self.quit.id() => Some(MenuAction::Quit),
+            _ => None,
+        })
+    }
+}
+
+/// Returns `true` if native context menus are supported on this platform.
+#[must_use]
+pub const fn is_supported() -> bool {
+    cfg!(any(target_os = "windows", target_os = "macos"))
+}
@@ -0,0 +1,39 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright © 2025 Jaxydog
+//
+// This file is part of Desktop Cube Baby.
+//
+// Desktop Cube Baby is free software: you can redistribute it and/or modify it under the terms of the GNU General
+// Public License as published by the Free Software Foundation, either version 3 of the License, or (at your option)
+// any later version.
+//
+// Desktop Cube Baby is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the
+// implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more
+// details.
+//
+// You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
+// see <https://www.gnu.org/licenses/>.
+
+use bevy::prelude::*;
+
+/// Shows a desktop notification with the given summary and body.
+///
+/// Without the `notifications` feature, or if the notification could not be shown, the message is logged instead.
+pub fn show(summary: &str, body: &str) {
+    #[cfg(feature = "notifications")]
+    {
+        let result = notify_rust::Notification::new()
+            .appname(crate::branding::APPLICATION_ID)
+            .summary(summary)
+            .body(body)
+            .show();
+
+        match result {
+            Ok(_) => return,
+            Err(error) => warn!("failed to show a notification: {error}"),
+        }
+    }
+
+    info!("{summary}: {body}");
+}
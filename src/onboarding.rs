@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright © 2025 Jaxydog
+//
+// This file is part of Desktop Cube Baby.
+//
+// Desktop Cube Baby is free software: you can redistribute it and/or modify it under the terms of the GNU General
+// Public License as published by the Free Software Foundation, either version 3 of the License, or (at your option)
+// any later version.
+//
+// Desktop Cube Baby is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the
+// implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more
+// details.
+//
+// You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
+// see <https://www.gnu.org/licenses/>.
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use crate::SPRITE_SCALE;
+
+/// The name of the data file that marks the onboarding hint as dismissed.
+pub const DISMISSED_FILE_NAME: &str = "onboarded";
+/// The hint shown to new users.
+pub const HINT_TEXT: &str = "Swipe me with your cursor! Press space to knock me around, or hold escape to quit.";
+/// The size of the hint window, in logical pixels.
+pub const HINT_SIZE: Vec2 = Vec2::new(580.0, 24.0);
+/// The amount of time in seconds spent wiggling.
+pub const WIGGLE_DURATION: f32 = 0.8;
+/// The number of back-and-forth wiggles performed.
+pub const WIGGLE_COUNT: f32 = 3.0;
+/// The largest angle in radians reached while wiggling.
+pub const WIGGLE_ANGLE: f32 = 0.3;
+/// The amount of time in seconds spent performing a lap.
+pub const LAP_DURATION: f32 = 3.0;
+/// The radius of the lap, in logical pixels.
+pub const LAP_RADIUS: f32 = 24.0 * SPRITE_SCALE;
+
+/// Returns the sprite rotation and the logical offset from the spawn point at the given time into the attention
+/// sequence, or [`None`] if the sequence has finished.
+///
+/// The sequence begins with a wiggle in place, followed by a lap that starts and ends at the spawn point.
+pub fn attention_pose(elapsed: f32) -> Option<(f32, Vec2)> {
+    if elapsed < WIGGLE_DURATION {
+        let angle = (elapsed / WIGGLE_DURATION * WIGGLE_COUNT * TAU).sin() * WIGGLE_ANGLE;
+
+        return Some((angle, Vec2::ZERO));
+    }
+
+    let progress = (elapsed - WIGGLE_DURATION) / LAP_DURATION;
+
+    if progress >= 1.0 {
+        return None;
+    }
+
+    // The lap's circle passes through the spawn point, so the sequence ends where it started.
+    let (sin, cos) = (progress * TAU).sin_cos();
+
+    Some((0.0, Vec2::new((cos - 1.0) * LAP_RADIUS, sin * LAP_RADIUS)))
+}
@@ -0,0 +1,190 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright © 2025 Jaxydog
+//
+// This file is part of Desktop Cube Baby.
+//
+// Desktop Cube Baby is free software: you can redistribute it and/or modify it under the terms of the GNU General
+// Public License as published by the Free Software Foundation, either version 3 of the License, or (at your option)
+// any later version.
+//
+// Desktop Cube Baby is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the
+// implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more
+// details.
+//
+// You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
+// see <https://www.gnu.org/licenses/>.
+
+use bevy::prelude::*;
+
+use crate::coords::ScreenPos;
+
+/// The first port that peers may bind to on the loopback interface.
+pub const FIRST_PORT: u16 = 47_310;
+/// The number of ports that peers may bind to, and therefore the maximum number of peers.
+pub const PORT_COUNT: u16 = 8;
+
+/// A message broadcast by each instance to advertise one of its cube babies' windows.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PeerMessage {
+    /// The sending instance's process identifier.
+    pub id: u32,
+    /// The index of the advertised cube baby's entity, which distinguishes the cube babies of a single instance.
+    pub baby: u32,
+    /// The position of the window's top-left corner.
+    pub position: ScreenPos,
+    /// The size of the window, in physical pixels.
+    pub extent: Vec2,
+    /// The cube baby's velocity.
+    pub velocity: Vec2,
+}
+
+impl PeerMessage {
+    /// The bytes that begin every message.
+    pub const MAGIC: [u8; 4] = *b"DCB2";
+    /// The size of an encoded message in bytes.
+    pub const SIZE: usize = 36;
+
+    /// Encodes this message into bytes.
+    #[must_use]
+    pub fn encode(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+        let values = [self.position.x, self.position.y, self.extent.x, self.extent.y, self.velocity.x, self.velocity.y];
+
+        bytes[.. 4].copy_from_slice(&Self::MAGIC);
+        bytes[4 .. 8].copy_from_slice(&self.id.to_le_bytes());
+        bytes[8 .. 12].copy_from_slice(&self.baby.to_le_bytes());
+
+        for (chunk, value) in bytes[12 ..].chunks_exact_mut(4).zip(values) {
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    /// Decodes a message from bytes, returning [`None`] if the bytes are not a valid message.
+    #[must_use]
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::SIZE || bytes[.. 4] != Self::MAGIC {
+            return None;
+        }
+
+        let id = u32::from_le_bytes(bytes[4 .. 8].try_into().ok()?);
+        let baby = u32::from_le_bytes(bytes[8 .. 12].try_into().ok()?);
+        let mut values = [0.0; 6];
+
+        for (value, chunk) in values.iter_mut().zip(bytes[12 ..].chunks_exact(4)) {
+            *value = f32::from_le_bytes(chunk.try_into().ok()?);
+        }
+
+        if !values.iter().all(|v| v.is_finite()) {
+            return None;
+        }
+
+        let [x, y, width, height, velocity_x, velocity_y] = values;
+
+        Some(Self {
+            id,
+            baby,
+            position: ScreenPos::new(x, y),
+            extent: Vec2::new(width, height),
+            velocity: Vec2::new(velocity_x, velocity_y),
+        })
+    }
+}
+
+/// Resolves a collision between the local window and a peer's window.
+///
+/// If the windows overlap, returns the local window's corrected position and velocity. The local window is pushed out
+/// along the axis of least penetration, and exchanges its velocity along that axis with the peer's if the two were
+/// approaching each other. Each instance resolves its own side of the collision, so the result is only approximately
+/// symmetric.
+#[must_use]
+pub fn resolve_collision(
+    position: ScreenPos,
+    extent: Vec2,
+    velocity: Vec2,
+    peer: &PeerMessage,
+) -> Option<(ScreenPos, Vec2)> {
+    let overlap = (position.0 + extent).min(peer.position.0 + peer.extent) - position.0.max(peer.position.0);
+
+    if overlap.x <= 0.0 || overlap.y <= 0.0 {
+        return None;
+    }
+
+    let offset = (position.0 + (extent / 2.0)) - (peer.position.0 + (peer.extent / 2.0));
+    let mut position = position;
+    let mut velocity = velocity;
+
+    if overlap.x < overlap.y {
+        let direction = if offset.x < 0.0 { -1.0 } else { 1.0 };
+
+        position.x += overlap.x * direction;
+
+        // Only exchange velocity if the windows are moving towards each other.
+        if (velocity.x - peer.velocity.x) * direction < 0.0 {
+            velocity.x = peer.velocity.x;
+        }
+    } else {
+        let direction = if offset.y < 0.0 { -1.0 } else { 1.0 };
+
+        position.y += overlap.y * direction;
+
+        if (velocity.y - peer.velocity.y) * direction < 0.0 {
+            velocity.y = peer.velocity.y;
+        }
+    }
+
+    Some((position, velocity))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a message about the given peer's cube baby.
+    fn message(id: u32, baby: u32) -> PeerMessage {
+        PeerMessage {
+            id,
+            baby,
+            position: ScreenPos::new(12.5, -40.0),
+            extent: Vec2::splat(64.0),
+            velocity: Vec2::new(-3.0, 250.0),
+        }
+    }
+
+    #[test]
+    fn messages_round_trip() {
+        let message = message(1234, 5);
+
+        assert_eq!(PeerMessage::decode(&message.encode()), Some(message));
+    }
+
+    #[test]
+    fn messages_of_the_wrong_size_are_rejected() {
+        let bytes = message(1234, 5).encode();
+        let mut oversized = bytes.to_vec();
+
+        oversized.push(0);
+
+        assert_eq!(PeerMessage::decode(&oversized), None);
+        assert_eq!(PeerMessage::decode(&bytes[.. PeerMessage::SIZE - 1]), None);
+        assert_eq!(PeerMessage::decode(&bytes[.. 4]), None);
+        assert_eq!(PeerMessage::decode(&[]), None);
+    }
+
+    #[test]
+    fn messages_with_invalid_contents_are_rejected() {
+        let mut bytes = message(1234, 5).encode();
+
+        bytes[0] = b'X';
+
+        assert_eq!(PeerMessage::decode(&bytes), None);
+
+        let mut bytes = message(1234, 5).encode();
+
+        bytes[12 .. 16].copy_from_slice(&f32::NAN.to_le_bytes());
+
+        assert_eq!(PeerMessage::decode(&bytes), None);
+    }
+}
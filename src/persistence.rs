@@ -0,0 +1,85 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright © 2025 Jaxydog
+//
+// This file is part of Desktop Cube Baby.
+//
+// Desktop Cube Baby is free software: you can redistribute it and/or modify it under the terms of the GNU General
+// Public License as published by the Free Software Foundation, either version 3 of the License, or (at your option)
+// any later version.
+//
+// Desktop Cube Baby is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the
+// implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more
+// details.
+//
+// You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
+// see <https://www.gnu.org/licenses/>.
+
+use bevy::prelude::*;
+
+use crate::coords::ScreenPos;
+
+/// The name of the file that the cube baby's state is persisted to.
+pub const FILE_NAME: &str = "state.bin";
+
+/// The bytes that every persisted state begins with.
+const MAGIC: &[u8; 4] = b"DCBS";
+
+/// The state of the cube baby that is kept across restarts.
+#[derive(Clone, Debug, PartialEq, Resource)]
+pub struct SavedState {
+    /// The position of the window's top-left corner.
+    pub position: ScreenPos,
+    /// The cube baby's velocity, in physical pixels per second.
+    pub velocity: Vec2,
+    /// The name of the monitor that the cube baby was on, if it had one.
+    pub monitor: Option<String>,
+    /// The sprite's atlas index.
+    pub atlas_index: usize,
+}
+
+impl SavedState {
+    /// Encodes the state into bytes for persistence.
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let monitor = self.monitor.as_deref().unwrap_or_default().as_bytes();
+        let mut bytes = Vec::with_capacity(24 + monitor.len());
+
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend(
+            [self.position.x, self.position.y, self.velocity.x, self.velocity.y].iter().flat_map(|v| v.to_le_bytes()),
+        );
+        bytes.extend_from_slice(&(self.atlas_index as u32).to_le_bytes());
+        bytes.extend_from_slice(monitor);
+
+        bytes
+    }
+
+    /// Decodes a persisted state, returning `None` if it is malformed.
+    #[must_use]
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (header, rest) = bytes.split_at_checked(4)?;
+        let (values, rest) = rest.split_at_checked(16)?;
+        let (atlas_index, monitor) = rest.split_at_checked(4)?;
+
+        if header != MAGIC {
+            return None;
+        }
+
+        let values: Vec<f32> = values.chunks_exact(4).map(|v| f32::from_le_bytes([v[0], v[1], v[2], v[3]])).collect();
+
+        if !values.iter().all(|v| v.is_finite()) {
+            return None;
+        }
+
+        let atlas_index = u32::from_le_bytes(atlas_index.try_into().ok()?) as usize;
+        let monitor = std::str::from_utf8(monitor).ok()?;
+
+        Some(Self {
+            position: ScreenPos::new(values[0], values[1]),
+            velocity: Vec2::new(values[2], values[3]),
+            monitor: (!monitor.is_empty()).then(|| monitor.to_string()),
+            atlas_index,
+        })
+    }
+}
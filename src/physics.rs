@@ -0,0 +1,695 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright © 2025 Jaxydog
+//
+// This file is part of Desktop Cube Baby.
+//
+// Desktop Cube Baby is free software: you can redistribute it and/or modify it under the terms of the GNU General
+// Public License as published by the Free Software Foundation, either version 3 of the License, or (at your option)
+// any later version.
+//
+// Desktop Cube Baby is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the
+// implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more
+// details.
+//
+// You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
+// see <https://www.gnu.org/licenses/>.
+
+use bevy::prelude::*;
+
+use crate::bounds::{self, BoundaryBehavior};
+use crate::components::CornerTrap;
+use crate::coords::ScreenPos;
+use crate::events::WallSide;
+use crate::resources::ForegroundWindow;
+use crate::settings::EdgeSettings;
+use crate::{CORNER_ESCAPE_DRAG, MAX_STEP_DELTA, STALL_DELTA};
+
+/// The distance in physical pixels within which a window counts as touching an edge.
+pub const CONTACT_DISTANCE: f32 = 0.5;
+
+/// The surroundings that the cube babies move within during a single fixed timestep.
+///
+/// Every speed is in physical pixels per second, and every drag has already been multiplied by the sprite scale.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Surroundings {
+    /// The length of the timestep, in seconds.
+    pub delta: f32,
+    /// The smallest position of the window's top-left corner.
+    pub minimum_position: ScreenPos,
+    /// The largest position of the window's top-left corner.
+    pub maximum_position: ScreenPos,
+    /// The size of the window, in physical pixels.
+    pub window_extent: Vec2,
+    /// The behavior of each of the display's edges.
+    pub edges: EdgeSettings,
+    /// The fraction of speed kept when bouncing off of an edge.
+    pub restitution: f32,
+    /// The fastest that a cube baby may move.
+    pub max_speed: f32,
+    /// The speed below which a sliding cube baby is brought to rest, or zero to never bring it to rest.
+    pub rest_speed: f32,
+    /// The drag applied whilst sliding.
+    pub slide_drag: f32,
+    /// The drag applied along an edge that a cube baby is pressed against.
+    pub wall_friction: f32,
+    /// The floor that gravity pulls the cube babies onto, if gravity is enabled.
+    pub ground: Option<Ground>,
+    /// The foreground window, which the cube babies may land on top of.
+    pub foreground_window: ForegroundWindow,
+    /// The position that a cube baby escaping a corner is pushed towards.
+    pub center_position: ScreenPos,
+    /// The speed that a cube baby escaping a corner is pushed with.
+    pub escape_speed: f32,
+}
+
+/// The floor that gravity pulls the cube babies onto.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ground {
+    /// The fraction of vertical speed kept when bouncing off of the floor, on top of the edge's own restitution.
+    pub restitution: f32,
+    /// The drag applied to horizontal speed while resting on the floor.
+    pub friction: f32,
+    /// The vertical speed below which a cube baby on the floor comes to rest.
+    pub rest_speed: f32,
+}
+
+/// The outcome of moving a cube baby through a single fixed timestep.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Step {
+    /// The position that the window should be smoothed from, which is the new position if the cube baby wrapped.
+    pub previous: ScreenPos,
+    /// The distance travelled, excluding any wrapping.
+    pub travelled: f32,
+    /// The walls that were hit, along with the speed of each impact.
+    pub bounces: [Option<(WallSide, f32)>; 3],
+}
+
+impl Step {
+    /// Records a hit against the given wall.
+    fn record_bounce(&mut self, side: WallSide, speed: f32) {
+        if let Some(slot) = self.bounces.iter_mut().find(|v| v.is_none()) {
+            *slot = Some((side, speed));
+        }
+    }
+}
+
+/// Returns the number of seconds to integrate over for a frame of the given length at the given simulation speed.
+///
+/// Long frames are clamped, so that a single step can never carry a cube baby arbitrarily far.
+#[inline]
+#[must_use]
+pub fn step_delta(delta: f32, simulation_speed: f32) -> f32 {
+    (delta * simulation_speed).clamp(0.0, MAX_STEP_DELTA)
+}
+
+/// Returns `true` if a frame of the given length means that the application stalled or slept, so that its motion
+/// should be skipped entirely.
+#[inline]
+#[must_use]
+pub fn is_stall(delta: f32) -> bool {
+    delta >= STALL_DELTA
+}
+
+/// Returns the fraction of speed kept after applying the given drag for the given number of seconds.
+///
+/// Exponential decay loses the same fraction of speed over any period of time, however it is divided into steps.
+#[inline]
+#[must_use]
+pub fn decay(drag: f32, delta: f32) -> f32 {
+    (-drag * delta).exp()
+}
+
+/// Returns the vertical velocity after falling under the given acceleration for the given number of seconds.
+///
+/// Only falling is limited to the terminal speed, so that jumps and upwards pushes keep their full strength.
+#[inline]
+#[must_use]
+pub fn fall(velocity: f32, acceleration: f32, terminal_speed: f32, delta: f32) -> f32 {
+    if velocity >= terminal_speed { velocity } else { (velocity + (acceleration * delta)).min(terminal_speed) }
+}
+
+/// Moves a cube baby through a single fixed timestep, resolving any edges that it passes.
+pub fn step(
+    surroundings: &Surroundings,
+    position: &mut ScreenPos,
+    velocity: &mut Vec2,
+    corner_trap: &mut CornerTrap,
+    held: bool,
+) -> Step {
+    let &Surroundings { delta, minimum_position, maximum_position, window_extent, .. } = surroundings;
+    let mut outcome = Step::default();
+
+    // Stacked pushes could otherwise build up enough speed to skip across the display in a single step.
+    *velocity = velocity.clamp_length_max(surroundings.max_speed);
+
+    let start_position = *position;
+    let supported =
+        position.y >= maximum_position.y || surroundings.foreground_window.supports(*position, window_extent);
+    let ground = surroundings.ground.filter(|_| !held);
+    let resting = ground.is_some() && velocity.y == 0.0 && supported;
+
+    *position += *velocity * delta;
+    *velocity *= self::decay(surroundings.slide_drag, delta);
+
+    if let Some(ground) = ground.filter(|_| resting) {
+        velocity.x *= self::decay(ground.friction, delta);
+    }
+
+    // The tail of a slide would otherwise crawl along a pixel at a time, which looks like stuttering.
+    if !held && velocity.length() < surroundings.rest_speed {
+        *velocity = Vec2::ZERO;
+    }
+
+    // Only the integrated movement counts towards the distance, so that wrapping doesn't spin the sprite.
+    outcome.travelled = start_position.distance(*position);
+
+    // Falling onto the foreground window lands on its top edge, as if it were the floor. Anything already below the top
+    // edge is left alone, so that the cube baby simply falls off once the window moves out from under it.
+    if let Some(top) = surroundings.foreground_window.top_edge(*position, window_extent).filter(|_| !held) {
+        let top = top - window_extent.y;
+
+        if start_position.y <= top && position.y > top {
+            let speed = velocity.y;
+
+            position.y = top;
+            velocity.y *= -surroundings.restitution.clamp(0.0, 1.0);
+
+            if let Some(ground) = ground {
+                self::absorb_landing(velocity, ground);
+            }
+
+            outcome.record_bounce(WallSide::Bottom, speed);
+        }
+    }
+
+    // Edges are resolved after moving, so that the resulting position is always within bounds, however far the cube
+    // baby travelled. A held cube baby may be carried past the edges, and onto other displays.
+    let passed_edges =
+        if held { [None; 2] } else { bounds::passed_edges(*position, minimum_position, maximum_position) };
+    let mut wrapped = false;
+
+    for side in passed_edges.into_iter().flatten() {
+        let behavior = surroundings.edges.get(side);
+
+        wrapped |= behavior == BoundaryBehavior::Wrap;
+
+        if let Some(speed) = bounds::resolve_edge(
+            side,
+            behavior,
+            position,
+            velocity,
+            minimum_position,
+            maximum_position,
+            surroundings.restitution,
+        ) {
+            // The floor absorbs some of each bounce under gravity, until the cube baby settles onto it.
+            if let Some(ground) = ground.filter(|_| side == WallSide::Bottom) {
+                self::absorb_landing(velocity, ground);
+            }
+
+            outcome.record_bounce(side, speed);
+        }
+    }
+
+    // Rubbing against an edge slows the cube baby along it, rather than letting it slip by. Contact is checked after
+    // resolving the edges, so that a cube baby resting or sliding along an edge is slowed on every step, not only when
+    // it first hits.
+    if !held && surroundings.wall_friction > 0.0 {
+        let touched_edges = bounds::touched_edges(*position, minimum_position, maximum_position, CONTACT_DISTANCE);
+
+        for side in touched_edges.into_iter().flatten() {
+            if surroundings.edges.get(side) != BoundaryBehavior::Wrap {
+                velocity[usize::from(side.is_horizontal())] *= self::decay(surroundings.wall_friction, delta);
+            }
+        }
+    }
+
+    // Hitting two edges at once, over and over, means that the cube baby is stuck vibrating in a corner.
+    let cornered = !wrapped && passed_edges.iter().all(Option::is_some);
+
+    if corner_trap.tick(delta, cornered) {
+        *velocity += (surroundings.center_position - *position).normalize_or_zero() * surroundings.escape_speed;
+    }
+
+    if corner_trap.is_escaping() {
+        *velocity *= self::decay(CORNER_ESCAPE_DRAG, delta);
+    }
+
+    // Wrapping is a teleport, so the window shouldn't be smoothed across the display.
+    outcome.previous = if wrapped { *position } else { start_position };
+
+    outcome
+}
+
+/// Absorbs some of the speed of a bounce off of the floor, bringing the cube baby to rest once it is slow enough.
+fn absorb_landing(velocity: &mut Vec2, ground: Ground) {
+    velocity.y *= ground.restitution;
+
+    if velocity.y.abs() < ground.rest_speed {
+        velocity.y = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::resources::PresentationThrottle;
+
+    /// Returns the surroundings of a 1920x1080 display with a 64 pixel window and no gravity.
+    fn surroundings(delta: f32) -> Surroundings {
+        Surroundings {
+            delta,
+            minimum_position: ScreenPos::new(0.0, 0.0),
+            maximum_position: ScreenPos::new(1920.0 - 64.0, 1080.0 - 64.0),
+            window_extent: Vec2::splat(64.0),
+            edges: EdgeSettings::default(),
+            restitution: 1.0,
+            max_speed: 8192.0,
+            rest_speed: 8.0,
+            slide_drag: crate::SLIDE_DRAG * crate::SPRITE_SCALE,
+            wall_friction: 0.0,
+            ground: None,
+            foreground_window: ForegroundWindow::default(),
+            center_position: ScreenPos::new(928.0, 508.0),
+            escape_speed: crate::CORNER_ESCAPE_SPEED * crate::SPRITE_SCALE,
+        }
+    }
+
+    /// Steps a cube baby through the given number of timesteps, returning its final position and velocity.
+    fn simulate(surroundings: &Surroundings, position: ScreenPos, velocity: Vec2, steps: usize) -> (ScreenPos, Vec2) {
+        let mut position = position;
+        let mut velocity = velocity;
+        let mut corner_trap = CornerTrap::default();
+
+        for _ in 0 .. steps {
+            self::step(surroundings, &mut position, &mut velocity, &mut corner_trap, false);
+        }
+
+        (position, velocity)
+    }
+
+    #[test]
+    fn identical_runs_are_deterministic() {
+        let surroundings = surroundings(1.0 / 64.0);
+        let start = ScreenPos::new(300.0, 200.0);
+        let velocity = Vec2::new(1234.5, -678.9);
+
+        let first = simulate(&surroundings, start, velocity, 1024);
+        let second = simulate(&surroundings, start, velocity, 1024);
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn decay_is_independent_of_timestep() {
+        let drag = crate::SLIDE_DRAG * crate::SPRITE_SCALE;
+        let whole = self::decay(drag, 1.0);
+        let halves = self::decay(drag, 0.5).powi(2);
+        let sixty_fourths = self::decay(drag, 1.0 / 64.0).powi(64);
+
+        assert!((whole - halves).abs() < 1e-6);
+        assert!((whole - sixty_fourths).abs() < 1e-5);
+    }
+
+    #[test]
+    fn large_deltas_are_clamped() {
+        assert_eq!(self::step_delta(1.0 / 64.0, 1.0), 1.0 / 64.0);
+        assert_eq!(self::step_delta(5.0, 1.0), MAX_STEP_DELTA);
+        assert_eq!(self::step_delta(3600.0, 3.0), MAX_STEP_DELTA);
+        assert_eq!(self::step_delta(f32::INFINITY, 1.0), MAX_STEP_DELTA);
+    }
+
+    #[test]
+    fn distance_scales_linearly_with_simulation_speed() {
+        let travelled = |simulation_speed: f32| {
+            let mut surroundings = surroundings(self::step_delta(1.0 / 64.0, simulation_speed));
+
+            // Without drag, the distance only depends on how much time each step integrates over.
+            surroundings.slide_drag = 0.0;
+            surroundings.rest_speed = 0.0;
+
+            let start = ScreenPos::new(900.0, 500.0);
+            let (position, _) = simulate(&surroundings, start, Vec2::new(256.0, 0.0), 16);
+
+            position.distance(start)
+        };
+        let normal = travelled(1.0);
+
+        assert!((normal - 64.0).abs() < 1e-3);
+
+        for simulation_speed in [0.25, 3.0] {
+            assert!((travelled(simulation_speed) - (normal * simulation_speed)).abs() < 1e-3);
+        }
+
+        // The drag must still only ever slow the cube baby down while fast-forwarding.
+        let decay = self::decay(crate::SLIDE_DRAG * crate::SPRITE_SCALE, self::step_delta(1.0 / 64.0, 3.0));
+
+        assert!((0.0 ..= 1.0).contains(&decay));
+    }
+
+    #[test]
+    fn repeated_pushes_approach_the_speed_limit() {
+        let mut surroundings = surroundings(1.0 / 1024.0);
+        let mut position = ScreenPos::new(900.0, 500.0);
+        let mut velocity = Vec2::ZERO;
+        let mut corner_trap = CornerTrap::default();
+        let mut previous_speed = 0.0;
+
+        surroundings.max_speed = 1000.0;
+
+        // Short steps keep the cube baby well away from the edges, so that only the limit slows it down.
+        for _ in 0 .. 32 {
+            velocity += Vec2::new(300.0, 200.0);
+
+            self::step(&surroundings, &mut position, &mut velocity, &mut corner_trap, false);
+
+            let speed = velocity.length();
+
+            assert!(speed <= surroundings.max_speed);
+            assert!(speed >= previous_speed);
+
+            previous_speed = speed;
+        }
+
+        let settled_speed = surroundings.max_speed * self::decay(surroundings.slide_drag, surroundings.delta);
+
+        assert!((previous_speed - settled_speed).abs() < 1e-2);
+        assert!(velocity.normalize().abs_diff_eq(Vec2::new(300.0, 200.0).normalize(), 1e-5));
+    }
+
+    #[test]
+    fn slow_slides_come_to_rest() {
+        let mut surroundings = surroundings(1.0 / 64.0);
+        let start = ScreenPos::new(900.0, 500.0);
+        let step = |surroundings: &Surroundings, velocity: Vec2, held: bool| {
+            let (mut position, mut velocity) = (start, velocity);
+
+            self::step(surroundings, &mut position, &mut velocity, &mut CornerTrap::default(), held);
+
+            velocity
+        };
+
+        // Anything below the threshold after drag stops outright, while anything faster keeps sliding.
+        assert_eq!(step(&surroundings, Vec2::new(4.0, -4.0), false), Vec2::ZERO);
+        assert_ne!(step(&surroundings, Vec2::new(16.0, 0.0), false), Vec2::ZERO);
+
+        // A held cube baby follows the cursor, so it is never brought to rest.
+        assert_ne!(step(&surroundings, Vec2::new(4.0, -4.0), true), Vec2::ZERO);
+
+        // A slide always ends up at rest eventually, rather than crawling forever.
+        let (_, velocity) = simulate(&surroundings, start, Vec2::new(64.0, 0.0), 1024);
+
+        assert_eq!(velocity, Vec2::ZERO);
+
+        // A threshold of zero never brings the cube baby to rest.
+        surroundings.rest_speed = 0.0;
+
+        assert_ne!(step(&surroundings, Vec2::new(0.5, 0.0), false), Vec2::ZERO);
+    }
+
+    #[test]
+    fn stalls_are_detected() {
+        assert!(!self::is_stall(1.0 / 60.0));
+        assert!(!self::is_stall(0.5));
+        assert!(self::is_stall(STALL_DELTA));
+        assert!(self::is_stall(3600.0));
+    }
+
+    #[test]
+    fn large_deltas_move_a_bounded_distance() {
+        let mut surroundings = surroundings(self::step_delta(3600.0, 1.0));
+        let mut position = ScreenPos::new(900.0, 500.0);
+        let mut velocity = Vec2::new(1_000_000.0, 0.0);
+        let mut corner_trap = CornerTrap::default();
+
+        // Without edges in the way, the step moves at most the maximum speed for the clamped delta.
+        surroundings.maximum_position = ScreenPos::new(f32::MAX, f32::MAX);
+
+        let start = position;
+        let step = self::step(&surroundings, &mut position, &mut velocity, &mut corner_trap, false);
+
+        assert!(step.travelled <= surroundings.max_speed * MAX_STEP_DELTA + 1e-3);
+        assert!(start.distance(position) <= surroundings.max_speed * MAX_STEP_DELTA + 1e-3);
+    }
+
+    /// Shoves a cube baby into the bottom-right corner once per timestep, for the given number of timesteps.
+    fn shove_into_corner(
+        surroundings: &Surroundings,
+        position: &mut ScreenPos,
+        velocity: &mut Vec2,
+        corner_trap: &mut CornerTrap,
+        steps: u32,
+    ) {
+        for _ in 0 .. steps {
+            *velocity = Vec2::splat(512.0);
+
+            self::step(surroundings, position, velocity, corner_trap, false);
+        }
+    }
+
+    #[test]
+    fn trapped_babies_escape_corners() {
+        let mut surroundings = surroundings(1.0 / 64.0);
+        let mut position = surroundings.maximum_position;
+        let mut velocity = Vec2::ZERO;
+        let mut corner_trap = CornerTrap::default();
+
+        // Without any bounce, a cube baby shoved into a corner would otherwise stay pinned there forever.
+        surroundings.restitution = 0.0;
+
+        shove_into_corner(&surroundings, &mut position, &mut velocity, &mut corner_trap, crate::CORNER_TRAP_HITS);
+
+        assert!(corner_trap.is_escaping());
+
+        for _ in 0 .. 640 {
+            self::step(&surroundings, &mut position, &mut velocity, &mut corner_trap, false);
+        }
+
+        assert!(position.cmpgt(surroundings.minimum_position.0).all());
+        assert!(position.cmplt(surroundings.maximum_position.0).all());
+        assert!(velocity.length() < surroundings.rest_speed);
+    }
+
+    #[test]
+    fn parked_babies_are_not_ejected_again() {
+        let mut surroundings = surroundings(1.0 / 64.0);
+        let mut position = surroundings.maximum_position;
+        let mut velocity = Vec2::ZERO;
+        let mut corner_trap = CornerTrap::default();
+
+        surroundings.restitution = 0.0;
+
+        shove_into_corner(&surroundings, &mut position, &mut velocity, &mut corner_trap, crate::CORNER_TRAP_HITS);
+
+        for _ in 0 .. 64 {
+            self::step(&surroundings, &mut position, &mut velocity, &mut corner_trap, false);
+        }
+
+        // Escapes are rate-limited, so being shoved straight back into the corner leaves the cube baby there.
+        position = surroundings.maximum_position;
+
+        shove_into_corner(&surroundings, &mut position, &mut velocity, &mut corner_trap, crate::CORNER_TRAP_HITS);
+
+        assert_eq!(position, surroundings.maximum_position);
+        assert_eq!(velocity, Vec2::ZERO);
+    }
+
+    #[test]
+    fn constant_wind_reaches_a_terminal_speed() {
+        let mut surroundings = surroundings(1.0 / 64.0);
+        let mut position = ScreenPos::new(0.0, 0.0);
+        let mut velocity = Vec2::ZERO;
+        let mut corner_trap = CornerTrap::default();
+
+        // Blowing steadily at full strength, far away from any edges.
+        let acceleration = Vec2::X * crate::settings::WindSettings::default().strength;
+        let terminal_speed = acceleration.length() / surroundings.slide_drag;
+
+        surroundings.minimum_position = ScreenPos::new(f32::MIN, f32::MIN);
+        surroundings.maximum_position = ScreenPos::new(f32::MAX, f32::MAX);
+        surroundings.rest_speed = 0.0;
+
+        for _ in 0 .. 64 * 60 {
+            velocity += acceleration * surroundings.delta;
+
+            self::step(&surroundings, &mut position, &mut velocity, &mut corner_trap, false);
+
+            assert!(velocity.length() <= terminal_speed * 1.01);
+        }
+
+        assert!((velocity.length() - terminal_speed).abs() < terminal_speed * 0.01);
+    }
+
+    #[test]
+    fn sliding_along_an_edge_applies_wall_friction() {
+        let mut surroundings = surroundings(1.0 / 64.0);
+        let mut corner_trap = CornerTrap::default();
+
+        surroundings.edges.bottom = BoundaryBehavior::Clamp;
+
+        let mut free_position = ScreenPos::new(100.0, 500.0);
+        let mut free_velocity = Vec2::new(512.0, 0.0);
+        let mut sliding_position = ScreenPos::new(100.0, surroundings.maximum_position.y);
+        let mut sliding_velocity = Vec2::new(512.0, 0.0);
+
+        for _ in 0 .. 32 {
+            self::step(&surroundings, &mut free_position, &mut free_velocity, &mut corner_trap, false);
+            self::step(&surroundings, &mut sliding_position, &mut sliding_velocity, &mut corner_trap, false);
+        }
+
+        // Without any wall friction, sliding along the edge is no different from sliding anywhere else.
+        assert_eq!(free_velocity, sliding_velocity);
+
+        surroundings.wall_friction = 2.0 * crate::SPRITE_SCALE;
+
+        for _ in 0 .. 32 {
+            self::step(&surroundings, &mut free_position, &mut free_velocity, &mut corner_trap, false);
+            self::step(&surroundings, &mut sliding_position, &mut sliding_velocity, &mut corner_trap, false);
+
+            assert_eq!(sliding_position.y, surroundings.maximum_position.y);
+        }
+
+        // Friction keeps applying for as long as the cube baby stays on the edge, not only when it first touches.
+        assert!(sliding_velocity.x < free_velocity.x * self::decay(surroundings.wall_friction, 0.5) * 1.01);
+    }
+
+    #[test]
+    fn falling_approaches_the_terminal_speed() {
+        let settings = crate::settings::GravitySettings::default();
+        let mut velocity = 0.0;
+        let mut previous = velocity;
+
+        for _ in 0 .. 64 * 10 {
+            velocity = self::fall(velocity, settings.acceleration, settings.terminal_speed, 1.0 / 64.0);
+
+            assert!(velocity >= previous);
+            assert!(velocity <= settings.terminal_speed);
+
+            previous = velocity;
+        }
+
+        assert_eq!(velocity, settings.terminal_speed);
+    }
+
+    #[test]
+    fn rising_is_not_limited() {
+        let jump = self::fall(-4096.0, 2048.0, 1024.0, 1.0 / 64.0);
+
+        assert_eq!(jump, -4096.0 + 32.0);
+    }
+
+    #[test]
+    fn grounded_babies_slow_faster_than_airborne_babies() {
+        let mut surroundings = surroundings(1.0 / 64.0);
+        let mut corner_trap = CornerTrap::default();
+
+        surroundings.ground = Some(Ground {
+            restitution: 0.5,
+            friction: crate::settings::GravitySettings::default().ground_friction * crate::SPRITE_SCALE,
+            rest_speed: crate::GRAVITY_REST_SPEED * crate::SPRITE_SCALE,
+        });
+
+        let mut grounded_position = ScreenPos::new(100.0, surroundings.maximum_position.y);
+        let mut grounded_velocity = Vec2::new(512.0, 0.0);
+        let mut airborne_position = ScreenPos::new(100.0, 100.0);
+        let mut airborne_velocity = Vec2::new(512.0, 0.0);
+
+        for _ in 0 .. 32 {
+            self::step(&surroundings, &mut grounded_position, &mut grounded_velocity, &mut corner_trap, false);
+            self::step(&surroundings, &mut airborne_position, &mut airborne_velocity, &mut corner_trap, false);
+        }
+
+        let airborne_decay = self::decay(surroundings.slide_drag, 0.5);
+        let grounded_decay = airborne_decay * self::decay(surroundings.ground.unwrap().friction, 0.5);
+
+        assert!((airborne_velocity.x - (512.0 * airborne_decay)).abs() < 0.1);
+        assert!((grounded_velocity.x - (512.0 * grounded_decay)).abs() < 0.1);
+        assert!(grounded_velocity.x < airborne_velocity.x);
+    }
+
+    #[test]
+    fn bounces_stay_within_bounds() {
+        let surroundings = surroundings(1.0 / 64.0);
+        let mut position = ScreenPos::new(10.0, 10.0);
+        let mut velocity = Vec2::new(-4096.0, 3000.0);
+        let mut corner_trap = CornerTrap::default();
+
+        for _ in 0 .. 512 {
+            self::step(&surroundings, &mut position, &mut velocity, &mut corner_trap, false);
+
+            assert!(position.cmpge(surroundings.minimum_position.0).all());
+            assert!(position.cmple(surroundings.maximum_position.0).all());
+        }
+    }
+
+    #[test]
+    fn unscaled_trajectories_match_plain_integration() {
+        let delta = 1.0 / 64.0;
+        let mut surroundings = surroundings(delta);
+
+        // At a scale factor of one, converting between logical and physical pixels must not change a single bit.
+        let extent = crate::coords::window_extent(64.0, 1.0);
+
+        assert_eq!(extent, surroundings.window_extent);
+        assert_eq!(ScreenPos::from_physical(IVec2::new(1856, 1016)), surroundings.maximum_position);
+
+        surroundings.window_extent = extent;
+        surroundings.rest_speed = 0.0;
+
+        let mut position = ScreenPos::new(300.0, 200.0);
+        let mut velocity = Vec2::new(640.0, 320.0);
+        let mut corner_trap = CornerTrap::default();
+        let mut expected_position = position.0;
+        let mut expected_velocity = velocity;
+
+        // The cube baby stays clear of every edge, so each step is nothing more than the plain integration.
+        for _ in 0 .. 64 {
+            self::step(&surroundings, &mut position, &mut velocity, &mut corner_trap, false);
+
+            expected_position += expected_velocity * delta;
+            expected_velocity *= (-surroundings.slide_drag * delta).exp();
+
+            assert_eq!(position.0, expected_position);
+            assert_eq!(velocity, expected_velocity);
+        }
+    }
+
+    #[test]
+    fn batched_presentation_keeps_the_trajectory() {
+        let delta = 1.0 / 64.0;
+        let surroundings = surroundings(delta);
+        let mut position = ScreenPos::new(10.0, 10.0);
+        let mut velocity = Vec2::new(-4096.0, 3000.0);
+        let mut corner_trap = CornerTrap::default();
+        let mut trajectory = Vec::new();
+
+        for _ in 0 .. 512 {
+            self::step(&surroundings, &mut position, &mut velocity, &mut corner_trap, false);
+
+            trajectory.push((position, velocity));
+        }
+
+        let mut throttle = PresentationThrottle::new(12.0);
+        let mut position = ScreenPos::new(10.0, 10.0);
+        let mut velocity = Vec2::new(-4096.0, 3000.0);
+        let mut corner_trap = CornerTrap::default();
+        let mut presentations = 0;
+
+        // Every step is still integrated, but only some of them are presented, each snapping to the latest position.
+        for &expected in &trajectory {
+            self::step(&surroundings, &mut position, &mut velocity, &mut corner_trap, false);
+            throttle.tick(Duration::from_secs_f32(delta));
+
+            assert_eq!((position, velocity), expected);
+
+            if throttle.is_ready() {
+                presentations += 1;
+            }
+        }
+
+        assert!((32 .. 128).contains(&presentations));
+    }
+}
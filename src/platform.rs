@@ -0,0 +1,364 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright © 2025 Jaxydog
+//
+// This file is part of Desktop Cube Baby.
+//
+// Desktop Cube Baby is free software: you can redistribute it and/or modify it under the terms of the GNU General
+// Public License as published by the Free Software Foundation, either version 3 of the License, or (at your option)
+// any later version.
+//
+// Desktop Cube Baby is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the
+// implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more
+// details.
+//
+// You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
+// see <https://www.gnu.org/licenses/>.
+
+use bevy::input::mouse::MouseButton;
+use bevy::math::{IVec2, UVec2};
+
+use crate::coords::ScreenPos;
+
+/// Returns `true` if the current session appears to support per-pixel window transparency.
+#[cfg(target_os = "linux")]
+pub fn supports_transparency() -> bool {
+    use x11rb::protocol::xproto::ConnectionExt;
+
+    // Wayland compositors always composite.
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        return true;
+    }
+
+    let Ok((connection, screen)) = x11rb::connect(None) else { return false };
+    // A running compositing manager owns the `_NET_WM_CM_S<n>` selection of the screen that it composites.
+    let selection = format!("_NET_WM_CM_S{screen}");
+
+    connection
+        .intern_atom(false, selection.as_bytes())
+        .ok()
+        .and_then(|v| v.reply().ok())
+        .and_then(|v| connection.get_selection_owner(v.atom).ok())
+        .and_then(|v| v.reply().ok())
+        .is_some_and(|v| v.owner != x11rb::NONE)
+}
+
+/// Returns `true` if the current session appears to support per-pixel window transparency.
+#[cfg(target_os = "windows")]
+pub fn supports_transparency() -> bool {
+    // Desktop composition is always enabled, except within some remote desktop sessions.
+    std::env::var("SESSIONNAME").map_or(true, |v| !v.starts_with("RDP-"))
+}
+
+/// Returns `true` if the current session appears to support per-pixel window transparency.
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+pub fn supports_transparency() -> bool {
+    true
+}
+
+/// Returns `true` if the application is running within a remote desktop session.
+#[cfg(target_os = "windows")]
+pub fn is_remote_session() -> bool {
+    /// The system metric that is non-zero within a remote desktop session.
+    const SM_REMOTESESSION: i32 = 0x1000;
+
+    #[link(name = "user32")]
+    unsafe extern "system" {
+        fn GetSystemMetrics(index: i32) -> i32;
+    }
+
+    // SAFETY: `GetSystemMetrics` has no preconditions, and returns zero for unknown metrics.
+    unsafe { GetSystemMetrics(SM_REMOTESESSION) != 0 }
+}
+
+/// Returns `true` if the application is running within a remote desktop session.
+#[cfg(not(target_os = "windows"))]
+pub fn is_remote_session() -> bool {
+    false
+}
+
+/// Returns the cursor's position on the desktop, if the platform allows it to be polled.
+///
+/// The scale factor is used on platforms that only report the cursor's position in logical pixels.
+#[cfg(target_os = "windows")]
+pub fn global_cursor_position(_: f32) -> Option<ScreenPos> {
+    #[repr(C)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[link(name = "user32")]
+    unsafe extern "system" {
+        fn GetCursorPos(point: *mut Point) -> i32;
+    }
+
+    let mut point = Point { x: 0, y: 0 };
+
+    // SAFETY: `point` is a valid pointer to a correctly laid out `POINT` for the duration of the call.
+    (unsafe { GetCursorPos(&raw mut point) } != 0).then(|| ScreenPos::new(point.x as f32, point.y as f32))
+}
+
+/// Returns the cursor's position on the desktop, if the platform allows it to be polled.
+///
+/// The scale factor is used on platforms that only report the cursor's position in logical pixels.
+#[cfg(target_os = "macos")]
+pub fn global_cursor_position(scale_factor: f32) -> Option<ScreenPos> {
+    use std::ffi::c_void;
+
+    #[repr(C)]
+    struct Point {
+        x: f64,
+        y: f64,
+    }
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    unsafe extern "C" {
+        fn CGEventCreate(source: *const c_void) -> *const c_void;
+        fn CGEventGetLocation(event: *const c_void) -> Point;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    unsafe extern "C" {
+        fn CFRelease(value: *const c_void);
+    }
+
+    // SAFETY: a null source is permitted, and the created event is only used while it is alive.
+    unsafe {
+        let event = CGEventCreate(std::ptr::null());
+
+        if event.is_null() {
+            return None;
+        }
+
+        let point = CGEventGetLocation(event);
+
+        CFRelease(event);
+
+        Some(ScreenPos::new(point.x as f32 * scale_factor, point.y as f32 * scale_factor))
+    }
+}
+
+/// Returns the cursor's position on the desktop, if the platform allows it to be polled.
+///
+/// The scale factor is used on platforms that only report the cursor's position in logical pixels.
+///
+/// Wayland does not allow clients to see the cursor outside of their own surfaces, and querying X11 directly would make
+/// Xlib a hard dependency even on Wayland-only desktops, so the cursor is never polled here.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn global_cursor_position(_: f32) -> Option<ScreenPos> {
+    None
+}
+
+/// Returns `true` if the given mouse button is held anywhere on the desktop, if the platform allows it to be polled.
+#[cfg(target_os = "windows")]
+pub fn global_mouse_button_pressed(button: MouseButton) -> Option<bool> {
+    #[link(name = "user32")]
+    unsafe extern "system" {
+        fn GetAsyncKeyState(key: i32) -> i16;
+    }
+
+    let key = match button {
+        MouseButton::Left => 0x01,
+        MouseButton::Right => 0x02,
+        MouseButton::Middle => 0x04,
+        MouseButton::Back => 0x05,
+        MouseButton::Forward => 0x06,
+        MouseButton::Other(_) => return None,
+    };
+
+    // SAFETY: `GetAsyncKeyState` has no preconditions, and returns zero for unknown keys.
+    Some(unsafe { GetAsyncKeyState(key) } < 0)
+}
+
+/// Returns `true` if the given mouse button is held anywhere on the desktop, if the platform allows it to be polled.
+#[cfg(target_os = "macos")]
+pub fn global_mouse_button_pressed(button: MouseButton) -> Option<bool> {
+    /// The event source state that combines every source within the current session.
+    const COMBINED_SESSION_STATE: i32 = 0;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    unsafe extern "C" {
+        fn CGEventSourceButtonState(state: i32, button: u32) -> bool;
+    }
+
+    let button = match button {
+        MouseButton::Left => 0,
+        MouseButton::Right => 1,
+        MouseButton::Middle => 2,
+        MouseButton::Back => 3,
+        MouseButton::Forward => 4,
+        MouseButton::Other(button) => u32::from(button),
+    };
+
+    // SAFETY: `CGEventSourceButtonState` has no preconditions, and returns `false` for unknown buttons.
+    Some(unsafe { CGEventSourceButtonState(COMBINED_SESSION_STATE, button) })
+}
+
+/// Returns `true` if the given mouse button is held anywhere on the desktop, if the platform allows it to be polled.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn global_mouse_button_pressed(_: MouseButton) -> Option<bool> {
+    None
+}
+
+/// Returns the position and size of the given monitor's work area, excluding any taskbars or docks, if the platform
+/// allows it to be queried.
+#[cfg(target_os = "windows")]
+pub fn work_area(position: IVec2, _: UVec2) -> Option<(IVec2, UVec2)> {
+    use std::ffi::c_void;
+
+    /// The flag that makes `MonitorFromPoint` return null if the point is not on any monitor.
+    const MONITOR_DEFAULTTONULL: u32 = 0;
+
+    #[repr(C)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct Rect {
+        left: i32,
+        top: i32,
+        right: i32,
+        bottom: i32,
+    }
+
+    #[repr(C)]
+    struct MonitorInfo {
+        size: u32,
+        monitor: Rect,
+        work: Rect,
+        flags: u32,
+    }
+
+    #[link(name = "user32")]
+    unsafe extern "system" {
+        fn MonitorFromPoint(point: Point, flags: u32) -> *mut c_void;
+        fn GetMonitorInfoW(monitor: *mut c_void, info: *mut MonitorInfo) -> i32;
+    }
+
+    // SAFETY: `MonitorFromPoint` has no preconditions, and returns null for points that are not on any monitor.
+    let monitor = unsafe { MonitorFromPoint(Point { x: position.x, y: position.y }, MONITOR_DEFAULTTONULL) };
+
+    if monitor.is_null() {
+        return None;
+    }
+
+    let empty = Rect { left: 0, top: 0, right: 0, bottom: 0 };
+    let mut info = MonitorInfo { size: size_of::<MonitorInfo>() as u32, monitor: empty, work: empty, flags: 0 };
+
+    // SAFETY: `monitor` is a valid monitor handle, and `info` is a correctly sized and laid out `MONITORINFO`.
+    (unsafe { GetMonitorInfoW(monitor, &raw mut info) } != 0).then(|| {
+        let work = info.work;
+
+        (IVec2::new(work.left, work.top), IVec2::new(work.right - work.left, work.bottom - work.top).as_uvec2())
+    })
+}
+
+/// Returns the position and size of the given monitor's work area, excluding any taskbars or docks, if the platform
+/// allows it to be queried.
+#[cfg(target_os = "linux")]
+pub fn work_area(position: IVec2, resolution: UVec2) -> Option<(IVec2, UVec2)> {
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{AtomEnum, ConnectionExt};
+
+    // Wayland does not expose the work area to clients at all.
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() || std::env::var_os("DISPLAY").is_none() {
+        return None;
+    }
+
+    let (connection, screen) = x11rb::connect(None).ok()?;
+    let root = connection.setup().roots.get(screen)?.root;
+    let atom = connection.intern_atom(true, b"_NET_WORKAREA").ok()?.reply().ok()?.atom;
+
+    // Window managers that do not reserve space for panels may not set a work area at all.
+    if atom == x11rb::NONE {
+        return None;
+    }
+
+    // Each virtual desktop has a work area of its own, but they are almost always the same as the first.
+    let reply = connection.get_property(false, root, atom, AtomEnum::CARDINAL, 0, 4).ok()?.reply().ok()?;
+    let values = reply.value32()?.map(|v| v as i32).collect::<Vec<_>>();
+    let &[x, y, width, height] = values.as_slice() else { return None };
+
+    // The work area spans every monitor, so it is narrowed down to the given one.
+    let minimum = position.max(IVec2::new(x, y));
+    let maximum = position.saturating_add_unsigned(resolution).min(IVec2::new(x + width, y + height));
+
+    minimum.cmplt(maximum).all().then(|| (minimum, (maximum - minimum).as_uvec2()))
+}
+
+/// Returns the position and size of the given monitor's work area, excluding any taskbars or docks, if the platform
+/// allows it to be queried.
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn work_area(_: IVec2, _: UVec2) -> Option<(IVec2, UVec2)> {
+    None
+}
+
+/// Returns the position and size of the foreground window, if it belongs to another application and the platform
+/// allows it to be queried.
+#[cfg(target_os = "windows")]
+pub fn foreground_window_bounds() -> Option<(IVec2, UVec2)> {
+    use std::ffi::c_void;
+
+    /// The attribute that holds a window's visible bounds, excluding its invisible resizing borders.
+    const DWMWA_EXTENDED_FRAME_BOUNDS: u32 = 9;
+
+    #[repr(C)]
+    struct Rect {
+        left: i32,
+        top: i32,
+        right: i32,
+        bottom: i32,
+    }
+
+    #[link(name = "user32")]
+    unsafe extern "system" {
+        fn GetForegroundWindow() -> *mut c_void;
+        fn GetWindowThreadProcessId(window: *mut c_void, process_id: *mut u32) -> u32;
+        fn IsIconic(window: *mut c_void) -> i32;
+    }
+
+    #[link(name = "dwmapi")]
+    unsafe extern "system" {
+        fn DwmGetWindowAttribute(window: *mut c_void, attribute: u32, value: *mut c_void, size: u32) -> i32;
+    }
+
+    // SAFETY: `GetForegroundWindow` has no preconditions, and returns null if there is no foreground window.
+    let window = unsafe { GetForegroundWindow() };
+
+    if window.is_null() {
+        return None;
+    }
+
+    let mut process_id = 0;
+
+    // SAFETY: `window` is a valid window handle, and `process_id` is a valid pointer for the duration of the call.
+    unsafe { GetWindowThreadProcessId(window, &raw mut process_id) };
+
+    // The cube babies' own windows are never treated as obstacles, nor are minimized windows.
+    // SAFETY: `window` is a valid window handle.
+    if process_id == std::process::id() || unsafe { IsIconic(window) } != 0 {
+        return None;
+    }
+
+    let mut rect = Rect { left: 0, top: 0, right: 0, bottom: 0 };
+
+    // SAFETY: `window` is a valid window handle, and `rect` is a correctly sized and laid out `RECT`.
+    let result = unsafe {
+        DwmGetWindowAttribute(window, DWMWA_EXTENDED_FRAME_BOUNDS, (&raw mut rect).cast(), size_of::<Rect>() as u32)
+    };
+
+    (result == 0 && rect.right > rect.left && rect.bottom > rect.top).then(|| {
+        (IVec2::new(rect.left, rect.top), IVec2::new(rect.right - rect.left, rect.bottom - rect.top).as_uvec2())
+    })
+}
+
+/// Returns the position and size of the foreground window, if it belongs to another application and the platform
+/// allows it to be queried.
+#[cfg(not(target_os = "windows"))]
+pub fn foreground_window_bounds() -> Option<(IVec2, UVec2)> {
+    None
+}
@@ -0,0 +1,355 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright © 2025 Jaxydog
+//
+// This file is part of Desktop Cube Baby.
+//
+// Desktop Cube Baby is free software: you can redistribute it and/or modify it under the terms of the GNU General
+// Public License as published by the Free Software Foundation, either version 3 of the License, or (at your option)
+// any later version.
+//
+// Desktop Cube Baby is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the
+// implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more
+// details.
+//
+// You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
+// see <https://www.gnu.org/licenses/>.
+
+use std::collections::VecDeque;
+
+use bevy::input::touch::TouchPhase;
+use bevy::prelude::*;
+
+use crate::resources::PushModel;
+use crate::settings::{PushCurve, RepelFalloff};
+use crate::{DIZZY_SHAKE_WINDOW, PUSH_SPEED_SCALE, PUSH_STRENGTH, SHAKE_MINIMUM_DELTA, TOUCH_TAP_DISTANCE};
+
+/// Returns the unit direction that the given nudge directions combine into, or zero if they cancel out.
+///
+/// Diagonals are normalized, so that they are no stronger than cardinal nudges.
+#[must_use]
+pub fn nudge_direction(directions: impl IntoIterator<Item = Vec2>) -> Vec2 {
+    let direction = directions.into_iter().sum::<Vec2>();
+
+    // Pressing two keys for the same direction should not double it.
+    direction.clamp(Vec2::NEG_ONE, Vec2::ONE).normalize_or_zero()
+}
+
+/// Returns a randomly directed knock for a cube baby moving at the given velocity, in logical pixels per second.
+#[must_use]
+pub fn random_knock(velocity: Vec2, sprite_scale: f32) -> Vec2 {
+    const MIN_STRENGTH: f32 = PUSH_STRENGTH * PUSH_STRENGTH;
+    const MAX_STRENGTH: f32 = PUSH_STRENGTH * PUSH_STRENGTH * 4.0;
+
+    let x = (fastrand::f32() * 2.0) - 1.0;
+    let y = (fastrand::f32() * 2.0) - 1.0;
+    let strength = ((fastrand::f32() * MAX_STRENGTH) - MIN_STRENGTH) + MIN_STRENGTH;
+    let movement = velocity.normalize_or_zero() + Vec2::new(x, y).normalize_or_zero();
+
+    movement * strength * sprite_scale
+}
+
+/// Returns the given stick position with its deadzone removed, rescaled so that its length spans zero to one.
+///
+/// The returned vector uses screen axes, so pushing the stick up returns a negative Y value.
+#[must_use]
+pub fn rescale_stick(stick: Vec2, deadzone: f32) -> Vec2 {
+    let length = stick.length();
+
+    if length <= deadzone || deadzone >= 1.0 {
+        return Vec2::ZERO;
+    }
+
+    let scale = ((length - deadzone) / (1.0 - deadzone)).min(1.0);
+
+    Vec2::new(stick.x, -stick.y) / length * scale
+}
+
+/// Returns the impulse applied by a cursor that moved by the given amount over the given number of seconds.
+///
+/// The cursor's speed is mapped through the curve, and the resulting strength is clamped between the minimum and
+/// maximum. All values are measured in logical pixels.
+#[must_use]
+pub fn cursor_impulse(delta: Vec2, elapsed: f32, curve: PushCurve, minimum: f32, maximum: f32) -> Vec2 {
+    if elapsed <= 0.0 || delta == Vec2::ZERO {
+        return Vec2::ZERO;
+    }
+
+    let speed = delta.length() / elapsed;
+    let strength = curve.apply(speed * PUSH_SPEED_SCALE).clamp(minimum, maximum.max(minimum));
+
+    delta.normalize_or_zero() * strength
+}
+
+/// Returns the total distance travelled along the given path of positions.
+#[must_use]
+pub fn path_length(positions: impl IntoIterator<Item = Vec2>) -> f32 {
+    let mut positions = positions.into_iter();
+    let Some(mut previous) = positions.next() else { return 0.0 };
+
+    positions.fold(0.0, |length, position| length + position.distance(std::mem::replace(&mut previous, position)))
+}
+
+/// Returns the impulse of a flick, if a cursor that travelled the given path would cross the window quickly enough.
+///
+/// The path length counts every point that the cursor passed through, so that curved flicks are measured by their true
+/// speed. The impulse follows the direction of the push. All values are measured in logical pixels.
+#[must_use]
+pub fn flick_impulse(
+    delta: Vec2,
+    path_length: f32,
+    elapsed: f32,
+    window_size: f32,
+    flick_time: f32,
+    strength: f32,
+) -> Option<Vec2> {
+    if elapsed <= 0.0 || flick_time <= 0.0 {
+        return None;
+    }
+
+    let speed = path_length / elapsed;
+
+    (speed >= window_size / flick_time).then(|| delta.normalize_or_zero() * strength).filter(|v| *v != Vec2::ZERO)
+}
+
+/// Returns the direction of a cursor push under the given model, scaled by the cursor's movement.
+///
+/// The offset points from the cursor to the window's center. Radial pushes fall back to the cursor's movement if the
+/// cursor is exactly at the window's center.
+#[must_use]
+pub fn directed_delta(model: PushModel, delta: Vec2, offset: Vec2) -> Vec2 {
+    match model {
+        PushModel::Delta => delta,
+        PushModel::Radial => offset.try_normalize().map_or(delta, |v| v * delta.length()),
+    }
+}
+
+/// Returns the acceleration applied by a cursor at the given offset from the window's center.
+///
+/// The acceleration points away from the cursor and reaches zero at the edge of the radius. Because sliding drag grows
+/// with velocity, a constant acceleration settles at a fixed speed of `strength / drag` rather than building forever.
+#[must_use]
+pub fn repel_acceleration(offset: Vec2, radius: f32, strength: f32, falloff: RepelFalloff) -> Vec2 {
+    // Clamps the inverse falloff close to the center, where it would otherwise grow without bound.
+    const INVERSE_CORE: f32 = 0.1;
+
+    let distance = offset.length();
+
+    if radius <= 0.0 || distance >= radius {
+        return Vec2::ZERO;
+    }
+
+    let fraction = distance / radius;
+    let scale = match falloff {
+        RepelFalloff::Linear => 1.0 - fraction,
+        RepelFalloff::Inverse => (1.0 / fraction.max(INVERSE_CORE) - 1.0) * INVERSE_CORE / (1.0 - INVERSE_CORE),
+    };
+
+    // A cursor exactly at the center has no direction to push away from, so push upwards.
+    offset.try_normalize().unwrap_or(Vec2::NEG_Y) * strength * scale
+}
+
+/// Tracks the cursor's recent direction reversals, so that shaking it back and forth may be detected.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ShakeTracker {
+    /// The latest cursor movement that was large enough to count.
+    last_delta: Option<Vec2>,
+    /// The times in seconds at which the cursor reversed direction, from oldest to newest.
+    reversals: VecDeque<f64>,
+}
+
+impl ShakeTracker {
+    /// Records a cursor movement of the given logical amount at the given time in seconds, returning the number of
+    /// reversals within the shake window.
+    ///
+    /// Movements too small to count are ignored, so that jitter is not mistaken for shaking.
+    pub fn record(&mut self, time: f64, delta: Vec2) -> usize {
+        if delta.length() >= SHAKE_MINIMUM_DELTA {
+            if self.last_delta.is_some_and(|v| v.dot(delta) < 0.0) {
+                self.reversals.push_back(time);
+            }
+
+            self.last_delta = Some(delta);
+        }
+
+        while self.reversals.front().is_some_and(|v| time - v > DIZZY_SHAKE_WINDOW) {
+            self.reversals.pop_front();
+        }
+
+        self.reversals.len()
+    }
+
+    /// Forgets every recorded movement and reversal.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// A gesture completed by a touch.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TouchGesture {
+    /// The touch lifted without travelling far, at the given logical position within the window.
+    Tap(Vec2),
+    /// The touch moved by the given logical amount.
+    Swipe(Vec2),
+}
+
+/// Tracks the first active touch on the window, so that taps may be told apart from swipes.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TouchTracker {
+    /// The identifier of the tracked touch, if one is active.
+    id: Option<u64>,
+    /// The tracked touch's latest logical position within the window.
+    position: Vec2,
+    /// The total distance that the tracked touch has travelled, in logical pixels.
+    travelled: f32,
+}
+
+impl TouchTracker {
+    /// Records the given touch event, returning the gesture that it produced, if any.
+    ///
+    /// Events from any touch other than the tracked one are ignored.
+    pub fn record(&mut self, event: &TouchInput) -> Option<TouchGesture> {
+        match (event.phase, self.id) {
+            (TouchPhase::Started, None) => {
+                *self = Self { id: Some(event.id), position: event.position, travelled: 0.0 };
+
+                None
+            }
+            (_, Some(id)) if id != event.id => None,
+            (TouchPhase::Moved, Some(_)) => {
+                let delta = event.position - self.position;
+
+                self.position = event.position;
+                self.travelled += delta.length();
+
+                // Small wobbles while tapping should not count as swipes.
+                (self.travelled >= TOUCH_TAP_DISTANCE).then_some(TouchGesture::Swipe(delta))
+            }
+            (TouchPhase::Ended, Some(_)) => {
+                let tapped = self.travelled < TOUCH_TAP_DISTANCE;
+
+                *self = Self::default();
+
+                tapped.then_some(TouchGesture::Tap(event.position))
+            }
+            (TouchPhase::Canceled, Some(_)) => {
+                *self = Self::default();
+
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The weakest impulse that a moving cursor applies, in logical pixels.
+    const MINIMUM: f32 = 4.0;
+    /// The strongest impulse that a moving cursor applies, in logical pixels.
+    const MAXIMUM: f32 = 2000.0;
+
+    #[test]
+    fn slow_cursors_barely_push() {
+        let impulse = cursor_impulse(Vec2::new(2.0, 0.0), 1.0, PushCurve::Linear, MINIMUM, MAXIMUM);
+
+        assert_eq!(impulse, Vec2::new(MINIMUM, 0.0));
+    }
+
+    #[test]
+    fn fast_cursors_push_hard() {
+        let impulse = cursor_impulse(Vec2::new(0.0, -600.0), 0.1, PushCurve::Linear, MINIMUM, MAXIMUM);
+
+        assert_eq!(impulse, Vec2::new(0.0, -MAXIMUM));
+    }
+
+    #[test]
+    fn impulses_scale_with_cursor_speed() {
+        let impulse = cursor_impulse(Vec2::new(100.0, 0.0), 0.5, PushCurve::Linear, MINIMUM, MAXIMUM);
+
+        assert_eq!(impulse, Vec2::new(200.0 * PUSH_SPEED_SCALE, 0.0));
+
+        // The same movement over less time is a faster, and so stronger, push.
+        let strengths = [1.0, 0.5, 0.25, 0.125, 0.0625].map(|elapsed| {
+            cursor_impulse(Vec2::new(30.0, 40.0), elapsed, PushCurve::Linear, MINIMUM, MAXIMUM).length()
+        });
+
+        assert!(strengths.windows(2).all(|v| v[0] < v[1]));
+    }
+
+    #[test]
+    fn impulses_follow_the_curve() {
+        let linear = cursor_impulse(Vec2::new(400.0, 300.0), 0.5, PushCurve::Linear, MINIMUM, MAXIMUM);
+        let saturating = cursor_impulse(Vec2::new(400.0, 300.0), 0.5, PushCurve::Saturating(250.0), MINIMUM, MAXIMUM);
+
+        assert!(saturating.length() < linear.length());
+        assert!((saturating.normalize() - linear.normalize()).length() < 1e-5);
+    }
+
+    #[test]
+    fn still_cursors_do_not_push() {
+        assert_eq!(cursor_impulse(Vec2::ZERO, 0.1, PushCurve::Linear, MINIMUM, MAXIMUM), Vec2::ZERO);
+        assert_eq!(cursor_impulse(Vec2::new(10.0, 0.0), 0.0, PushCurve::Linear, MINIMUM, MAXIMUM), Vec2::ZERO);
+    }
+
+    #[test]
+    fn inverted_clamps_prefer_the_minimum() {
+        let impulse = cursor_impulse(Vec2::new(600.0, 0.0), 0.1, PushCurve::Linear, MINIMUM, 1.0);
+
+        assert_eq!(impulse, Vec2::new(MINIMUM, 0.0));
+    }
+
+    #[test]
+    fn delta_pushes_follow_the_cursor() {
+        let delta = Vec2::new(12.0, -5.0);
+
+        assert_eq!(directed_delta(PushModel::Delta, delta, Vec2::new(-30.0, 0.0)), delta);
+        assert_eq!(PushModel::default(), PushModel::Delta);
+    }
+
+    #[test]
+    fn radial_pushes_point_away_from_the_cursor() {
+        // The cursor entered from the left and stopped to the right of the center, so a delta push would pull the
+        // cube baby towards it.
+        let delta = Vec2::new(8.0, 6.0);
+        let pushed = directed_delta(PushModel::Radial, delta, Vec2::new(-20.0, 0.0));
+
+        assert_eq!(pushed, Vec2::new(-10.0, 0.0));
+
+        let pushed = directed_delta(PushModel::Radial, delta, Vec2::new(3.0, 4.0));
+
+        assert!((pushed - Vec2::new(6.0, 8.0)).length() < 1e-5);
+    }
+
+    #[test]
+    fn radial_pushes_at_the_center_fall_back_to_the_delta() {
+        let delta = Vec2::new(-3.0, 7.0);
+
+        assert_eq!(directed_delta(PushModel::Radial, delta, Vec2::ZERO), delta);
+        assert_eq!(directed_delta(PushModel::Radial, Vec2::ZERO, Vec2::new(5.0, 5.0)), Vec2::ZERO);
+    }
+
+    #[test]
+    fn empty_paths_have_no_length() {
+        assert_eq!(path_length([]), 0.0);
+        assert_eq!(path_length([Vec2::new(12.0, -3.0)]), 0.0);
+    }
+
+    #[test]
+    fn path_lengths_sum_every_segment() {
+        let path = [Vec2::ZERO, Vec2::new(3.0, 4.0), Vec2::new(3.0, 0.0), Vec2::new(-2.0, 0.0)];
+
+        assert_eq!(path_length(path), 5.0 + 4.0 + 5.0);
+    }
+
+    #[test]
+    fn jittering_paths_count_every_wiggle() {
+        // A still hand on a high polling rate mouse wiggles back and forth without going anywhere.
+        let path = [Vec2::ZERO, Vec2::new(0.25, 0.0), Vec2::ZERO, Vec2::new(0.25, 0.0), Vec2::ZERO];
+
+        assert_eq!(path_length(path), 1.0);
+    }
+}
@@ -15,9 +15,27 @@
 // You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
 // see <https://www.gnu.org/licenses/>.
 
+use std::collections::{BTreeMap, HashMap};
+use std::io::ErrorKind;
+use std::net::{Ipv4Addr, UdpSocket};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
 use bevy::prelude::*;
+use bevy::window::PresentMode;
+use bevy::winit::UpdateMode;
+use serde::Deserialize;
 
-use crate::{ATLAS_FRAMES, WINDOW_SIZE};
+use crate::components::{Distance, Patrolling, Position, PushDelays, Velocity};
+use crate::coords::{self, ScreenPos, TexturePx};
+use crate::evasion::EvasionTrigger;
+use crate::input::{Action, ChordKey};
+use crate::peers::{self, PeerMessage};
+use crate::{
+    ATLAS_FRAMES, EXIT_HOLD_DURATION, PEER_TIMEOUT, QUALITY_DEGRADE_DWELL, QUALITY_RESTORE_DWELL, SPRITE_SCALE,
+    WINDOW_SIZE, bounds, platform,
+};
 
 /// Contains metadata relating to an atlased texture.
 #[derive(Clone, Debug, PartialEq, Eq, Resource)]
@@ -27,55 +45,1624 @@ pub struct TextureMetadata {
     /// The handle to the texture's atlas layout.
     pub layout_handle: Handle<TextureAtlasLayout>,
     /// The size of the image.
-    pub size: UVec2,
+    pub size: TexturePx,
+    /// The spacing between adjacent frames.
+    pub padding: TexturePx,
+    /// The spacing before the first frame.
+    pub offset: TexturePx,
 }
 
 impl TextureMetadata {
-    /// Returns the size of a single frame.
-    pub const fn frame_size(&self) -> UVec2 {
-        UVec2::new(self.size.x / ATLAS_FRAMES, self.size.y)
+    /// Returns the size of a single frame, excluding any padding or offset.
+    pub const fn frame_size(&self) -> TexturePx {
+        let width = self.size.0.x.saturating_sub(self.offset.0.x + (self.padding.0.x * (ATLAS_FRAMES - 1)));
+
+        TexturePx::new(width / ATLAS_FRAMES, self.size.0.y.saturating_sub(self.offset.0.y))
+    }
+
+    /// Returns the atlas layout that splits the texture into its frames.
+    pub fn layout(&self) -> TextureAtlasLayout {
+        TextureAtlasLayout::from_grid(
+            self.frame_size().0,
+            ATLAS_FRAMES,
+            1,
+            Some(self.padding.0).filter(|v| *v != UVec2::ZERO),
+            Some(self.offset.0).filter(|v| *v != UVec2::ZERO),
+        )
     }
 
-    /// Returns the calculated sprite scale.
+    /// Returns the calculated sprite scale, from texture pixels to the logical pixels of a window of the given size.
     #[inline]
-    pub fn sprite_scale(&self) -> Vec2 {
-        Vec2::splat(WINDOW_SIZE) / self.frame_size().as_vec2()
+    pub fn sprite_scale(&self, window_size: f32) -> Vec2 {
+        Vec2::splat(window_size) / self.frame_size().as_vec2()
     }
 }
 
 /// Contains the properties of the current display.
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Resource)]
+#[derive(Clone, Copy, Debug, PartialEq, Resource)]
 pub struct DisplayProperties {
-    /// The display's position.
+    /// The display's position, in physical pixels.
     pub position: IVec2,
-    /// The display's resolution.
+    /// The display's resolution, in physical pixels.
     pub resolution: UVec2,
+    /// The position of the display's work area, excluding any taskbars or docks, in physical pixels.
+    pub work_position: IVec2,
+    /// The resolution of the display's work area, excluding any taskbars or docks, in physical pixels.
+    pub work_resolution: UVec2,
+    /// Whether the window is kept within the display's work area, rather than the entire display.
+    pub use_work_area: bool,
+    /// The display's scale factor, from logical to physical pixels.
+    pub scale_factor: f32,
+    /// The margins kept between the display's edges and the window.
+    pub margins: Margins,
+    /// The size of one side of the window, in logical pixels.
+    pub window_size: f32,
 }
 
 impl DisplayProperties {
-    /// Returns the smallest possible position that is contained within this display.
+    /// Returns the smallest position on the display itself, ignoring margins.
+    #[inline]
+    pub fn display_minimum_position(&self) -> ScreenPos {
+        ScreenPos::from_physical(self.position)
+    }
+
+    /// Returns the largest position on the display itself, ignoring margins.
+    #[inline]
+    pub fn display_maximum_position(&self) -> ScreenPos {
+        ScreenPos::from_physical(self.position.saturating_add_unsigned(self.resolution))
+    }
+
+    /// Returns the smallest position within the display's work area, ignoring margins.
+    #[inline]
+    pub fn work_area_minimum_position(&self) -> ScreenPos {
+        ScreenPos::from_physical(self.work_position)
+    }
+
+    /// Returns the largest position within the display's work area, ignoring margins.
+    #[inline]
+    pub fn work_area_maximum_position(&self) -> ScreenPos {
+        ScreenPos::from_physical(self.work_position.saturating_add_unsigned(self.work_resolution))
+    }
+
+    /// Returns the smallest possible position that the window may occupy on this display.
+    #[inline]
+    pub fn minimum_position(&self) -> ScreenPos {
+        self.bounds().0
+    }
+
+    /// Returns the largest possible position that the window may occupy on this display.
+    #[inline]
+    pub fn maximum_position(&self) -> ScreenPos {
+        self.bounds().1
+    }
+
+    /// Returns the position at the center of the area that the window may occupy on this display.
+    #[inline]
+    pub fn center_position(&self) -> ScreenPos {
+        let (minimum_position, maximum_position) = self.bounds();
+
+        ScreenPos((minimum_position.0 + maximum_position.0) / 2.0)
+    }
+
+    /// Returns the smallest and largest possible positions that the window may occupy on this display.
+    #[inline]
+    pub fn bounds(&self) -> (ScreenPos, ScreenPos) {
+        if self.use_work_area {
+            self.margins.shrink(
+                self.work_area_minimum_position(),
+                self.work_area_maximum_position(),
+                self.window_size,
+                self.scale_factor,
+            )
+        } else {
+            self.margins.shrink(
+                self.display_minimum_position(),
+                self.display_maximum_position(),
+                self.window_size,
+                self.scale_factor,
+            )
+        }
+    }
+
+    /// Returns the size of the window on this display, in physical pixels.
+    #[inline]
+    pub fn window_extent(&self) -> Vec2 {
+        coords::window_extent(self.window_size, self.scale_factor)
+    }
+
+    /// Returns the current sprite scale, which distances and speeds are multiplied by so that they follow the window's
+    /// size.
+    #[inline]
+    pub fn sprite_scale(&self) -> f32 {
+        SPRITE_SCALE * (self.window_size / WINDOW_SIZE)
+    }
+
+    /// Returns `true` if the given push should be ignored for pressing the window into a nearby edge.
+    ///
+    /// The dead zone is given in logical pixels.
+    pub fn presses_into_edge(&self, impulse: Vec2, position: ScreenPos, deadzone: f32) -> bool {
+        let maximum_position = self.maximum_position() - self.window_extent();
+
+        bounds::presses_into_edge(
+            impulse,
+            position,
+            self.minimum_position(),
+            maximum_position,
+            deadzone * self.scale_factor,
+        )
+    }
+
+    /// Loads the position, resolution, and scale factor of the given monitor.
+    pub fn load_monitor(&mut self, monitor: &winit::monitor::MonitorHandle) {
+        self.position = IVec2::new(monitor.position().x, monitor.position().y);
+        self.resolution = UVec2::new(monitor.size().width, monitor.size().height);
+        self.scale_factor = monitor.scale_factor() as f32;
+    }
+
+    /// Loads the work area of the loaded monitor, falling back to the entire monitor if it cannot be determined.
+    ///
+    /// This is kept apart from [`DisplayProperties::load_monitor`], since querying the work area may be slow.
+    pub fn load_work_area(&mut self) {
+        (self.work_position, self.work_resolution) =
+            platform::work_area(self.position, self.resolution).unwrap_or((self.position, self.resolution));
+    }
+
+    /// Returns `true` if this display contains the given position, ignoring margins.
+    pub fn contains(&self, position: ScreenPos) -> bool {
+        self.display_minimum_position().x < position.x
+            && self.display_maximum_position().x > position.x
+            && self.display_minimum_position().y < position.y
+            && self.display_maximum_position().y > position.y
+    }
+}
+
+impl Default for DisplayProperties {
+    fn default() -> Self {
+        Self {
+            position: IVec2::ZERO,
+            resolution: UVec2::ZERO,
+            work_position: IVec2::ZERO,
+            work_resolution: UVec2::ZERO,
+            use_work_area: true,
+            scale_factor: 1.0,
+            margins: Margins::ZERO,
+            window_size: WINDOW_SIZE,
+        }
+    }
+}
+
+/// The margins kept between each of a display's edges and the window, in logical pixels.
+///
+/// Negative margins allow the window to overlap the edge of the display, up to half of the window's size.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Margins {
+    /// The margin from the left edge.
+    pub left: f32,
+    /// The margin from the right edge.
+    pub right: f32,
+    /// The margin from the top edge.
+    pub top: f32,
+    /// The margin from the bottom edge.
+    pub bottom: f32,
+}
+
+impl Margins {
+    /// No margins.
+    pub const ZERO: Self = Self::splat(0.0);
+
+    /// Creates a new [`Margins`] with the same margin for every edge.
+    #[inline]
+    pub const fn splat(margin: f32) -> Self {
+        Self { left: margin, right: margin, top: margin, bottom: margin }
+    }
+
+    /// Returns these margins with negative margins clamped to at most half of the given window size.
+    #[must_use]
+    pub fn clamped(self, window_size: f32) -> Self {
+        let minimum = -window_size / 2.0;
+
+        Self {
+            left: self.left.max(minimum),
+            right: self.right.max(minimum),
+            top: self.top.max(minimum),
+            bottom: self.bottom.max(minimum),
+        }
+    }
+
+    /// Shrinks the given bounds by these margins, returning the new smallest and largest positions.
+    ///
+    /// Negative margins are clamped to at most half of the given window size. If the margins are larger than the bounds
+    /// on an axis, that axis collapses onto the center of the bounds.
+    #[must_use]
+    pub fn shrink(
+        self,
+        minimum: ScreenPos,
+        maximum: ScreenPos,
+        window_size: f32,
+        scale_factor: f32,
+    ) -> (ScreenPos, ScreenPos) {
+        let clamped = self.clamped(window_size);
+        let near = coords::logical_to_physical(Vec2::new(clamped.left, clamped.top), scale_factor);
+        let far = coords::logical_to_physical(Vec2::new(clamped.right, clamped.bottom), scale_factor);
+
+        let mut shrunk_minimum = minimum + near;
+        let mut shrunk_maximum = maximum - far;
+        let center = (minimum.0 + maximum.0) / 2.0;
+
+        if shrunk_minimum.x > shrunk_maximum.x {
+            shrunk_minimum.x = center.x;
+            shrunk_maximum.x = center.x;
+        }
+
+        if shrunk_minimum.y > shrunk_maximum.y {
+            shrunk_minimum.y = center.y;
+            shrunk_maximum.y = center.y;
+        }
+
+        (shrunk_minimum, shrunk_maximum)
+    }
+}
+
+/// A discrete rendering quality tier, ordered from highest to lowest quality.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QualityTier {
+    /// Renders continuously without a frame cap.
+    #[default]
+    Full,
+    /// Renders continuously with vsync, which limits the frame rate to the display's refresh rate.
+    Reduced,
+    /// Renders reactively at a low frame rate.
+    Minimal,
+}
+
+impl QualityTier {
+    /// Returns the next-lowest tier, if any.
+    #[inline]
+    #[must_use]
+    pub const fn lower(self) -> Option<Self> {
+        match self {
+            Self::Full => Some(Self::Reduced),
+            Self::Reduced => Some(Self::Minimal),
+            Self::Minimal => None,
+        }
+    }
+
+    /// Returns the next-highest tier, if any.
+    #[inline]
+    #[must_use]
+    pub const fn higher(self) -> Option<Self> {
+        match self {
+            Self::Full => None,
+            Self::Reduced => Some(Self::Full),
+            Self::Minimal => Some(Self::Reduced),
+        }
+    }
+
+    /// Returns the average frame time in seconds above which this tier is considered to be under pressure.
+    #[must_use]
+    pub const fn degrade_frame_time(self) -> f64 {
+        match self {
+            Self::Full => 1.0 / 30.0,
+            Self::Reduced => 1.0 / 20.0,
+            Self::Minimal => f64::INFINITY,
+        }
+    }
+
+    /// Returns the average frame time in seconds below which this tier is considered to be relieved.
+    ///
+    /// This is intentionally lower than the degrade threshold of the next-highest tier to prevent oscillation.
+    #[must_use]
+    pub const fn restore_frame_time(self) -> f64 {
+        match self {
+            Self::Full => 0.0,
+            Self::Reduced => 1.0 / 45.0,
+            Self::Minimal => 1.0 / 28.0,
+        }
+    }
+
+    /// Returns the window present mode used by this tier.
+    #[must_use]
+    pub const fn present_mode(self) -> PresentMode {
+        match self {
+            Self::Full => PresentMode::AutoNoVsync,
+            Self::Reduced | Self::Minimal => PresentMode::AutoVsync,
+        }
+    }
+
+    /// Returns the application update mode used by this tier.
+    #[must_use]
+    pub fn update_mode(self) -> UpdateMode {
+        match self {
+            Self::Full | Self::Reduced => UpdateMode::Continuous,
+            Self::Minimal => UpdateMode::reactive_low_power(Duration::from_secs_f64(1.0 / 30.0)),
+        }
+    }
+}
+
+/// Tracks sustained frame time pressure and selects the current quality tier.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Resource)]
+pub struct QualityGovernor {
+    /// The current tier.
+    tier: QualityTier,
+    /// The tier that the governor is pinned to, if any.
+    pinned: Option<QualityTier>,
+    /// The number of seconds that the current tier has continuously been under pressure.
+    pressure: f64,
+    /// The number of seconds that the current tier has continuously been relieved.
+    relief: f64,
+}
+
+impl QualityGovernor {
+    /// Creates a new [`QualityGovernor`], optionally pinned to the given tier.
+    #[inline]
+    pub const fn new(pinned: Option<QualityTier>) -> Self {
+        let tier = match pinned {
+            Some(tier) => tier,
+            None => QualityTier::Full,
+        };
+
+        Self { tier, pinned, pressure: 0.0, relief: 0.0 }
+    }
+
+    /// Returns the current tier.
+    #[inline]
+    #[must_use]
+    pub const fn tier(&self) -> QualityTier {
+        self.tier
+    }
+
+    /// Returns `true` if the governor is pinned to a single tier.
+    #[inline]
+    #[must_use]
+    pub const fn is_pinned(&self) -> bool {
+        self.pinned.is_some()
+    }
+
+    /// Records an average frame time in seconds observed over the given number of seconds, returning the new tier if
+    /// it changed.
+    pub fn observe(&mut self, frame_time: f64, delta: f64) -> Option<QualityTier> {
+        if self.is_pinned() {
+            return None;
+        }
+
+        self.pressure = if frame_time > self.tier.degrade_frame_time() { self.pressure + delta } else { 0.0 };
+        self.relief = if frame_time < self.tier.restore_frame_time() { self.relief + delta } else { 0.0 };
+
+        let next_tier = if self.pressure >= QUALITY_DEGRADE_DWELL {
+            self.tier.lower()
+        } else if self.relief >= QUALITY_RESTORE_DWELL {
+            self.tier.higher()
+        } else {
+            None
+        };
+
+        if let Some(tier) = next_tier {
+            self.tier = tier;
+            self.pressure = 0.0;
+            self.relief = 0.0;
+        }
+
+        next_tier
+    }
+}
+
+/// The route that the cube baby patrols while idle.
+#[derive(Clone, Debug, Default, PartialEq, Resource)]
+pub struct PatrolRoute {
+    /// The route's waypoints, as fractions of the display's size.
+    waypoints: Vec<Vec2>,
+}
+
+impl PatrolRoute {
+    /// Creates a new [`PatrolRoute`] from waypoints given as percentages of the display's width and height.
+    ///
+    /// Waypoints that fall outside of the display are clamped into it, and non-finite waypoints are skipped.
+    pub fn new(percentages: &[[f32; 2]]) -> Self {
+        let waypoints = percentages.iter().enumerate().filter_map(|(index, &[x, y])| {
+            if !x.is_finite() || !y.is_finite() {
+                warn!("skipping patrol waypoint #{index}, as ({x}, {y}) is not a valid position");
+
+                return None;
+            }
+
+            let clamped = Vec2::new(x, y).clamp(Vec2::ZERO, Vec2::splat(100.0));
+
+            if clamped != Vec2::new(x, y) {
+                warn!("clamping patrol waypoint #{index} from ({x}, {y}) to ({}, {})", clamped.x, clamped.y);
+            }
+
+            Some(clamped / 100.0)
+        });
+
+        Self { waypoints: waypoints.collect() }
+    }
+
+    /// Returns the number of waypoints in this route.
     #[inline]
-    pub const fn minimum_position(&self) -> IVec2 {
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.waypoints.len()
+    }
+
+    /// Returns `true` if this route has no waypoints.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.waypoints.is_empty()
+    }
+
+    /// Returns the index of the waypoint that follows the one at the given index, looping back to the first.
+    #[inline]
+    #[must_use]
+    pub fn next(&self, index: usize) -> usize {
+        (index + 1).checked_rem(self.len()).unwrap_or(0)
+    }
+
+    /// Returns the window position that corresponds to the waypoint at the given index on the given display.
+    ///
+    /// Waypoints refer to the center of the window, and are clamped so that the window never leaves the display.
+    pub fn target(&self, index: usize, display_properties: &DisplayProperties) -> Option<ScreenPos> {
+        let waypoint = self.waypoints.get(index)?;
+        let window_extent = display_properties.window_extent();
+        let minimum_position = display_properties.minimum_position();
+        let maximum_position = display_properties.maximum_position() - window_extent;
+        let center = minimum_position + ((display_properties.maximum_position() - minimum_position) * *waypoint);
+        let target = center - (window_extent / 2.0);
+
+        Some(ScreenPos(target.clamp(minimum_position.0, maximum_position.max(minimum_position.0))))
+    }
+}
+
+/// A snapshot of the cube baby's state, taken while the primary window is being recreated.
+#[derive(Clone, Debug, PartialEq, Resource)]
+pub struct WindowSnapshot {
+    /// The cube baby's position.
+    pub position: Position,
+    /// The cube baby's velocity.
+    pub velocity: Velocity,
+    /// The cube baby's push delays.
+    pub push_delays: PushDelays,
+    /// The cube baby's distance since its sprite was last updated.
+    pub distance: Distance,
+    /// The cube baby's texture atlas index.
+    pub atlas_index: usize,
+    /// The cube baby's patrol progress, if it was patrolling.
+    pub patrolling: Option<Patrolling>,
+    /// The size that the recreated window should have.
+    pub window_size: f32,
+}
+
+impl WindowSnapshot {
+    /// Captures the given cube baby's state, to be restored into a window of the given size.
+    #[must_use]
+    pub fn capture(
+        window_size: f32,
+        position: &Position,
+        velocity: &Velocity,
+        push_delays: &PushDelays,
+        distance: &Distance,
+        sprite: &Sprite,
+        patrolling: Option<&Patrolling>,
+    ) -> Self {
+        Self {
+            position: *position,
+            velocity: *velocity,
+            push_delays: *push_delays,
+            distance: *distance,
+            atlas_index: sprite.texture_atlas.as_ref().map_or(0, |v| v.index),
+            patrolling: patrolling.cloned(),
+            window_size,
+        }
+    }
+
+    /// Restores the captured state onto the given cube baby, returning its patrol progress if it was patrolling.
+    pub fn restore(
+        &self,
+        position: &mut Position,
+        velocity: &mut Velocity,
+        push_delays: &mut PushDelays,
+        distance: &mut Distance,
+        sprite: &mut Sprite,
+    ) -> Option<Patrolling> {
+        *position = self.position;
+        *velocity = self.velocity;
+        *push_delays = self.push_delays;
+        *distance = self.distance;
+
+        if let Some(texture_atlas) = sprite.texture_atlas.as_mut() {
+            texture_atlas.index = self.atlas_index;
+        }
+
+        self.patrolling.clone()
+    }
+}
+
+/// The socket used to communicate with other running instances.
+#[derive(Debug, Resource)]
+pub struct PeerSocket {
+    /// The bound socket.
+    socket: UdpSocket,
+    /// The port that the socket is bound to.
+    port: u16,
+}
+
+impl PeerSocket {
+    /// Binds a new [`PeerSocket`] to the first available peer port.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if every peer port is unavailable.
+    pub fn bind() -> std::io::Result<Self> {
+        let mut last_error = None;
+
+        for port in peers::FIRST_PORT .. peers::FIRST_PORT + peers::PORT_COUNT {
+            match UdpSocket::bind((Ipv4Addr::LOCALHOST, port)) {
+                Ok(socket) => {
+                    socket.set_nonblocking(true)?;
+
+                    return Ok(Self { socket, port });
+                }
+                Err(error) => last_error = Some(error),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| ErrorKind::AddrInUse.into()))
+    }
+
+    /// Returns the port that this socket is bound to.
+    #[inline]
+    #[must_use]
+    pub const fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Sends the given message to every other peer port.
+    pub fn broadcast(&self, message: &PeerMessage) {
+        let bytes = message.encode();
+
+        for port in (peers::FIRST_PORT .. peers::FIRST_PORT + peers::PORT_COUNT).filter(|v| *v != self.port) {
+            // Most ports will not have a listener, so failures are expected and ignored.
+            let _ = self.socket.send_to(&bytes, (Ipv4Addr::LOCALHOST, port));
+        }
+    }
+
+    /// Returns an iterator over every valid message that has been received since the last call.
+    pub fn receive(&self) -> impl Iterator<Item = PeerMessage> + '_ {
+        // Leave room for an extra byte so that oversized messages are never mistaken for valid ones.
+        let mut buffer = [0; PeerMessage::SIZE + 1];
+
+        std::iter::from_fn(move || {
+            loop {
+                match self.socket.recv(&mut buffer) {
+                    Ok(length) => match PeerMessage::decode(&buffer[.. length]) {
+                        Some(message) => return Some(message),
+                        None => continue,
+                    },
+                    // Windows reports sends to ports without a listener as a reset on the next receive.
+                    Err(error) if error.kind() == ErrorKind::ConnectionReset => continue,
+                    Err(_) => return None,
+                }
+            }
+        })
+    }
+}
+
+/// Tracks the cube babies of other running instances.
+#[derive(Clone, Debug, Default, PartialEq, Resource)]
+pub struct PeerBabies {
+    /// The latest message about each peer's cube babies, alongside the time in seconds at which it was received.
+    peers: HashMap<(u32, u32), (PeerMessage, f64)>,
+}
+
+impl PeerBabies {
+    /// Records a message from a peer that was received at the given time in seconds.
+    pub fn insert(&mut self, message: PeerMessage, now: f64) {
+        self.peers.insert((message.id, message.baby), (message, now));
+    }
+
+    /// Forgets every peer that has not been heard from recently.
+    pub fn age_out(&mut self, now: f64) {
+        self.peers.retain(|_, (_, received)| now - *received <= PEER_TIMEOUT);
+    }
+
+    /// Returns an iterator over the latest message about each known peer cube baby.
+    pub fn iter(&self) -> impl Iterator<Item = &PeerMessage> {
+        self.peers.values().map(|(message, _)| message)
+    }
+
+    /// Returns the number of known peer cube babies.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.peers.len()
+    }
+
+    /// Returns `true` if there are no known peer cube babies.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.peers.is_empty()
+    }
+}
+
+/// The sources that may trigger the cube baby to evade a screen capture.
+#[derive(Debug, Default, Resource, Deref, DerefMut)]
+pub struct EvasionTriggers(pub Vec<Box<dyn EvasionTrigger>>);
+
+/// Limits how often the window's position and animation are presented.
+#[derive(Clone, Debug, Default, PartialEq, Resource)]
+pub struct PresentationThrottle {
+    /// The timer between presentations, if presentation is being limited.
+    timer: Option<Timer>,
+}
+
+impl PresentationThrottle {
+    /// Creates a new [`PresentationThrottle`] that presents the given number of times per second.
+    pub fn new(rate: f32) -> Self {
+        Self { timer: Some(Timer::from_seconds(1.0 / rate.max(1.0), TimerMode::Repeating)) }
+    }
+
+    /// Advances the throttle by the given amount of time.
+    pub fn tick(&mut self, delta: Duration) {
+        if let Some(timer) = self.timer.as_mut() {
+            timer.tick(delta);
+        }
+    }
+
+    /// Returns `true` if the window should be presented this frame.
+    #[inline]
+    #[must_use]
+    pub fn is_ready(&self) -> bool {
+        self.timer.as_ref().is_none_or(Timer::just_finished)
+    }
+}
+
+/// Tracks whether the application is running in safe mode, and which optional subsystems it has skipped.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Resource)]
+pub struct SafeMode {
+    /// Whether safe mode is enabled.
+    enabled: bool,
+    /// The names of the optional subsystems that were skipped.
+    skipped: Vec<&'static str>,
+}
+
+impl SafeMode {
+    /// The command-line flag that enables safe mode.
+    pub const FLAG: &str = "--safe-mode";
+
+    /// Creates a new [`SafeMode`] that has not yet skipped any optional subsystems.
+    #[inline]
+    #[must_use]
+    pub const fn new(enabled: bool) -> Self {
+        Self { enabled, skipped: Vec::new() }
+    }
+
+    /// Creates a new [`SafeMode`] that is enabled if the application was launched with [`SafeMode::FLAG`].
+    #[must_use]
+    pub fn from_args() -> Self {
+        Self::new(std::env::args().skip(1).any(|v| v == Self::FLAG))
+    }
+
+    /// Returns `true` if safe mode is enabled.
+    #[inline]
+    #[must_use]
+    pub const fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns `true` if the given optional subsystem may be registered, remembering it as skipped otherwise.
+    pub fn allows(&mut self, subsystem: &'static str) -> bool {
+        if self.enabled {
+            self.skipped.push(subsystem);
+        }
+
+        !self.enabled
+    }
+
+    /// Returns the names of the optional subsystems that were skipped.
+    #[inline]
+    #[must_use]
+    pub fn skipped(&self) -> &[&'static str] {
+        &self.skipped
+    }
+}
+
+/// Set once the operating system asks the application to shut down, such as when the user logs off.
+#[derive(Clone, Debug, Default, Resource)]
+pub struct ShutdownSignal(Arc<AtomicBool>);
+
+impl ShutdownSignal {
+    /// Installs handlers for the platform's termination signals, returning the signal that they set.
+    ///
+    /// This handles `SIGINT`, `SIGTERM`, and `SIGHUP` on Unix, and console control events on Windows. The handlers only
+    /// ever set the signal, since they may run at any time on another thread.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the handlers could not be installed.
+    pub fn install() -> Result<Self, ctrlc::Error> {
+        let signal = Self::default();
+        let handler_signal = signal.clone();
+
+        ctrlc::set_handler(move || handler_signal.request())?;
+
+        Ok(signal)
+    }
+
+    /// Requests that the application shuts down.
+    pub fn request(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the application has been asked to shut down.
+    #[inline]
+    #[must_use]
+    pub fn is_requested(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Tracks the cursor's position on the desktop, even while it is not over the window.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Resource)]
+pub struct GlobalCursor {
+    /// The cursor's latest position.
+    position: Option<ScreenPos>,
+    /// The cursor's position before the latest update.
+    previous: Option<ScreenPos>,
+}
+
+impl GlobalCursor {
+    /// Records the cursor's latest position.
+    pub fn update(&mut self, position: Option<ScreenPos>) {
+        self.previous = self.position;
+        self.position = position;
+    }
+
+    /// Returns the cursor's latest position.
+    #[inline]
+    #[must_use]
+    pub const fn position(&self) -> Option<ScreenPos> {
         self.position
     }
 
-    /// Returns the largest possible position that is contained within this display.
+    /// Returns the cursor's position before the latest update.
     #[inline]
-    pub const fn maximum_position(&self) -> IVec2 {
-        self.minimum_position().saturating_add_unsigned(self.resolution)
+    #[must_use]
+    pub const fn previous(&self) -> Option<ScreenPos> {
+        self.previous
     }
+}
+
+/// How the direction of a cursor push is decided.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Resource, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PushModel {
+    /// Push in the direction that the cursor moved.
+    #[default]
+    Delta,
+    /// Push away from the cursor, out from the window's center.
+    Radial,
+}
+
+/// How the cube baby behaves on its own.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Resource, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BehaviorMode {
+    /// Wait to be pushed.
+    #[default]
+    Passive,
+    /// Flee from the cursor whenever it comes close.
+    Skittish,
+    /// Lazily follow the cursor around.
+    Following,
+    /// Circle around the cursor while it moves.
+    Orbiting,
+}
 
-    /// Returns the position at the center of this display.
+impl BehaviorMode {
+    /// Returns the behavior mode that follows this one when cycling through them.
     #[inline]
-    pub const fn center_position(&self) -> IVec2 {
-        self.minimum_position().saturating_add_unsigned(self.resolution.saturating_div(UVec2::splat(2)))
+    #[must_use]
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Passive => Self::Skittish,
+            Self::Skittish => Self::Following,
+            Self::Following => Self::Orbiting,
+            Self::Orbiting => Self::Passive,
+        }
     }
 
-    /// Returns `true` if this display contains the given position.
-    pub const fn contains(&self, position: IVec2) -> bool {
-        self.minimum_position().x < position.x
-            && self.maximum_position().x > position.x
-            && self.minimum_position().y < position.y
-            && self.maximum_position().y > position.y
+    /// Returns the given behavior mode, or the passive behavior mode if this is already the given mode.
+    #[inline]
+    #[must_use]
+    pub fn toggled(self, mode: Self) -> Self {
+        if self == mode { Self::Passive } else { mode }
+    }
+}
+
+/// Tracks how long the exit key has been held for.
+#[derive(Clone, Debug, PartialEq, Resource)]
+pub struct ExitHold {
+    /// The timer that finishes once the key has been held long enough.
+    timer: Timer,
+}
+
+impl ExitHold {
+    /// Creates a new [`ExitHold`] that finishes after the key is held for the given number of seconds.
+    pub fn new(duration: f32) -> Self {
+        Self { timer: Timer::from_seconds(duration, TimerMode::Once) }
+    }
+
+    /// Advances the hold by the given amount of time, returning `true` once it has finished.
+    pub fn tick(&mut self, delta: Duration) -> bool {
+        self.timer.tick(delta).finished()
+    }
+
+    /// Cancels the hold.
+    pub fn reset(&mut self) {
+        self.timer.reset();
+    }
+
+    /// Returns the hold's progress from zero to one.
+    #[inline]
+    #[must_use]
+    pub fn progress(&self) -> f32 {
+        self.timer.fraction()
+    }
+}
+
+impl Default for ExitHold {
+    fn default() -> Self {
+        Self::new(EXIT_HOLD_DURATION)
+    }
+}
+
+/// The extra cube babies that have been spawned, from oldest to newest.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Resource)]
+pub struct ExtraBabies(pub Vec<ExtraBaby>);
+
+/// The entities that make up an extra cube baby.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ExtraBaby {
+    /// The cube baby itself.
+    pub baby: Entity,
+    /// The window that the cube baby is shown in.
+    pub window: Entity,
+    /// The camera that renders the cube baby into its window, which also parents the window's borders.
+    pub camera: Entity,
+}
+
+/// Whether the window passes all mouse input through to whatever is underneath it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Resource, Deref, DerefMut)]
+pub struct ClickThrough(pub bool);
+
+/// The multiplier applied to the passage of time within the cube baby's physics.
+#[derive(Clone, Copy, Debug, PartialEq, Resource, Deref, DerefMut)]
+pub struct SimulationSpeed(pub f32);
+
+impl Default for SimulationSpeed {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// The random number generator shared by every system, so that random behavior can be reproduced from a seed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Resource, Deref, DerefMut)]
+pub struct Rng(pub fastrand::Rng);
+
+impl Rng {
+    /// Creates a new [`Rng`] with the given seed.
+    #[inline]
+    #[must_use]
+    pub fn with_seed(seed: u64) -> Self {
+        Self(fastrand::Rng::with_seed(seed))
+    }
+}
+
+/// A gentle, slowly shifting wind that pushes the cube babies around the display.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Resource)]
+pub struct Wind {
+    /// The wind's current force, in logical pixels per second squared.
+    pub force: Vec2,
+    /// The force that the wind is shifting towards, in logical pixels per second squared.
+    pub target: Vec2,
+}
+
+impl Wind {
+    /// Shifts the wind's force towards its target by up to the given amount, choosing a new random target no stronger
+    /// than the given strength once it has been reached.
+    pub fn shift(&mut self, rng: &mut fastrand::Rng, amount: f32, strength: f32) {
+        let offset = self.target - self.force;
+
+        if offset.length() > amount {
+            self.force += offset.normalize() * amount;
+        } else {
+            self.force = self.target;
+            self.target = Vec2::from_angle(rng.f32() * std::f32::consts::TAU) * (rng.f32() * strength);
+        }
+    }
+}
+
+/// The cube babies' mood, which the weather and the length of the session may nudge.
+#[derive(Clone, Copy, Debug, PartialEq, Resource)]
+pub struct Mood {
+    /// The multiplier applied to the cube babies' sliding drag, which makes them sluggish when raised.
+    pub drag: f32,
+    /// The multiplier applied to how often the cube baby wanders.
+    pub wander: f32,
+    /// How far the cube babies are tinted gloomy, from zero to one.
+    pub gloom: f32,
+}
+
+impl Mood {
+    /// The mood that leaves the cube babies untouched.
+    pub const NEUTRAL: Self = Self { drag: 1.0, wander: 1.0, gloom: 0.0 };
+
+    /// Returns the mood that results from both of these moods at once.
+    ///
+    /// This is the one rule that every mood is composed by: multipliers multiply, and the gloomier tint wins. The
+    /// result never depends on the order that moods are combined in, and combining with [`Mood::NEUTRAL`] changes
+    /// nothing.
+    #[must_use]
+    pub fn combine(self, other: Self) -> Self {
+        Self { drag: self.drag * other.drag, wander: self.wander * other.wander, gloom: self.gloom.max(other.gloom) }
+    }
+}
+
+impl Default for Mood {
+    fn default() -> Self {
+        Self::NEUTRAL
+    }
+}
+
+/// Every source that nudges the cube babies' mood, which are combined into their [`Mood`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Resource)]
+pub struct MoodModifiers {
+    /// The mood caused by the weather at the configured location.
+    pub weather: Mood,
+    /// The mood caused by the length of the current session.
+    pub tiredness: Mood,
+}
+
+impl MoodModifiers {
+    /// Returns the mood that every modifier combines into.
+    #[must_use]
+    pub fn mood(&self) -> Mood {
+        [self.weather, self.tiredness].into_iter().fold(Mood::NEUTRAL, Mood::combine)
+    }
+}
+
+/// The bounds of the foreground window, which the cube babies may land on top of.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Resource)]
+pub struct ForegroundWindow(pub Option<(ScreenPos, Vec2)>);
+
+impl ForegroundWindow {
+    /// Returns the height of the foreground window's top edge, if a cube baby of the given extent at the given position
+    /// lies across it.
+    pub fn top_edge(&self, position: ScreenPos, extent: Vec2) -> Option<f32> {
+        let (origin, size) = self.0?;
+
+        (position.x + extent.x > origin.x && position.x < origin.x + size.x).then_some(origin.y)
+    }
+
+    /// Returns `true` if a cube baby of the given extent at the given position is sitting on the foreground window.
+    pub fn supports(&self, position: ScreenPos, extent: Vec2) -> bool {
+        self.top_edge(position, extent).is_some_and(|v| (position.y + extent.y - v).abs() < 1.0)
+    }
+}
+
+/// Whether gravity pulls the cube babies down onto the bottom of the display.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Resource, Deref, DerefMut)]
+pub struct Gravity(pub bool);
+
+/// The opaque pixels of each frame of an atlased texture, used to only collide with the visible parts of the sprite.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Resource)]
+pub struct AlphaMask {
+    /// The size of each frame.
+    frame_size: UVec2,
+    /// Whether each pixel of each frame is opaque, in row-major order.
+    frames: Vec<Vec<bool>>,
+}
+
+impl AlphaMask {
+    /// Creates a new [`AlphaMask`] from RGBA8 image data, where pixels with an alpha above the threshold are opaque.
+    ///
+    /// Returns [`None`] if any frame does not fit within the image or the image data is incomplete.
+    #[must_use]
+    pub fn from_rgba(data: &[u8], image_size: UVec2, frames: &[URect], threshold: u8) -> Option<Self> {
+        if data.len() < (image_size.x as usize * image_size.y as usize * 4) {
+            return None;
+        }
+
+        let frame_size = frames.first()?.size();
+        let frames = frames
+            .iter()
+            .map(|frame| {
+                if frame.size() != frame_size || frame.max.cmpgt(image_size).any() {
+                    return None;
+                }
+
+                let pixels =
+                    (frame.min.y .. frame.max.y).flat_map(|y| (frame.min.x .. frame.max.x).map(move |x| (x, y)));
+
+                Some(pixels.map(|(x, y)| data[(((y * image_size.x) + x) as usize * 4) + 3] > threshold).collect())
+            })
+            .collect::<Option<_>>()?;
+
+        Some(Self { frame_size, frames })
+    }
+
+    /// Returns `true` if the given frame is opaque at the given position, in texture pixels from the frame's corner.
+    ///
+    /// Positions outside of the frame are always transparent.
+    #[must_use]
+    pub fn is_opaque(&self, index: usize, position: Vec2) -> bool {
+        if position.cmplt(Vec2::ZERO).any() || position.cmpge(self.frame_size.as_vec2()).any() {
+            return false;
+        }
+
+        let position = position.as_uvec2();
+
+        self.frames.get(index).is_some_and(|v| v[((position.y * self.frame_size.x) + position.x) as usize])
+    }
+}
+
+/// The keys bound to each action.
+#[derive(Clone, Debug, PartialEq, Eq, Resource)]
+pub struct Keybindings(BTreeMap<Action, Vec<ChordKey>>);
+
+impl Keybindings {
+    /// Creates a new [`Keybindings`] from the bindings within the settings file, keeping the default keys for any
+    /// binding that could not be parsed.
+    ///
+    /// Returns a warning for every binding that was ignored, so that mistakes are reported rather than fatal. A mouse
+    /// button may only be bound to one action, so a configured binding that reuses another action's button is dropped.
+    pub fn load(bindings: &BTreeMap<String, Vec<String>>) -> (Self, Vec<String>) {
+        let mut keybindings = Self::default();
+        let mut warnings = Vec::new();
+        let mut configured = Vec::new();
+
+        for (name, keys) in bindings {
+            let action = match name.parse::<Action>() {
+                Ok(action) => action,
+                Err(error) => {
+                    warnings.push(format!("ignoring key binding: {error}"));
+
+                    continue;
+                }
+            };
+
+            match keys.iter().map(|v| v.parse()).collect::<Result<Vec<ChordKey>, _>>() {
+                Ok(keys) => {
+                    keybindings.0.insert(action, keys);
+                    configured.push(action);
+                }
+                Err(error) => warnings.push(format!("using the default keys for '{name}': {error}")),
+            }
+        }
+
+        for action in configured {
+            for key in keybindings.keys(action).to_vec() {
+                let Some(conflict) = Action::ALL
+                    .into_iter()
+                    .find(|v| *v != action && key.mouse_button().is_some() && keybindings.keys(*v).contains(&key))
+                else {
+                    continue;
+                };
+
+                warnings.push(format!(
+                    "ignoring '{key}' for '{}', since it is already bound to '{}'",
+                    action.name(),
+                    conflict.name()
+                ));
+
+                keybindings.0.entry(action).or_default().retain(|v| *v != key);
+            }
+        }
+
+        (keybindings, warnings)
+    }
+
+    /// Returns the keys bound to the given action.
+    #[inline]
+    #[must_use]
+    pub fn keys(&self, action: Action) -> &[ChordKey] {
+        self.0.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns `true` if any key bound to the given action is currently pressed.
+    #[must_use]
+    pub fn pressed(&self, action: Action, input: &ButtonInput<KeyCode>) -> bool {
+        self.keys(action).iter().any(|v| v.pressed(input))
+    }
+
+    /// Returns `true` if any key bound to the given action was pressed this frame.
+    #[must_use]
+    pub fn just_pressed(&self, action: Action, input: &ButtonInput<KeyCode>) -> bool {
+        self.keys(action).iter().any(|v| v.just_pressed(input))
+    }
+
+    /// Returns the mouse buttons bound to the given action.
+    pub fn mouse_buttons(&self, action: Action) -> impl Iterator<Item = MouseButton> + '_ {
+        self.keys(action).iter().filter_map(|v| v.mouse_button())
+    }
+
+    /// Returns `true` if any mouse button bound to the given action is currently pressed.
+    #[must_use]
+    pub fn mouse_pressed(&self, action: Action, input: &ButtonInput<MouseButton>) -> bool {
+        self.keys(action).iter().any(|v| v.mouse_pressed(input))
+    }
+
+    /// Returns `true` if any mouse button bound to the given action was pressed this frame.
+    #[must_use]
+    pub fn mouse_just_pressed(&self, action: Action, input: &ButtonInput<MouseButton>) -> bool {
+        self.keys(action).iter().any(|v| v.mouse_just_pressed(input))
+    }
+
+    /// Returns `true` if the given key is bound to any action.
+    #[must_use]
+    pub fn is_bound(&self, key_code: KeyCode) -> bool {
+        self.0.values().flatten().any(|v| v.key_codes().is_some_and(|v| v.contains(&key_code)))
+    }
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        Self(Action::ALL.into_iter().map(|v| (v, v.default_keys().to_vec())).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::Chord;
+    use crate::settings::Settings;
+
+    /// Writes the given bindings into a settings file and loads them back.
+    fn round_trip(keybindings: &Keybindings) -> (Keybindings, Vec<String>) {
+        let mut string = String::from("[keybindings]\n");
+
+        for (action, keys) in &keybindings.0 {
+            let keys = keys.iter().map(|v| format!("{:?}", v.to_string())).collect::<Vec<_>>().join(", ");
+
+            string.push_str(&format!("{} = [{keys}]\n", action.name()));
+        }
+
+        let settings: Settings = toml::from_str(&string).expect("invalid settings");
+
+        Keybindings::load(&settings.keybindings)
+    }
+
+    #[test]
+    fn default_keybindings_round_trip() {
+        let keybindings = Keybindings::default();
+
+        assert_eq!(round_trip(&keybindings), (keybindings, Vec::new()));
+    }
+
+    #[test]
+    fn custom_keybindings_round_trip() {
+        let mut keybindings = Keybindings::default();
+
+        keybindings.0.insert(Action::Knock, vec![ChordKey::Control, ChordKey::Key(KeyCode::KeyK)]);
+        keybindings.0.insert(Action::Pause, vec![ChordKey::Key(KeyCode::F12), ChordKey::Key(KeyCode::PageDown)]);
+        keybindings.0.insert(Action::Center, vec![ChordKey::Super, ChordKey::Key(KeyCode::Digit0)]);
+        keybindings.0.insert(Action::Quit, Vec::new());
+
+        assert_eq!(round_trip(&keybindings), (keybindings, Vec::new()));
+    }
+
+    #[test]
+    fn invalid_keybindings_fall_back_to_defaults() {
+        let bindings = BTreeMap::from([
+            ("knock".to_string(), vec!["k".to_string(), "hyper".to_string()]),
+            ("dance".to_string(), vec!["d".to_string()]),
+            ("pause".to_string(), vec!["Shift".to_string(), "F2".to_string()]),
+        ]);
+
+        let (keybindings, warnings) = Keybindings::load(&bindings);
+
+        assert_eq!(keybindings.keys(Action::Knock), Action::Knock.default_keys());
+        assert_eq!(keybindings.keys(Action::Pause), [ChordKey::Shift, ChordKey::Key(KeyCode::F2)]);
+        assert_eq!(warnings.len(), 2);
+    }
+
+    #[test]
+    fn mouse_buttons_are_parsed() {
+        let bindings = BTreeMap::from([
+            ("knock".to_string(), vec!["MouseMiddle".to_string(), "k".to_string()]),
+            ("pause".to_string(), vec!["mouseback".to_string(), "mouseforward".to_string(), "mouse8".to_string()]),
+            ("center".to_string(), vec!["mousewheel".to_string()]),
+        ]);
+
+        let (keybindings, warnings) = Keybindings::load(&bindings);
+
+        assert_eq!(keybindings.keys(Action::Knock), [
+            ChordKey::Mouse(MouseButton::Middle),
+            ChordKey::Key(KeyCode::KeyK)
+        ]);
+        assert_eq!(keybindings.keys(Action::Pause), [
+            ChordKey::Mouse(MouseButton::Back),
+            ChordKey::Mouse(MouseButton::Forward),
+            ChordKey::Mouse(MouseButton::Other(8))
+        ]);
+        assert_eq!(keybindings.keys(Action::Center), Action::Center.default_keys());
+        assert_eq!(warnings.len(), 1);
+        assert!("ctrl+mouseleft".parse::<Chord>().is_err());
+    }
+
+    #[test]
+    fn conflicting_mouse_buttons_are_rejected() {
+        let bindings = BTreeMap::from([
+            ("knock".to_string(), vec!["mouseleft".to_string(), "space".to_string()]),
+            ("pause".to_string(), vec!["mousemiddle".to_string()]),
+            ("quit".to_string(), vec!["mousemiddle".to_string()]),
+        ]);
+
+        let (keybindings, warnings) = Keybindings::load(&bindings);
+
+        assert_eq!(keybindings.keys(Action::Grab), [ChordKey::Mouse(MouseButton::Left)]);
+        assert_eq!(keybindings.keys(Action::Knock), [ChordKey::Key(KeyCode::Space)]);
+        assert!(keybindings.keys(Action::Pause).is_empty());
+        assert_eq!(keybindings.keys(Action::Quit), [ChordKey::Mouse(MouseButton::Middle)]);
+        assert_eq!(warnings, [
+            "ignoring 'mouseleft' for 'knock', since it is already bound to 'grab'",
+            "ignoring 'mousemiddle' for 'pause', since it is already bound to 'quit'",
+        ]);
+    }
+
+    #[test]
+    fn default_mouse_buttons_match_the_original_behavior() {
+        let keybindings = Keybindings::default();
+
+        assert_eq!(keybindings.mouse_buttons(Action::Grab).collect::<Vec<_>>(), [MouseButton::Left]);
+        assert_eq!(keybindings.mouse_buttons(Action::Menu).collect::<Vec<_>>(), [MouseButton::Right]);
+        assert!(
+            Action::ALL
+                .into_iter()
+                .filter(|v| !matches!(v, Action::Grab | Action::Menu))
+                .all(|v| keybindings.mouse_buttons(v).next().is_none())
+        );
+    }
+
+    #[test]
+    fn moods_compose_in_any_order() {
+        let rainy = Mood { drag: 1.5, wander: 0.5, gloom: 0.35 };
+        let tired = Mood { drag: 1.25, wander: 0.75, gloom: 0.0 };
+
+        assert_eq!(rainy.combine(Mood::NEUTRAL), rainy);
+        assert_eq!(rainy.combine(tired), tired.combine(rainy));
+        assert_eq!(rainy.combine(tired), Mood { drag: 1.875, wander: 0.375, gloom: 0.35 });
+        assert_eq!(MoodModifiers::default().mood(), Mood::NEUTRAL);
+        assert_eq!(MoodModifiers { weather: rainy, tiredness: tired }.mood(), rainy.combine(tired));
+    }
+
+    #[test]
+    fn safe_mode_allows_everything_when_disabled() {
+        let mut safe_mode = SafeMode { enabled: false, skipped: Vec::new() };
+
+        assert!(safe_mode.allows("heatmap"));
+        assert!(safe_mode.allows("peers"));
+        assert!(!safe_mode.is_enabled());
+        assert!(safe_mode.skipped().is_empty());
+    }
+
+    #[test]
+    fn safe_mode_skips_every_optional_subsystem() {
+        let mut safe_mode = SafeMode { enabled: true, skipped: Vec::new() };
+        let subsystems = ["settings", "platform probes", "heatmap", "peers"];
+
+        assert!(subsystems.into_iter().all(|v| !safe_mode.allows(v)));
+        assert!(safe_mode.is_enabled());
+        assert_eq!(safe_mode.skipped(), subsystems);
+    }
+
+    #[test]
+    fn seeded_wind_is_deterministic() {
+        let simulate = |seed| {
+            let mut rng = Rng::with_seed(seed);
+            let mut wind = Wind::default();
+
+            (0 .. 256)
+                .map(|_| {
+                    wind.shift(&mut rng.0, 4.0, 64.0);
+
+                    wind
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(simulate(7), simulate(7));
+        assert_ne!(simulate(7), simulate(8));
+    }
+
+    #[test]
+    fn window_snapshots_round_trip() {
+        let mut patrolling = Patrolling::new(2.0);
+
+        patrolling.index = 3;
+        patrolling.dwell.tick(Duration::from_secs_f32(0.5));
+
+        let sprite =
+            Sprite { texture_atlas: Some(TextureAtlas { index: 5, layout: Handle::default() }), ..Sprite::default() };
+        let snapshot = WindowSnapshot::capture(
+            96.0,
+            &Position(ScreenPos::new(120.0, 340.0)),
+            &Velocity(Vec2::new(-80.0, 15.0)),
+            &PushDelays { mouse: 0.1, keyboard: 0.2, gamepad: 0.0 },
+            &Distance(12.5),
+            &sprite,
+            Some(&patrolling),
+        );
+
+        let mut position = Position(ScreenPos::new(0.0, 0.0));
+        let mut velocity = Velocity(Vec2::ZERO);
+        let mut push_delays = PushDelays::ZERO;
+        let mut distance = Distance(0.0);
+        let mut sprite =
+            Sprite { texture_atlas: Some(TextureAtlas { index: 0, layout: Handle::default() }), ..Sprite::default() };
+
+        let restored = snapshot.restore(&mut position, &mut velocity, &mut push_delays, &mut distance, &mut sprite);
+
+        assert_eq!(position, Position(ScreenPos::new(120.0, 340.0)));
+        assert_eq!(velocity, Velocity(Vec2::new(-80.0, 15.0)));
+        assert_eq!(push_delays, PushDelays { mouse: 0.1, keyboard: 0.2, gamepad: 0.0 });
+        assert_eq!(distance, Distance(12.5));
+        assert_eq!(sprite.texture_atlas.map(|v| v.index), Some(5));
+        assert_eq!(restored, Some(patrolling));
+        assert_eq!(snapshot.window_size, 96.0);
+    }
+
+    #[test]
+    fn window_snapshots_without_patrols_restore_none() {
+        let snapshot = WindowSnapshot::capture(
+            64.0,
+            &Position(ScreenPos::new(1.0, 2.0)),
+            &Velocity(Vec2::ZERO),
+            &PushDelays::ZERO,
+            &Distance(0.0),
+            &Sprite::default(),
+            None,
+        );
+        let mut sprite = Sprite::default();
+
+        let restored = snapshot.restore(
+            &mut Position(ScreenPos::new(0.0, 0.0)),
+            &mut Velocity(Vec2::ONE),
+            &mut PushDelays::ZERO,
+            &mut Distance(1.0),
+            &mut sprite,
+        );
+
+        assert_eq!(restored, None);
+        assert_eq!(snapshot.atlas_index, 0);
+    }
+
+    /// A frame time that puts every tier but the lowest under pressure.
+    const SLOW_FRAME_TIME: f64 = 1.0 / 10.0;
+    /// A frame time that relieves every tier.
+    const FAST_FRAME_TIME: f64 = 1.0 / 120.0;
+
+    /// Returns a governor that has already been lowered to the reduced tier.
+    fn reduced_governor() -> QualityGovernor {
+        let mut governor = QualityGovernor::new(None);
+
+        assert_eq!(governor.observe(SLOW_FRAME_TIME, QUALITY_DEGRADE_DWELL), Some(QualityTier::Reduced));
+
+        governor
+    }
+
+    #[test]
+    fn quality_degrades_after_the_dwell() {
+        let mut governor = QualityGovernor::new(None);
+
+        assert_eq!(governor.observe(SLOW_FRAME_TIME, QUALITY_DEGRADE_DWELL - 0.5), None);
+        assert_eq!(governor.tier(), QualityTier::Full);
+        assert_eq!(governor.observe(SLOW_FRAME_TIME, 0.5), Some(QualityTier::Reduced));
+        assert_eq!(governor.tier(), QualityTier::Reduced);
+
+        // The dwell starts over for every tier.
+        assert_eq!(governor.observe(SLOW_FRAME_TIME, QUALITY_DEGRADE_DWELL - 0.5), None);
+        assert_eq!(governor.observe(SLOW_FRAME_TIME, 0.5), Some(QualityTier::Minimal));
+        assert_eq!(governor.observe(SLOW_FRAME_TIME, QUALITY_DEGRADE_DWELL * 10.0), None);
+        assert_eq!(governor.tier(), QualityTier::Minimal);
+    }
+
+    #[test]
+    fn quality_pressure_resets_on_a_single_good_frame() {
+        let mut governor = QualityGovernor::new(None);
+
+        assert_eq!(governor.observe(SLOW_FRAME_TIME, QUALITY_DEGRADE_DWELL - 0.5), None);
+        assert_eq!(governor.observe(FAST_FRAME_TIME, 0.01), None);
+        assert_eq!(governor.observe(SLOW_FRAME_TIME, 0.5), None);
+        assert_eq!(governor.tier(), QualityTier::Full);
+    }
+
+    #[test]
+    fn quality_restores_with_hysteresis() {
+        let mut governor = reduced_governor();
+        // Fast enough to no longer pressure the full tier, yet too slow to relieve the reduced tier.
+        let frame_time = (QualityTier::Reduced.restore_frame_time() + QualityTier::Full.degrade_frame_time()) / 2.0;
+
+        assert!(frame_time < QualityTier::Full.degrade_frame_time());
+        assert_eq!(governor.observe(frame_time, QUALITY_RESTORE_DWELL * 10.0), None);
+        assert_eq!(governor.tier(), QualityTier::Reduced);
+
+        assert_eq!(governor.observe(FAST_FRAME_TIME, QUALITY_RESTORE_DWELL - 0.5), None);
+        assert_eq!(governor.observe(FAST_FRAME_TIME, 0.5), Some(QualityTier::Full));
+        assert_eq!(governor.tier(), QualityTier::Full);
+    }
+
+    #[test]
+    fn pinned_quality_never_changes() {
+        for tier in [QualityTier::Full, QualityTier::Reduced, QualityTier::Minimal] {
+            let mut governor = QualityGovernor::new(Some(tier));
+
+            assert!(governor.is_pinned());
+            assert_eq!(governor.observe(SLOW_FRAME_TIME, QUALITY_DEGRADE_DWELL * 10.0), None);
+            assert_eq!(governor.observe(FAST_FRAME_TIME, QUALITY_RESTORE_DWELL * 10.0), None);
+            assert_eq!(governor.tier(), tier);
+        }
+    }
+
+    #[test]
+    fn padded_textures_split_into_frames() {
+        let frame = 32;
+        let texture_metadata = TextureMetadata {
+            image_handle: Handle::default(),
+            layout_handle: Handle::default(),
+            size: TexturePx::new(3 + (frame * ATLAS_FRAMES) + (2 * (ATLAS_FRAMES - 1)), 1 + frame),
+            padding: TexturePx::new(2, 0),
+            offset: TexturePx::new(3, 1),
+        };
+
+        assert_eq!(texture_metadata.frame_size(), TexturePx::new(frame, frame));
+
+        let layout = texture_metadata.layout();
+
+        assert_eq!(layout.size, texture_metadata.size.0);
+        assert_eq!(layout.textures.len(), ATLAS_FRAMES as usize);
+
+        for (index, rect) in (0 ..).zip(&layout.textures) {
+            let minimum = UVec2::new(3 + ((frame + 2) * index), 1);
+
+            assert_eq!((rect.min, rect.max), (minimum, minimum + UVec2::splat(frame)));
+        }
+    }
+
+    #[test]
+    fn unpadded_textures_split_into_frames() {
+        let texture_metadata = TextureMetadata {
+            image_handle: Handle::default(),
+            layout_handle: Handle::default(),
+            size: TexturePx::new(16 * ATLAS_FRAMES, 16),
+            padding: TexturePx::ZERO,
+            offset: TexturePx::ZERO,
+        };
+
+        assert_eq!(texture_metadata.frame_size(), TexturePx::new(16, 16));
+
+        for (index, rect) in (0 ..).zip(&texture_metadata.layout().textures) {
+            assert_eq!(rect.min, UVec2::new(16 * index, 0));
+        }
+    }
+
+    #[test]
+    fn margins_shrink_the_bounds() {
+        let margins = Margins { left: 8.0, right: 16.0, top: 4.0, bottom: 2.0 };
+        let (minimum, maximum) = margins.shrink(ScreenPos::new(0.0, 0.0), ScreenPos::new(1000.0, 500.0), 64.0, 2.0);
+
+        assert_eq!(minimum, ScreenPos::new(16.0, 8.0));
+        assert_eq!(maximum, ScreenPos::new(968.0, 496.0));
+    }
+
+    #[test]
+    fn negative_margins_are_clamped_to_half_the_window() {
+        let margins = Margins { left: -8.0, right: -100.0, top: -100.0, bottom: 0.0 };
+
+        // The clamp follows the live window size rather than the default one.
+        for window_size in [32.0, 128.0] {
+            let (minimum, maximum) =
+                margins.shrink(ScreenPos::new(0.0, 0.0), ScreenPos::new(1000.0, 500.0), window_size, 1.0);
+
+            assert_eq!(minimum, ScreenPos::new(-8.0, -window_size / 2.0));
+            assert_eq!(maximum, ScreenPos::new(1000.0 + (window_size / 2.0), 500.0));
+        }
+    }
+
+    #[test]
+    fn oversized_margins_collapse_onto_the_center() {
+        let margins = Margins { left: 600.0, right: 600.0, top: 10.0, bottom: 10.0 };
+        let (minimum, maximum) = margins.shrink(ScreenPos::new(100.0, 0.0), ScreenPos::new(1100.0, 500.0), 64.0, 1.0);
+
+        assert_eq!(minimum, ScreenPos::new(600.0, 10.0));
+        assert_eq!(maximum, ScreenPos::new(600.0, 490.0));
+    }
+
+    #[test]
+    fn patrol_routes_skip_non_finite_waypoints() {
+        let route =
+            PatrolRoute::new(&[[f32::NAN, 10.0], [10.0, f32::INFINITY], [50.0, 25.0], [f32::NEG_INFINITY, 0.0]]);
+
+        assert_eq!(route.waypoints, vec![Vec2::new(0.5, 0.25)]);
+    }
+
+    #[test]
+    fn patrol_routes_clamp_waypoints_onto_the_display() {
+        let route = PatrolRoute::new(&[[150.0, -10.0], [100.0, 0.0], [250.0, 250.0]]);
+
+        assert_eq!(route.waypoints, vec![Vec2::new(1.0, 0.0), Vec2::new(1.0, 0.0), Vec2::ONE]);
+    }
+
+    #[test]
+    fn patrol_routes_progress_through_every_waypoint() {
+        let route = PatrolRoute::new(&[[10.0, 10.0], [90.0, 10.0], [100.0, 100.0]]);
+        let display_properties = DisplayProperties {
+            resolution: UVec2::new(1000, 500),
+            use_work_area: false,
+            window_size: 100.0,
+            ..DisplayProperties::default()
+        };
+        let mut index = 0;
+        let mut targets = Vec::new();
+
+        for _ in 0 .. route.len() * 2 {
+            targets.push(route.target(index, &display_properties).expect("missing waypoint"));
+            index = route.next(index);
+        }
+
+        // The last waypoint sits in the corner, so it is clamped to keep the whole window on the display.
+        let expected = [ScreenPos::new(50.0, 0.0), ScreenPos::new(850.0, 0.0), ScreenPos::new(900.0, 400.0)];
+
+        assert_eq!(targets, [expected, expected].concat());
+        assert_eq!(route.target(route.len(), &display_properties), None);
+        assert_eq!(PatrolRoute::new(&[]).next(0), 0);
+    }
+
+    /// Returns a message about the given peer's cube baby at the given position.
+    fn peer_message(id: u32, baby: u32, x: f32) -> PeerMessage {
+        PeerMessage { id, baby, position: ScreenPos::new(x, 0.0), extent: Vec2::splat(64.0), velocity: Vec2::ZERO }
+    }
+
+    #[test]
+    fn peer_babies_are_replaced_by_newer_messages() {
+        let mut peer_babies = PeerBabies::default();
+
+        peer_babies.insert(peer_message(1, 0, 0.0), 0.0);
+        peer_babies.insert(peer_message(1, 1, 10.0), 0.0);
+        peer_babies.insert(peer_message(2, 0, 20.0), 0.0);
+        peer_babies.insert(peer_message(1, 0, 30.0), 0.5);
+
+        assert_eq!(peer_babies.len(), 3);
+        assert!(peer_babies.iter().any(|v| *v == peer_message(1, 0, 30.0)));
+        assert!(!peer_babies.iter().any(|v| *v == peer_message(1, 0, 0.0)));
+    }
+
+    #[test]
+    fn silent_peer_babies_age_out() {
+        let mut peer_babies = PeerBabies::default();
+
+        peer_babies.insert(peer_message(1, 0, 0.0), 0.0);
+        peer_babies.insert(peer_message(2, 0, 0.0), 0.5);
+
+        // Peers are kept for exactly the timeout, and forgotten as soon as it has passed.
+        peer_babies.age_out(PEER_TIMEOUT);
+
+        assert_eq!(peer_babies.len(), 2);
+
+        peer_babies.age_out(PEER_TIMEOUT + 0.25);
+
+        assert_eq!(peer_babies.iter().collect::<Vec<_>>(), [&peer_message(2, 0, 0.0)]);
+
+        // A peer that broadcasts again is kept around for longer.
+        peer_babies.insert(peer_message(2, 0, 5.0), PEER_TIMEOUT + 0.25);
+        peer_babies.age_out(PEER_TIMEOUT + 1.0);
+
+        assert_eq!(peer_babies.len(), 1);
+
+        peer_babies.age_out((PEER_TIMEOUT * 2.0) + 0.5);
+
+        assert!(peer_babies.is_empty());
     }
 }
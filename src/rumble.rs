@@ -0,0 +1,115 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright © 2025 Jaxydog
+//
+// This file is part of Desktop Cube Baby.
+//
+// Desktop Cube Baby is free software: you can redistribute it and/or modify it under the terms of the GNU General
+// Public License as published by the Free Software Foundation, either version 3 of the License, or (at your option)
+// any later version.
+//
+// Desktop Cube Baby is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the
+// implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more
+// details.
+//
+// You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
+// see <https://www.gnu.org/licenses/>.
+
+use std::time::Duration;
+
+use bevy::input::gamepad::GamepadRumbleIntensity;
+
+use crate::SPRITE_SCALE;
+
+/// The impact speed below which bounces do not cause a rumble.
+pub const MINIMUM_SPEED: f32 = 64.0 * SPRITE_SCALE;
+/// The impact speed at which bounces cause the strongest rumble.
+pub const MAXIMUM_SPEED: f32 = 1024.0 * SPRITE_SCALE;
+/// The duration of the weakest rumble.
+pub const MINIMUM_DURATION: Duration = Duration::from_millis(40);
+/// The duration of the strongest rumble.
+pub const MAXIMUM_DURATION: Duration = Duration::from_millis(200);
+
+/// Returns the rumble intensity and duration caused by a bounce at the given impact speed.
+///
+/// Corner bounces drive the strong motor, while bounces off of a single wall only drive the weak motor. Returns
+/// [`None`] if the bounce is too gentle to be felt.
+pub fn bounce_rumble(speed: f32, corner: bool, multiplier: f32) -> Option<(GamepadRumbleIntensity, Duration)> {
+    if speed < MINIMUM_SPEED || multiplier <= 0.0 {
+        return None;
+    }
+
+    let strength = ((speed - MINIMUM_SPEED) / (MAXIMUM_SPEED - MINIMUM_SPEED)).clamp(0.0, 1.0);
+    let motor = (strength * multiplier).clamp(0.0, 1.0);
+    let duration = MINIMUM_DURATION + (MAXIMUM_DURATION - MINIMUM_DURATION).mul_f32(strength);
+
+    let intensity = if corner {
+        GamepadRumbleIntensity { strong_motor: motor, weak_motor: motor }
+    } else {
+        GamepadRumbleIntensity::weak_motor(motor)
+    };
+
+    Some((intensity, duration))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gentle_bounces_do_not_rumble() {
+        assert_eq!(bounce_rumble(0.0, false, 1.0), None);
+        assert_eq!(bounce_rumble(MINIMUM_SPEED - 1.0, true, 1.0), None);
+        assert_eq!(bounce_rumble(MAXIMUM_SPEED, true, 0.0), None);
+    }
+
+    #[test]
+    fn rumbles_span_the_speed_range() {
+        let (intensity, duration) = bounce_rumble(MINIMUM_SPEED, false, 1.0).expect("missing rumble");
+
+        assert_eq!(intensity.weak_motor, 0.0);
+        assert_eq!(duration, MINIMUM_DURATION);
+
+        let (intensity, duration) = bounce_rumble(MAXIMUM_SPEED, false, 1.0).expect("missing rumble");
+
+        assert_eq!(intensity.weak_motor, 1.0);
+        assert_eq!(duration, MAXIMUM_DURATION);
+
+        // Faster impacts than the maximum are no stronger.
+        assert_eq!(bounce_rumble(MAXIMUM_SPEED * 4.0, false, 1.0), Some((intensity, duration)));
+    }
+
+    #[test]
+    fn faster_bounces_rumble_harder_and_longer() {
+        let rumbles = [0.25, 0.5, 0.75].map(|v| {
+            bounce_rumble(MINIMUM_SPEED + ((MAXIMUM_SPEED - MINIMUM_SPEED) * v), false, 1.0).expect("missing rumble")
+        });
+
+        assert!(rumbles.windows(2).all(|v| v[0].0.weak_motor < v[1].0.weak_motor && v[0].1 < v[1].1));
+    }
+
+    #[test]
+    fn only_corners_drive_the_strong_motor() {
+        let (wall, _) = bounce_rumble(MAXIMUM_SPEED, false, 1.0).expect("missing rumble");
+        let (corner, _) = bounce_rumble(MAXIMUM_SPEED, true, 1.0).expect("missing rumble");
+
+        assert_eq!(wall.strong_motor, 0.0);
+        assert_eq!(corner.strong_motor, 1.0);
+        assert_eq!(corner.weak_motor, 1.0);
+    }
+
+    #[test]
+    fn multiplier_scales_intensity_but_not_duration() {
+        let speed = MINIMUM_SPEED + ((MAXIMUM_SPEED - MINIMUM_SPEED) * 0.5);
+        let (full, full_duration) = bounce_rumble(speed, false, 1.0).expect("missing rumble");
+        let (half, half_duration) = bounce_rumble(speed, false, 0.5).expect("missing rumble");
+
+        assert!((half.weak_motor - (full.weak_motor * 0.5)).abs() < 1e-6);
+        assert_eq!(half_duration, full_duration);
+
+        // The motors never exceed their full strength.
+        let (boosted, _) = bounce_rumble(MAXIMUM_SPEED, true, 4.0).expect("missing rumble");
+
+        assert_eq!(boosted.strong_motor, 1.0);
+    }
+}
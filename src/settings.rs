@@ -0,0 +1,1118 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright © 2025 Jaxydog
+//
+// This file is part of Desktop Cube Baby.
+//
+// Desktop Cube Baby is free software: you can redistribute it and/or modify it under the terms of the GNU General
+// Public License as published by the Free Software Foundation, either version 3 of the License, or (at your option)
+// any later version.
+//
+// Desktop Cube Baby is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the
+// implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more
+// details.
+//
+// You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
+// see <https://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::bounds::BoundaryBehavior;
+use crate::events::WallSide;
+use crate::input::Chord;
+use crate::resources::{BehaviorMode, Margins, PushModel, QualityTier};
+use crate::{MAXIMUM_WINDOW_SIZE, MINIMUM_WINDOW_SIZE, PUSH_STRENGTH, SPRITE_SCALE, WINDOW_SIZE};
+
+/// The application's user-configurable settings.
+#[derive(Clone, Debug, Default, PartialEq, Resource, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// The quality governor's settings.
+    pub quality: QualitySettings,
+    /// The patrol route's settings.
+    pub patrol: PatrolSettings,
+    /// The gamepad rumble settings.
+    pub rumble: RumbleSettings,
+    /// The display bounds settings.
+    pub bounds: BoundsSettings,
+    /// The window settings.
+    pub window: WindowSettings,
+    /// The settings for splitting the sprite sheet into frames.
+    pub texture: TextureSettings,
+    /// The settings for interacting with other running instances.
+    pub peers: PeerSettings,
+    /// The settings for evading screen captures.
+    pub evasion: EvasionSettings,
+    /// The settings for pushing the cube baby.
+    pub push: PushSettings,
+    /// The settings for tracking where the cube baby spends its time.
+    pub heatmap: HeatmapSettings,
+    /// The settings for knocking the cube baby.
+    pub knock: KnockSettings,
+    /// The settings for summoning the cube baby to the cursor.
+    pub summon: SummonSettings,
+    /// The settings for repelling the cube baby from a nearby cursor.
+    pub repel: RepelSettings,
+    /// The settings for nudging the cube baby out from under a resting cursor.
+    pub personal_space: PersonalSpaceSettings,
+    /// The settings for the cube baby glancing at an approaching cursor.
+    pub glance: GlanceSettings,
+    /// The settings for patrolling the display edge farthest from the cursor while idle.
+    pub edge_patrol: EdgePatrolSettings,
+    /// The settings for spawning extra cube babies.
+    pub spawning: SpawningSettings,
+    /// The settings for pulling the cube baby down with gravity.
+    pub gravity: GravitySettings,
+    /// The settings for the cube baby wandering off on its own.
+    pub wander: WanderSettings,
+    /// The settings for how the cube baby behaves on its own.
+    pub behavior: BehaviorSettings,
+    /// The settings for the wind that gently pushes the cube baby around.
+    pub wind: WindSettings,
+    /// The settings for the weather nudging the cube baby's mood.
+    pub weather: WeatherSettings,
+    /// The settings for the cube baby acting out how long the session has lasted.
+    pub tiredness: TirednessSettings,
+    /// The settings for how quickly the cube baby's sprite spins as it moves.
+    pub spin: SpinSettings,
+    /// The settings for the cube baby settling into nearby corners.
+    pub corner_snap: CornerSnapSettings,
+    /// The keys bound to each action, by action name.
+    ///
+    /// These are parsed separately so that a mistake only resets the affected binding.
+    pub keybindings: BTreeMap<String, Vec<String>>,
+}
+
+impl Settings {
+    /// The name of the settings file.
+    pub const FILE_NAME: &str = "config.toml";
+
+    /// Returns the path to the settings file, if the platform provides a configuration directory.
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|v| v.join(env!("CARGO_PKG_NAME")).join(Self::FILE_NAME))
+    }
+
+    /// Loads the settings file, returning the default settings if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file exists but could not be read or parsed.
+    pub fn load() -> Result<Self, SettingsError> {
+        let Some(path) = Self::path() else { return Ok(Self::default()) };
+
+        match std::fs::read_to_string(path) {
+            Ok(string) => toml::from_str(&string).map_err(SettingsError::Parse),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(SettingsError::Io(error)),
+        }
+    }
+}
+
+/// The settings of the quality governor.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct QualitySettings {
+    /// If set, pins the quality governor to the given tier.
+    pub tier: Option<QualityTier>,
+}
+
+/// The settings of the patrol route.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct PatrolSettings {
+    /// The waypoints to patrol, as percentages of the display's width and height.
+    pub waypoints: Vec<[f32; 2]>,
+    /// The number of seconds to wait at each waypoint.
+    pub dwell: f32,
+}
+
+impl Default for PatrolSettings {
+    fn default() -> Self {
+        Self { waypoints: Vec::new(), dwell: 3.0 }
+    }
+}
+
+/// The settings of gamepad rumble.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct RumbleSettings {
+    /// Whether gamepads should rumble when the cube baby bounces off of a wall.
+    pub enabled: bool,
+    /// The multiplier applied to the strength of each rumble.
+    pub intensity: f32,
+}
+
+impl Default for RumbleSettings {
+    fn default() -> Self {
+        Self { enabled: true, intensity: 1.0 }
+    }
+}
+
+/// The settings of the display bounds.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct BoundsSettings {
+    /// The margin kept between the display's edges and the window.
+    pub margin: Margin,
+    /// The behavior of each of the display's edges.
+    pub edges: EdgeSettings,
+    /// The distance from an edge, in logical pixels, within which pushes towards that edge are ignored.
+    pub edge_push_deadzone: f32,
+    /// The fraction of speed, from 0.0 to 1.0, kept when bouncing off of an edge.
+    pub restitution: f32,
+    /// The drag applied to the cube baby's speed along an edge while it is touching that edge.
+    ///
+    /// This is zero by default, so that sliding along an edge loses no more speed than sliding anywhere else.
+    pub wall_friction: f32,
+    /// Whether to use the entire display as the bounds, rather than only its work area.
+    ///
+    /// The work area excludes any taskbars or docks, and is the entire display where it cannot be determined.
+    pub full_display: bool,
+    /// Whether the cube baby lands on top of the foreground window, as if it were another floor.
+    ///
+    /// This is only supported on Windows.
+    pub foreground_window: bool,
+    /// The smallest width and height that the foreground window must have to be landed on, in logical pixels.
+    pub foreground_window_minimum_size: f32,
+}
+
+impl Default for BoundsSettings {
+    fn default() -> Self {
+        Self {
+            margin: Margin::default(),
+            edges: EdgeSettings::default(),
+            edge_push_deadzone: 0.0,
+            restitution: 1.0,
+            wall_friction: 0.0,
+            full_display: false,
+            foreground_window: false,
+            foreground_window_minimum_size: 128.0 * SPRITE_SCALE,
+        }
+    }
+}
+
+/// The behavior of each of the display's edges.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct EdgeSettings {
+    /// The behavior of the left edge.
+    pub left: BoundaryBehavior,
+    /// The behavior of the right edge.
+    pub right: BoundaryBehavior,
+    /// The behavior of the top edge.
+    pub top: BoundaryBehavior,
+    /// The behavior of the bottom edge.
+    pub bottom: BoundaryBehavior,
+}
+
+impl EdgeSettings {
+    /// Returns the behavior of the given edge.
+    #[inline]
+    #[must_use]
+    pub const fn get(&self, side: WallSide) -> BoundaryBehavior {
+        match side {
+            WallSide::Left => self.left,
+            WallSide::Right => self.right,
+            WallSide::Top => self.top,
+            WallSide::Bottom => self.bottom,
+        }
+    }
+}
+
+/// A margin kept between the display's edges and the window, in logical pixels.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum Margin {
+    /// The same margin for every edge.
+    Uniform(f32),
+    /// A separate margin for each edge.
+    PerEdge {
+        /// The margin from the left edge.
+        #[serde(default)]
+        left: f32,
+        /// The margin from the right edge.
+        #[serde(default)]
+        right: f32,
+        /// The margin from the top edge.
+        #[serde(default)]
+        top: f32,
+        /// The margin from the bottom edge.
+        #[serde(default)]
+        bottom: f32,
+    },
+}
+
+impl Default for Margin {
+    fn default() -> Self {
+        Self::Uniform(0.0)
+    }
+}
+
+impl From<Margin> for Margins {
+    fn from(value: Margin) -> Self {
+        match value {
+            Margin::Uniform(margin) => Self::splat(margin),
+            Margin::PerEdge { left, right, top, bottom } => Self { left, right, top, bottom },
+        }
+        .clamped()
+    }
+}
+
+/// The settings of the window.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct WindowSettings {
+    /// The window's title, which is shown in tooltips and task switchers.
+    pub title: Option<String>,
+    /// The window's background.
+    pub background: Background,
+    /// The size of one side of the window, in logical pixels.
+    ///
+    /// Changes to this are picked up while running, by recreating the window.
+    pub size: f32,
+    /// Whether to move the window less often, which is much smoother over remote desktop sessions.
+    pub remote_friendly: bool,
+    /// The number of times per second that the window is moved when remote-friendly.
+    pub remote_update_rate: f32,
+    /// The maximum number of seconds between two clicks on the cube baby for them to exit the application.
+    ///
+    /// A value of zero disables exiting by double-clicking.
+    pub double_click_exit: f32,
+    /// Whether the window starts out passing all mouse input through to whatever is underneath it.
+    pub click_through: bool,
+    /// The key chord that toggles click-through mode.
+    pub click_through_hotkey: Option<Chord>,
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self {
+            title: None,
+            background: Background::default(),
+            size: WINDOW_SIZE,
+            remote_friendly: false,
+            remote_update_rate: 12.0,
+            double_click_exit: 0.4,
+            click_through: false,
+            click_through_hotkey: "Ctrl+Shift+T".parse().ok(),
+        }
+    }
+}
+
+impl WindowSettings {
+    /// Returns the window's size, limited to the sizes that the window may be resized to.
+    #[must_use]
+    pub fn clamped_size(&self) -> f32 {
+        if self.size.is_finite() { self.size.clamp(MINIMUM_WINDOW_SIZE, MAXIMUM_WINDOW_SIZE) } else { WINDOW_SIZE }
+    }
+}
+
+/// The settings for splitting the sprite sheet into frames.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct TextureSettings {
+    /// The horizontal and vertical spacing between adjacent frames, in texture pixels.
+    pub padding: [u32; 2],
+    /// The horizontal and vertical spacing before the first frame, in texture pixels.
+    pub offset: [u32; 2],
+}
+
+/// The background drawn behind the cube baby.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Background {
+    /// A fully transparent background.
+    #[default]
+    Transparent,
+    /// A transparent background if supported, falling back to a solid background otherwise.
+    Auto,
+    /// A solid background of the given color.
+    Solid(Color),
+}
+
+impl Background {
+    /// The color used when falling back to a solid background.
+    pub const FALLBACK_COLOR: Color = Color::srgb(0.125, 0.125, 0.125);
+
+    /// Resolves an [`Auto`] background into either a [`Transparent`] or [`Solid`] background.
+    ///
+    /// [`Auto`]: Background::Auto
+    /// [`Transparent`]: Background::Transparent
+    /// [`Solid`]: Background::Solid
+    #[must_use]
+    pub fn resolve(self) -> Self {
+        match self {
+            Self::Auto if crate::platform::supports_transparency() => Self::Transparent,
+            Self::Auto => Self::Solid(Self::FALLBACK_COLOR),
+            background => background,
+        }
+    }
+
+    /// Returns `true` if the background is transparent.
+    #[inline]
+    #[must_use]
+    pub const fn is_transparent(&self) -> bool {
+        matches!(self, Self::Transparent)
+    }
+
+    /// Returns the color that the window should be cleared with.
+    #[must_use]
+    pub const fn clear_color(&self) -> Color {
+        match self {
+            Self::Transparent | Self::Auto => Color::NONE,
+            Self::Solid(color) => *color,
+        }
+    }
+}
+
+impl TryFrom<String> for Background {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.trim() {
+            "transparent" => Ok(Self::Transparent),
+            "auto" => Ok(Self::Auto),
+            other => {
+                let Some(color) = other.strip_prefix("solid").map(str::trim) else {
+                    return Err(format!(
+                        "unknown background '{other}', expected 'transparent', 'auto', or 'solid <color>'"
+                    ));
+                };
+
+                Srgba::hex(color).map(|v| Self::Solid(v.into())).map_err(|e| format!("invalid color '{color}': {e}"))
+            }
+        }
+    }
+}
+
+/// The settings for interacting with other running instances.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct PeerSettings {
+    /// Whether cube babies from other running instances should collide with this one.
+    pub enabled: bool,
+}
+
+/// The settings for evading screen captures.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct EvasionSettings {
+    /// The key chord that makes the cube baby hide in the nearest corner, such as `"Ctrl+Shift+S"`.
+    pub hotkey: Option<Chord>,
+    /// Whether to hide when the platform's screenshot interface is launched.
+    ///
+    /// This requires the `screenshot_probe` feature.
+    pub probe: bool,
+}
+
+/// The settings for pushing the cube baby.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct PushSettings {
+    /// How the direction of each cursor push is decided.
+    pub model: PushModel,
+    /// The curve applied to the strength of each push.
+    pub curve: PushCurve,
+    /// The strength of the weakest possible cursor push, in logical pixels per second.
+    pub minimum: f32,
+    /// The strength of the strongest possible cursor push, in logical pixels per second.
+    pub maximum: f32,
+    /// The fastest that the cube baby may ever travel, in logical pixels per second.
+    ///
+    /// This is scaled along with the window's size.
+    pub max_speed: f32,
+    /// The speed in logical pixels per second below which a sliding cube baby is brought to rest.
+    ///
+    /// This is scaled along with the window's size. Set this to zero to let every slide crawl to a stop.
+    pub rest_speed: f32,
+    /// The shortest distance in logical pixels that the cursor must travel within an update to push the cube baby.
+    ///
+    /// This keeps the jitter of a resting hand on a sensitive mouse from pushing the cube baby.
+    pub jitter_deadzone: f32,
+    /// The strength of each nudge from the arrow keys, in logical pixels per second.
+    pub nudge: f32,
+    /// The multiplier applied to a space bar knock that has been fully charged by holding the key.
+    pub charge_multiplier: f32,
+    /// The longest time in seconds that the cursor may take to cross the window for its push to count as a flick.
+    ///
+    /// Set this to zero to disable flicks.
+    pub flick_time: f32,
+    /// The strength of each flick, in logical pixels per second.
+    pub flick_strength: f32,
+    /// Whether to poll the cursor's position on the desktop, so that pushes work without the window being hovered.
+    ///
+    /// This is only supported on Windows and macOS. Elsewhere, pushes fall back to cursor events over the window, and
+    /// every feature that watches the cursor globally is unavailable.
+    ///
+    /// Summoning, repelling, personal space, and every behavior but [`BehaviorMode::Passive`] share this polling, and
+    /// are disabled along with it.
+    pub global_cursor: bool,
+}
+
+impl Default for PushSettings {
+    fn default() -> Self {
+        Self {
+            model: PushModel::default(),
+            curve: PushCurve::default(),
+            minimum: 4.0 * SPRITE_SCALE,
+            maximum: 2048.0 * SPRITE_SCALE,
+            max_speed: 4096.0 * SPRITE_SCALE,
+            rest_speed: 4.0 * SPRITE_SCALE,
+            jitter_deadzone: 2.0,
+            nudge: 128.0 * SPRITE_SCALE,
+            charge_multiplier: 4.0,
+            flick_time: 0.1,
+            flick_strength: 1024.0 * SPRITE_SCALE,
+            global_cursor: true,
+        }
+    }
+}
+
+/// A curve applied to the strength of each push.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum PushCurve {
+    /// Pushes are as strong as the cursor's movement.
+    #[default]
+    Linear,
+    /// Pushes approach, but never exceed, the given strength in logical pixels per second.
+    Saturating(f32),
+    /// Pushes grow with the given power of the cursor's movement, relative to the weakest possible push.
+    Power(f32),
+}
+
+impl PushCurve {
+    /// Applies this curve to the given push strength, in logical pixels per second.
+    #[must_use]
+    pub fn apply(self, strength: f32) -> f32 {
+        let minimum = PUSH_STRENGTH * SPRITE_SCALE;
+
+        match self {
+            Self::Linear => strength,
+            Self::Saturating(maximum) if maximum > 0.0 => maximum * (strength / maximum).tanh(),
+            Self::Saturating(_) => 0.0,
+            Self::Power(exponent) => minimum * (strength / minimum).powf(exponent),
+        }
+    }
+
+    /// Applies this curve to the strength of the given impulse, preserving its direction.
+    #[must_use]
+    pub fn apply_to(self, impulse: Vec2) -> Vec2 {
+        // Avoid rounding errors from renormalizing, so that linear pushes are exactly unchanged.
+        if self == Self::Linear {
+            return impulse;
+        }
+
+        impulse.normalize_or_zero() * self.apply(impulse.length())
+    }
+}
+
+impl TryFrom<String> for PushCurve {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let value = value.trim();
+
+        if value == "linear" {
+            return Ok(Self::Linear);
+        }
+
+        let parameter =
+            |name: &str| value.strip_prefix(name)?.trim().strip_prefix('(')?.strip_suffix(')').map(str::trim);
+        let parse =
+            |parameter: &str| parameter.parse::<f32>().map_err(|e| format!("invalid number '{parameter}': {e}"));
+
+        if let Some(maximum) = parameter("saturating") {
+            parse(maximum).map(Self::Saturating)
+        } else if let Some(exponent) = parameter("power") {
+            parse(exponent).map(Self::Power)
+        } else {
+            Err(format!("unknown push curve '{value}', expected 'linear', 'saturating(<max>)', or 'power(<exponent>)'"))
+        }
+    }
+}
+
+/// The settings for tracking where the cube baby spends its time.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct HeatmapSettings {
+    /// Whether to track where the cube baby spends its time.
+    pub enabled: bool,
+    /// The key chord that renders the heatmap to an image.
+    pub export_hotkey: Option<Chord>,
+}
+
+impl Default for HeatmapSettings {
+    fn default() -> Self {
+        Self { enabled: false, export_hotkey: "Ctrl+Shift+H".parse().ok() }
+    }
+}
+
+/// The settings for knocking the cube baby.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct KnockSettings {
+    /// The key chord that knocks the cube baby, even while its window is unfocused.
+    ///
+    /// This requires the `global_hotkey` feature.
+    pub global_hotkey: Option<Chord>,
+}
+
+impl Default for KnockSettings {
+    fn default() -> Self {
+        Self { global_hotkey: "Ctrl+Alt+B".parse().ok() }
+    }
+}
+
+/// The settings for summoning the cube baby to the cursor.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct SummonSettings {
+    /// Whether clicking anywhere on the desktop summons the cube baby.
+    ///
+    /// This polls the mouse globally, and is only supported on Windows and macOS.
+    pub enabled: bool,
+    /// The mouse button that summons the cube baby.
+    pub button: SummonButton,
+}
+
+/// A mouse button that may summon the cube baby.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SummonButton {
+    /// The middle mouse button.
+    #[default]
+    Middle,
+    /// The right mouse button.
+    Right,
+    /// The back side button.
+    Back,
+    /// The forward side button.
+    Forward,
+}
+
+impl From<SummonButton> for MouseButton {
+    fn from(value: SummonButton) -> Self {
+        match value {
+            SummonButton::Middle => Self::Middle,
+            SummonButton::Right => Self::Right,
+            SummonButton::Back => Self::Back,
+            SummonButton::Forward => Self::Forward,
+        }
+    }
+}
+
+/// The settings for pulling the cube baby down with gravity.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct GravitySettings {
+    /// Whether gravity starts out enabled.
+    pub enabled: bool,
+    /// The downwards acceleration, in logical pixels per second squared.
+    pub acceleration: f32,
+    /// The fastest that the cube baby may fall, in logical pixels per second.
+    pub terminal_speed: f32,
+    /// The fraction of the cube baby's vertical speed that is kept when it bounces off of the floor.
+    pub restitution: f32,
+    /// The drag applied to the cube baby's horizontal speed while it rests on the floor.
+    pub ground_friction: f32,
+    /// How high the cube baby jumps when knocked while resting on the floor, in logical pixels.
+    pub jump_height: f32,
+    /// Whether the cube baby may jump once more while in the air.
+    pub double_jump: bool,
+    /// The key chord that toggles gravity.
+    pub hotkey: Option<Chord>,
+}
+
+impl Default for GravitySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            acceleration: 1024.0 * SPRITE_SCALE,
+            terminal_speed: 1024.0 * SPRITE_SCALE,
+            restitution: 0.5,
+            ground_friction: 2.0,
+            jump_height: 96.0 * SPRITE_SCALE,
+            double_jump: false,
+            hotkey: "Ctrl+Shift+G".parse().ok(),
+        }
+    }
+}
+
+impl GravitySettings {
+    /// Returns the upwards speed that lifts the cube baby to its jump height before gravity stops it, in logical pixels
+    /// per second.
+    pub fn jump_speed(&self) -> f32 {
+        (2.0 * self.acceleration.max(0.0) * self.jump_height.max(0.0)).sqrt()
+    }
+}
+
+/// The settings for the cube baby occasionally pushing itself in a random direction.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct WanderSettings {
+    /// Whether the cube baby wanders at all.
+    pub enabled: bool,
+    /// The shortest time in seconds between wanders.
+    pub minimum_delay: f32,
+    /// The longest time in seconds between wanders.
+    pub maximum_delay: f32,
+    /// The strength of the weakest wander, in logical pixels per second.
+    pub minimum_strength: f32,
+    /// The strength of the strongest wander, in logical pixels per second.
+    pub maximum_strength: f32,
+}
+
+impl Default for WanderSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            minimum_delay: 20.0,
+            maximum_delay: 90.0,
+            minimum_strength: 32.0 * SPRITE_SCALE,
+            maximum_strength: 128.0 * SPRITE_SCALE,
+        }
+    }
+}
+
+impl WanderSettings {
+    /// Returns a random time in seconds until the next wander.
+    pub fn random_delay(&self, rng: &mut fastrand::Rng) -> f32 {
+        let maximum = self.maximum_delay.max(self.minimum_delay);
+
+        self.minimum_delay + (rng.f32() * (maximum - self.minimum_delay))
+    }
+
+    /// Returns a randomly directed wander, in logical pixels per second.
+    pub fn random_impulse(&self, rng: &mut fastrand::Rng) -> Vec2 {
+        let maximum = self.maximum_strength.max(self.minimum_strength);
+        let strength = self.minimum_strength + (rng.f32() * (maximum - self.minimum_strength));
+
+        Vec2::from_angle(rng.f32() * std::f32::consts::TAU) * strength
+    }
+}
+
+/// The settings for how the cube baby behaves on its own.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct BehaviorSettings {
+    /// The behavior that the cube baby starts out with.
+    ///
+    /// Every behavior but [`BehaviorMode::Passive`] polls the cursor globally, and is only supported on Windows and
+    /// macOS.
+    pub mode: BehaviorMode,
+    /// The distance in logical pixels from the cube baby's center within which a skittish cube baby flees the cursor.
+    pub flee_radius: f32,
+    /// The fastest that a skittish cube baby flees, in logical pixels per second.
+    pub flee_speed: f32,
+    /// How quickly a skittish cube baby reaches its flee speed, in logical pixels per second squared.
+    pub flee_acceleration: f32,
+    /// The distance in logical pixels from the cursor at which a following cube baby stops.
+    pub follow_distance: f32,
+    /// The fastest that a following cube baby moves, in logical pixels per second.
+    pub follow_speed: f32,
+    /// The distance in logical pixels from the cursor at which an orbiting cube baby circles it.
+    pub orbit_radius: f32,
+    /// How quickly an orbiting cube baby circles the cursor, in radians per second.
+    pub orbit_speed: f32,
+    /// The key chord that cycles through the behaviors.
+    pub hotkey: Option<Chord>,
+}
+
+impl Default for BehaviorSettings {
+    fn default() -> Self {
+        Self {
+            mode: BehaviorMode::default(),
+            flee_radius: 200.0,
+            flee_speed: 512.0 * SPRITE_SCALE,
+            flee_acceleration: 2048.0 * SPRITE_SCALE,
+            follow_distance: 100.0,
+            follow_speed: 256.0 * SPRITE_SCALE,
+            orbit_radius: 150.0,
+            orbit_speed: 2.0,
+            hotkey: "Ctrl+Shift+S".parse().ok(),
+        }
+    }
+}
+
+/// The settings for the wind that gently pushes the cube baby around.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct WindSettings {
+    /// Whether the wind blows at all.
+    pub enabled: bool,
+    /// The strongest that the wind may blow, in logical pixels per second squared.
+    ///
+    /// The slide drag keeps the cube baby from ever moving faster than this divided by the drag.
+    pub strength: f32,
+    /// How quickly the wind shifts, as a fraction of its strength per second.
+    pub variation: f32,
+}
+
+impl Default for WindSettings {
+    fn default() -> Self {
+        Self { enabled: false, strength: 16.0 * SPRITE_SCALE, variation: 0.25 }
+    }
+}
+
+/// The settings for the weather at a location nudging the cube baby's mood.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct WeatherSettings {
+    /// Whether the weather nudges the cube baby's mood, making it sluggish in the rain and zippy in the sunshine.
+    ///
+    /// This requires the `weather` feature, and sends the configured location to Open-Meteo every half hour.
+    pub enabled: bool,
+    /// The latitude of the location to fetch the weather for, in degrees.
+    pub latitude: f32,
+    /// The longitude of the location to fetch the weather for, in degrees.
+    pub longitude: f32,
+}
+
+/// The settings for the cube baby acting out how long the session has lasted.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct TirednessSettings {
+    /// Whether the cube baby tires out as the session goes on, falling asleep once it has lasted too long.
+    pub enabled: bool,
+    /// The number of hours before the cube baby gets tired.
+    pub tired_after: f32,
+    /// The number of hours before the cube baby falls asleep and suggests a break.
+    pub asleep_after: f32,
+}
+
+impl Default for TirednessSettings {
+    fn default() -> Self {
+        Self { enabled: false, tired_after: 4.0, asleep_after: 8.0 }
+    }
+}
+
+/// The settings for the cube baby settling into nearby corners.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct CornerSnapSettings {
+    /// Whether a slow cube baby is pulled into a nearby corner.
+    pub enabled: bool,
+    /// The distance from a corner within which the cube baby is pulled into it, in logical pixels.
+    pub radius: f32,
+    /// The speed below which the cube baby is pulled into a nearby corner, in logical pixels per second.
+    ///
+    /// Anything moving faster is left to pass the corner by.
+    pub capture_speed: f32,
+    /// The strongest that the cube baby is pulled into a corner, in logical pixels per second squared.
+    pub force: f32,
+}
+
+impl Default for CornerSnapSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            radius: 24.0 * SPRITE_SCALE,
+            capture_speed: 48.0 * SPRITE_SCALE,
+            force: 96.0 * SPRITE_SCALE,
+        }
+    }
+}
+
+/// The settings for spawning extra cube babies.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct SpawningSettings {
+    /// The most extra cube babies that may exist at once.
+    pub maximum: usize,
+    /// The key chord that spawns an extra cube baby.
+    pub spawn_hotkey: Option<Chord>,
+    /// The key chord that despawns the most recently spawned extra cube baby.
+    pub despawn_hotkey: Option<Chord>,
+}
+
+impl Default for SpawningSettings {
+    fn default() -> Self {
+        Self { maximum: 4, spawn_hotkey: "Ctrl+Plus".parse().ok(), despawn_hotkey: "Ctrl+Minus".parse().ok() }
+    }
+}
+
+/// The settings for repelling the cube baby from a nearby cursor.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct RepelSettings {
+    /// Whether the cube baby is continuously pushed away from the cursor while it is nearby.
+    ///
+    /// This polls the cursor globally, and is only supported on Windows and macOS.
+    pub enabled: bool,
+    /// The distance from the window's center within which the cursor repels, in logical pixels.
+    pub radius: f32,
+    /// The acceleration applied by a cursor at the window's center, in logical pixels per second squared.
+    pub strength: f32,
+    /// How the acceleration falls off with the cursor's distance.
+    pub falloff: RepelFalloff,
+}
+
+impl Default for RepelSettings {
+    fn default() -> Self {
+        Self { enabled: false, radius: 150.0, strength: 128.0 * SPRITE_SCALE, falloff: RepelFalloff::default() }
+    }
+}
+
+/// The settings for nudging the cube baby out from under a resting cursor.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct PersonalSpaceSettings {
+    /// Whether the cube baby is nudged out from under the cursor once the cursor has rested on it.
+    ///
+    /// This polls the cursor globally, and is only supported on Windows and macOS.
+    pub enabled: bool,
+    /// The amount of time in seconds that the cursor must rest before the cube baby is nudged.
+    pub rest_delay: f32,
+    /// The speed that the cube baby is nudged up to, in logical pixels per second.
+    pub speed: f32,
+}
+
+impl Default for PersonalSpaceSettings {
+    fn default() -> Self {
+        Self { enabled: false, rest_delay: 0.5, speed: 32.0 * SPRITE_SCALE }
+    }
+}
+
+/// The settings for the cube baby glancing at an approaching cursor.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct GlanceSettings {
+    /// Whether the cube baby leans slightly away from the cursor as it approaches the window.
+    ///
+    /// This polls the cursor globally, and is only supported on Windows and macOS.
+    pub enabled: bool,
+}
+
+/// The settings for patrolling the display edge farthest from the cursor while idle.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct EdgePatrolSettings {
+    /// Whether the cube baby patrols the display edge farthest from the cursor once it has been idle for a while.
+    ///
+    /// This polls the cursor globally, and is only supported on Windows and macOS.
+    pub enabled: bool,
+    /// The amount of time in seconds that the cube baby must rest before it starts patrolling.
+    pub idle_delay: f32,
+}
+
+impl Default for EdgePatrolSettings {
+    fn default() -> Self {
+        Self { enabled: false, idle_delay: 30.0 }
+    }
+}
+
+/// How a cursor's repelling force falls off with distance.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RepelFalloff {
+    /// The force shrinks steadily, reaching zero at the edge of the radius.
+    #[default]
+    Linear,
+    /// The force shrinks with the inverse of the distance, staying strong only close to the window.
+    Inverse,
+}
+
+/// The settings for how quickly the cube baby's sprite spins as it moves.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct SpinSettings {
+    /// How the distance travelled per frame is decided.
+    pub mode: SpinMode,
+    /// The speed at which a cube baby spins twice as quickly per distance travelled, in logical pixels per second.
+    ///
+    /// This is only used by the speed mode.
+    pub reference_speed: f32,
+    /// The most that a cube baby's speed may multiply how quickly it spins per distance travelled.
+    ///
+    /// This is only used by the speed mode.
+    pub max_multiplier: f32,
+}
+
+impl Default for SpinSettings {
+    fn default() -> Self {
+        Self { mode: SpinMode::default(), reference_speed: 512.0 * SPRITE_SCALE, max_multiplier: 4.0 }
+    }
+}
+
+impl SpinSettings {
+    /// Returns the multiplier applied to how quickly a cube baby moving at the given speed spins.
+    ///
+    /// The speed is given in logical pixels per second.
+    #[must_use]
+    pub fn multiplier(&self, speed: f32) -> f32 {
+        match self.mode {
+            SpinMode::Distance => 1.0,
+            SpinMode::Speed if self.reference_speed <= 0.0 => 1.0,
+            SpinMode::Speed => (1.0 + (speed / self.reference_speed)).clamp(1.0, self.max_multiplier.max(1.0)),
+        }
+    }
+}
+
+/// How the distance that a cube baby travels per frame of its spin is decided.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpinMode {
+    /// Every frame takes the same distance, so the spin looks the same at any speed.
+    #[default]
+    Distance,
+    /// Frames take less distance at higher speeds, so that fast movement reads as a faster roll.
+    Speed,
+}
+
+/// An error that may occur when loading the settings file.
+#[derive(Debug)]
+pub enum SettingsError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The file could not be parsed.
+    Parse(toml::de::Error),
+}
+
+impl Display for SettingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "failed to read settings: {error}"),
+            Self::Parse(error) => write!(f, "failed to parse settings: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Parse(error) => Some(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A representative range of push strengths, from barely moving to a hard flick.
+    const STRENGTHS: [f32; 5] = [0.0, PUSH_STRENGTH * SPRITE_SCALE, 100.0, 1000.0, 10_000.0];
+
+    #[test]
+    fn linear_curve_is_unchanged() {
+        for strength in STRENGTHS {
+            assert_eq!(PushCurve::Linear.apply(strength), strength);
+        }
+
+        let impulse = Vec2::new(123.4, -56.7);
+
+        assert_eq!(PushCurve::default(), PushCurve::Linear);
+        assert_eq!(PushCurve::Linear.apply_to(impulse), impulse);
+    }
+
+    #[test]
+    fn saturating_curve_never_exceeds_its_maximum() {
+        let curve = PushCurve::Saturating(500.0);
+        let mut previous = -1.0;
+
+        for strength in STRENGTHS {
+            let applied = curve.apply(strength);
+
+            assert!(applied <= 500.0);
+            assert!(applied <= strength);
+            assert!(applied > previous);
+
+            previous = applied;
+        }
+
+        // Weak pushes are left nearly untouched.
+        assert!((curve.apply(10.0) - 10.0).abs() < 0.01);
+        assert_eq!(PushCurve::Saturating(0.0).apply(1000.0), 0.0);
+    }
+
+    #[test]
+    fn power_curve_keeps_the_weakest_push() {
+        let minimum = PUSH_STRENGTH * SPRITE_SCALE;
+
+        for exponent in [0.5, 1.0, 2.0] {
+            assert!((PushCurve::Power(exponent).apply(minimum) - minimum).abs() < 1e-3);
+        }
+
+        assert!(PushCurve::Power(2.0).apply(minimum * 4.0) > minimum * 4.0);
+        assert!(PushCurve::Power(0.5).apply(minimum * 4.0) < minimum * 4.0);
+        assert!((PushCurve::Power(2.0).apply(minimum * 4.0) - (minimum * 16.0)).abs() < 1e-2);
+    }
+
+    #[test]
+    fn curves_preserve_direction() {
+        let impulse = Vec2::new(300.0, -400.0);
+        let applied = PushCurve::Saturating(100.0).apply_to(impulse);
+
+        assert!((applied.normalize() - impulse.normalize()).length() < 1e-5);
+        assert_eq!(PushCurve::Power(2.0).apply_to(Vec2::ZERO), Vec2::ZERO);
+    }
+
+    #[test]
+    fn curves_parse_from_strings() {
+        assert_eq!(PushCurve::try_from("linear".to_string()), Ok(PushCurve::Linear));
+        assert_eq!(PushCurve::try_from("saturating(250)".to_string()), Ok(PushCurve::Saturating(250.0)));
+        assert_eq!(PushCurve::try_from(" power( 1.5 ) ".to_string()), Ok(PushCurve::Power(1.5)));
+        assert!(PushCurve::try_from("cubic".to_string()).is_err());
+        assert!(PushCurve::try_from("power(fast)".to_string()).is_err());
+    }
+
+    #[test]
+    fn backgrounds_parse_from_strings() {
+        assert_eq!(Background::try_from("transparent".to_string()), Ok(Background::Transparent));
+        assert_eq!(Background::try_from(" auto ".to_string()), Ok(Background::Auto));
+        assert_eq!(
+            Background::try_from("solid #202020".to_string()),
+            Ok(Background::Solid(Srgba::rgb_u8(0x20, 0x20, 0x20).into()))
+        );
+        assert!(Background::try_from("solid".to_string()).is_err());
+        assert!(Background::try_from("solid #zzzzzz".to_string()).is_err());
+        assert!(Background::try_from("opaque".to_string()).is_err());
+    }
+
+    #[test]
+    fn explicit_backgrounds_skip_the_probe() {
+        let solid = Background::Solid(Background::FALLBACK_COLOR);
+
+        assert_eq!(Background::Transparent.resolve(), Background::Transparent);
+        assert_eq!(solid.resolve(), solid);
+        assert!(matches!(Background::Auto.resolve(), Background::Transparent | Background::Solid(_)));
+    }
+
+    #[test]
+    fn jumps_reach_their_height_under_any_gravity() {
+        for acceleration in [256.0, 1024.0, 4096.0] {
+            for jump_height in [32.0, 128.0] {
+                let gravity = GravitySettings { acceleration, jump_height, ..GravitySettings::default() };
+                let speed = gravity.jump_speed();
+
+                assert!(((speed * speed) / (2.0 * acceleration) - jump_height).abs() < 0.01);
+
+                // Integrating the jump frame by frame should peak at about the same height.
+                let delta = 1.0 / 240.0;
+                let (mut height, mut velocity) = (0.0_f32, speed);
+
+                while velocity > 0.0 {
+                    velocity -= acceleration * delta;
+                    height += velocity.max(0.0) * delta;
+                }
+
+                assert!((height - jump_height).abs() <= speed * delta, "peaked at {height} instead of {jump_height}");
+            }
+        }
+    }
+
+    #[test]
+    fn jumps_without_gravity_or_height_stay_put() {
+        let gravity = GravitySettings { acceleration: -10.0, jump_height: 64.0, ..GravitySettings::default() };
+
+        assert_eq!(gravity.jump_speed(), 0.0);
+        assert_eq!(GravitySettings { jump_height: -1.0, ..GravitySettings::default() }.jump_speed(), 0.0);
+    }
+}
@@ -0,0 +1,301 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright © 2025 Jaxydog
+//
+// This file is part of Desktop Cube Baby.
+//
+// Desktop Cube Baby is free software: you can redistribute it and/or modify it under the terms of the GNU General
+// Public License as published by the Free Software Foundation, either version 3 of the License, or (at your option)
+// any later version.
+//
+// Desktop Cube Baby is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the
+// implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more
+// details.
+//
+// You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
+// see <https://www.gnu.org/licenses/>.
+
+use bevy::prelude::*;
+
+use crate::coords::ScreenPos;
+use crate::events::WallSide;
+
+/// Returns the acceleration required to steer towards the given target, slowing down when within the given radius.
+///
+/// The returned acceleration never exceeds `max_acceleration`, and the resulting velocity will settle at zero once the
+/// target has been reached.
+pub fn arrive(
+    position: ScreenPos,
+    velocity: Vec2,
+    target: ScreenPos,
+    max_speed: f32,
+    slowing_radius: f32,
+    max_acceleration: f32,
+) -> Vec2 {
+    let offset = target - position;
+    let distance = offset.length();
+
+    let speed = if distance < slowing_radius { max_speed * (distance / slowing_radius) } else { max_speed };
+    let desired_velocity = offset.normalize_or_zero() * speed;
+
+    (desired_velocity - velocity).clamp_length_max(max_acceleration)
+}
+
+/// Returns the acceleration required to flee from the given threat, up to the given speed.
+///
+/// Fleeing never presses into an edge within `wall_distance` of the position. If the edges leave no room to flee, the
+/// returned acceleration instead darts sideways along them, towards the middle of the bounds.
+pub fn flee(
+    position: ScreenPos,
+    velocity: Vec2,
+    threat: ScreenPos,
+    bounds: (ScreenPos, ScreenPos),
+    wall_distance: f32,
+    max_speed: f32,
+    max_acceleration: f32,
+) -> Vec2 {
+    let (minimum, maximum) = bounds;
+    let away = (position - threat).normalize_or_zero();
+    let mut direction = away;
+
+    for axis in 0 .. 2 {
+        if (direction[axis] < 0.0 && position[axis] - minimum[axis] <= wall_distance)
+            || (direction[axis] > 0.0 && maximum[axis] - position[axis] <= wall_distance)
+        {
+            direction[axis] = 0.0;
+        }
+    }
+
+    // Mostly blocked by walls, so darting sideways is the only way to get away.
+    if direction.length() < 0.5 {
+        let middle = ScreenPos((minimum.0 + maximum.0) / 2.0);
+        let sideways = away.perp();
+
+        direction = if sideways.dot(middle - position) >= 0.0 { sideways } else { -sideways };
+    }
+
+    ((direction.normalize_or_zero() * max_speed) - velocity).clamp_length_max(max_acceleration)
+}
+
+/// Returns the acceleration of a damped spring that pulls towards the given target.
+pub fn spring(position: ScreenPos, velocity: Vec2, target: ScreenPos, stiffness: f32, damping: f32) -> Vec2 {
+    ((target - position) * stiffness) - (velocity * damping)
+}
+
+/// Returns the display edge farthest from the given cursor, keeping the edge that is currently being patrolled unless
+/// another is farther from the cursor by more than `hysteresis`.
+///
+/// The hysteresis stops a cursor resting near the middle of the bounds from sending the cube baby back and forth
+/// between opposite edges. Ties are broken in the order bottom, top, left, right.
+#[must_use]
+pub fn far_edge(
+    cursor: ScreenPos,
+    bounds: (ScreenPos, ScreenPos),
+    current: Option<WallSide>,
+    hysteresis: f32,
+) -> WallSide {
+    let (minimum, maximum) = bounds;
+    // Prevent inverted bounds, and treat a cursor beyond them as sitting on their edge.
+    let maximum = maximum.0.max(minimum.0);
+    let cursor = cursor.0.clamp(minimum.0, maximum);
+    let distance = |side| match side {
+        WallSide::Left => cursor.x - minimum.x,
+        WallSide::Right => maximum.x - cursor.x,
+        WallSide::Top => cursor.y - minimum.y,
+        WallSide::Bottom => maximum.y - cursor.y,
+    };
+
+    let farthest = [WallSide::Top, WallSide::Left, WallSide::Right]
+        .into_iter()
+        .fold(WallSide::Bottom, |farthest, v| if distance(v) > distance(farthest) { v } else { farthest });
+
+    match current {
+        Some(current) if distance(farthest) - distance(current) <= hysteresis => current,
+        _ => farthest,
+    }
+}
+
+/// Returns the position to steer towards while patrolling along the given edge, along with the heading to keep.
+///
+/// The bounds are given as the minimum and maximum positions that the window may reach. The returned position lies on
+/// the edge, `lookahead` ahead of the given position, and the heading turns around once the position comes within a
+/// quarter of the lookahead of the edge's end.
+#[must_use]
+pub fn edge_waypoint(
+    position: ScreenPos,
+    edge: WallSide,
+    bounds: (ScreenPos, ScreenPos),
+    heading: f32,
+    lookahead: f32,
+) -> (ScreenPos, f32) {
+    let (minimum, maximum) = bounds;
+    let maximum = maximum.0.max(minimum.0);
+    // The top and bottom edges run horizontally, while the left and right edges run vertically.
+    let axis = if edge.is_vertical() { 0 } else { 1 };
+    let along = position[axis];
+    let mut heading = if heading < 0.0 { -1.0 } else { 1.0 };
+
+    if (heading > 0.0 && maximum[axis] - along <= lookahead / 4.0)
+        || (heading < 0.0 && along - minimum[axis] <= lookahead / 4.0)
+    {
+        heading = -heading;
+    }
+
+    let mut target = position.0.clamp(minimum.0, maximum);
+
+    target[axis] = (along + (heading * lookahead)).clamp(minimum[axis], maximum[axis]);
+
+    match edge {
+        WallSide::Left => target.x = minimum.x,
+        WallSide::Right => target.x = maximum.x,
+        WallSide::Top => target.y = minimum.y,
+        WallSide::Bottom => target.y = maximum.y,
+    }
+
+    (ScreenPos(target), heading)
+}
+
+/// Returns the acceleration that pulls a slow window into the nearest corner of the given bounds, or [`None`] if no
+/// corner is within the given radius.
+///
+/// The bounds are given as the smallest and largest positions of the window's top-left corner. Anything moving at or
+/// above `capture_speed` is left alone, so that a window passing by at speed is never captured, and the pull never
+/// drives the window any faster than that.
+#[must_use]
+pub fn corner_pull(
+    position: ScreenPos,
+    velocity: Vec2,
+    (minimum, maximum): (ScreenPos, ScreenPos),
+    radius: f32,
+    capture_speed: f32,
+    force: f32,
+) -> Option<Vec2> {
+    if velocity.length() >= capture_speed {
+        return None;
+    }
+
+    let corners = [minimum, ScreenPos::new(maximum.x, minimum.y), ScreenPos::new(minimum.x, maximum.y), maximum];
+    let corner = corners
+        .into_iter()
+        .min_by(|a, b| a.distance(position.0).total_cmp(&b.distance(position.0)))
+        .filter(|v| v.distance(position.0) <= radius)?;
+
+    Some(self::arrive(position, velocity, corner, capture_speed, radius, force))
+}
+
+/// Tracks the cube baby's progress around an orbit of the cursor.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Orbit {
+    /// The current angle around the orbit, in radians.
+    pub angle: f32,
+    /// How strongly the orbit pulls, from zero to one.
+    pub engagement: f32,
+    /// The number of seconds that the cursor has been resting for.
+    pub idle: f32,
+    /// The cursor's last known position.
+    pub cursor: Option<ScreenPos>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOUNDS: (ScreenPos, ScreenPos) = (ScreenPos(Vec2::ZERO), ScreenPos(Vec2::new(1920.0, 1080.0)));
+
+    #[test]
+    fn far_edges_face_away_from_the_cursor() {
+        assert_eq!(far_edge(ScreenPos::new(100.0, 100.0), BOUNDS, None, 0.0), WallSide::Right);
+        assert_eq!(far_edge(ScreenPos::new(1800.0, 540.0), BOUNDS, None, 0.0), WallSide::Left);
+        assert_eq!(far_edge(ScreenPos::new(960.0, 60.0), BOUNDS, None, 0.0), WallSide::Bottom);
+        assert_eq!(far_edge(ScreenPos::new(960.0, 1070.0), BOUNDS, Some(WallSide::Bottom), 0.0), WallSide::Top);
+        // A cursor beyond the bounds counts as sitting on their edge.
+        assert_eq!(far_edge(ScreenPos::new(-500.0, 540.0), BOUNDS, None, 0.0), WallSide::Right);
+    }
+
+    #[test]
+    fn far_edges_keep_the_current_edge_within_the_hysteresis() {
+        // Just right of the middle, the left edge is only slightly farther away than the right.
+        let cursor = ScreenPos::new(980.0, 540.0);
+
+        assert_eq!(far_edge(cursor, BOUNDS, None, 128.0), WallSide::Left);
+        assert_eq!(far_edge(cursor, BOUNDS, Some(WallSide::Right), 128.0), WallSide::Right);
+        assert_eq!(far_edge(ScreenPos::new(1800.0, 540.0), BOUNDS, Some(WallSide::Right), 128.0), WallSide::Left);
+    }
+
+    #[test]
+    fn far_edges_handle_degenerate_bounds() {
+        let flat = (ScreenPos::new(0.0, 500.0), ScreenPos::new(1920.0, 501.0));
+
+        assert_eq!(far_edge(ScreenPos::new(100.0, 500.0), flat, None, 0.0), WallSide::Right);
+        assert_eq!(far_edge(ScreenPos::new(1800.0, 0.0), flat, None, 0.0), WallSide::Left);
+
+        let point = (ScreenPos::new(5.0, 5.0), ScreenPos::new(5.0, 5.0));
+
+        assert_eq!(far_edge(ScreenPos::new(100.0, 100.0), point, None, 0.0), WallSide::Bottom);
+        assert_eq!(far_edge(ScreenPos::new(100.0, 100.0), point, Some(WallSide::Left), 0.0), WallSide::Left);
+
+        let (target, _) = edge_waypoint(ScreenPos::new(8.0, 8.0), WallSide::Left, flat, 1.0, 64.0);
+
+        assert!(target.is_finite() && target.x == 0.0 && (500.0 ..= 501.0).contains(&target.y));
+    }
+
+    #[test]
+    fn edge_waypoints_run_along_the_edge_and_turn_around() {
+        let (target, heading) = edge_waypoint(ScreenPos::new(500.0, 300.0), WallSide::Bottom, BOUNDS, 1.0, 64.0);
+
+        assert_eq!((target, heading), (ScreenPos::new(564.0, 1080.0), 1.0));
+
+        let (target, heading) = edge_waypoint(ScreenPos::new(1910.0, 1080.0), WallSide::Bottom, BOUNDS, 1.0, 64.0);
+
+        assert_eq!((target, heading), (ScreenPos::new(1846.0, 1080.0), -1.0));
+
+        let (target, heading) = edge_waypoint(ScreenPos::new(0.0, 4.0), WallSide::Left, BOUNDS, -1.0, 64.0);
+
+        assert_eq!((target, heading), (ScreenPos::new(0.0, 68.0), 1.0));
+    }
+
+    #[test]
+    fn slow_windows_are_pulled_into_nearby_corners() {
+        let pull = corner_pull(ScreenPos::new(10.0, 20.0), Vec2::ZERO, BOUNDS, 48.0, 96.0, 192.0);
+
+        assert!(pull.is_some_and(|v| v.x < 0.0 && v.y < 0.0));
+
+        let pull = corner_pull(ScreenPos::new(1900.0, 1070.0), Vec2::new(-10.0, 0.0), BOUNDS, 48.0, 96.0, 192.0);
+
+        assert!(pull.is_some_and(|v| v.x > 0.0 && v.y > 0.0));
+    }
+
+    #[test]
+    fn corners_only_capture_within_their_radius() {
+        assert!(corner_pull(ScreenPos::new(30.0, 30.0), Vec2::ZERO, BOUNDS, 48.0, 96.0, 192.0).is_some());
+        assert!(corner_pull(ScreenPos::new(40.0, 40.0), Vec2::ZERO, BOUNDS, 48.0, 96.0, 192.0).is_none());
+        assert!(corner_pull(ScreenPos::new(960.0, 0.0), Vec2::ZERO, BOUNDS, 48.0, 96.0, 192.0).is_none());
+    }
+
+    #[test]
+    fn fast_windows_escape_corners() {
+        let position = ScreenPos::new(10.0, 10.0);
+
+        assert!(corner_pull(position, Vec2::new(95.0, 0.0), BOUNDS, 48.0, 96.0, 192.0).is_some());
+        assert!(corner_pull(position, Vec2::new(96.0, 0.0), BOUNDS, 48.0, 96.0, 192.0).is_none());
+        assert!(corner_pull(position, Vec2::new(-300.0, -300.0), BOUNDS, 48.0, 96.0, 192.0).is_none());
+    }
+
+    #[test]
+    fn corner_pulls_park_without_speeding_up() {
+        let mut position = ScreenPos::new(30.0, 20.0);
+        let mut velocity = Vec2::ZERO;
+        let delta = 1.0 / 64.0;
+
+        for _ in 0 .. 512 {
+            let Some(pull) = corner_pull(position, velocity, BOUNDS, 48.0, 96.0, 192.0) else { break };
+
+            velocity += pull * delta;
+            position += velocity * delta;
+
+            assert!(velocity.length() < 96.0);
+        }
+
+        assert!(position.distance(BOUNDS.0) < 1.0);
+    }
+}
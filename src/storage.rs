@@ -0,0 +1,63 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright © 2025 Jaxydog
+//
+// This file is part of Desktop Cube Baby.
+//
+// Desktop Cube Baby is free software: you can redistribute it and/or modify it under the terms of the GNU General
+// Public License as published by the Free Software Foundation, either version 3 of the License, or (at your option)
+// any later version.
+//
+// Desktop Cube Baby is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the
+// implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more
+// details.
+//
+// You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
+// see <https://www.gnu.org/licenses/>.
+
+use std::path::PathBuf;
+
+/// Returns the application's data directory, if the platform provides one.
+pub fn data_directory() -> Option<PathBuf> {
+    dirs::data_dir().map(|v| v.join(env!("CARGO_PKG_NAME")))
+}
+
+/// Returns the path to the data file with the given name, if the platform provides a data directory.
+pub fn data_path(name: &str) -> Option<PathBuf> {
+    self::data_directory().map(|v| v.join(name))
+}
+
+/// Returns `true` if the data file with the given name exists.
+pub fn data_exists(name: &str) -> bool {
+    self::data_path(name).is_some_and(|v| v.exists())
+}
+
+/// Writes the given contents to the data file with the given name, creating the data directory if necessary.
+///
+/// # Errors
+///
+/// This function will return an error if the platform has no data directory, or if the file could not be written.
+pub fn write_data(name: &str, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let Some(path) = self::data_path(name) else {
+        return Err(std::io::Error::new(std::io::ErrorKind::NotFound, "missing platform data directory"));
+    };
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    std::fs::write(path, contents)
+}
+
+/// Reads the contents of the data file with the given name.
+///
+/// # Errors
+///
+/// This function will return an error if the platform has no data directory, or if the file could not be read.
+pub fn read_data(name: &str) -> std::io::Result<Vec<u8>> {
+    let Some(path) = self::data_path(name) else {
+        return Err(std::io::Error::new(std::io::ErrorKind::NotFound, "missing platform data directory"));
+    };
+
+    std::fs::read(path)
+}
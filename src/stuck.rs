@@ -0,0 +1,194 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright © 2025 Jaxydog
+//
+// This file is part of Desktop Cube Baby.
+//
+// Desktop Cube Baby is free software: you can redistribute it and/or modify it under the terms of the GNU General
+// Public License as published by the Free Software Foundation, either version 3 of the License, or (at your option)
+// any later version.
+//
+// Desktop Cube Baby is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the
+// implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more
+// details.
+//
+// You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
+// see <https://www.gnu.org/licenses/>.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::SPRITE_SCALE;
+use crate::coords::ScreenPos;
+
+/// The number of seconds of movement that are considered when checking whether the cube baby is stuck.
+pub const WINDOW_DURATION: f64 = 10.0;
+/// The number of seconds between samples.
+pub const SAMPLE_DELAY: f64 = 0.1;
+/// The largest extent that positions may cover while the cube baby is considered stuck, in physical pixels.
+pub const MAXIMUM_EXTENT: f32 = 4.0 * SPRITE_SCALE;
+/// The speed below which the cube baby is considered to be at rest, in physical pixels per second.
+pub const REST_SPEED: f32 = 1.0 * SPRITE_SCALE;
+/// The number of seconds that must pass between rescues.
+pub const RESCUE_DELAY: f64 = 60.0;
+/// The speed that the cube baby is rescued with, in physical pixels per second.
+pub const RESCUE_SPEED: f32 = 256.0 * SPRITE_SCALE;
+
+/// A single sample of the cube baby's movement.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Sample {
+    /// The cube baby's position.
+    pub position: ScreenPos,
+    /// The cube baby's speed, in physical pixels per second.
+    pub speed: f32,
+}
+
+/// A rolling history of the cube baby's movement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct History {
+    /// The recorded samples, from oldest to newest.
+    samples: VecDeque<Sample>,
+}
+
+impl History {
+    /// The number of samples kept by the history.
+    pub const CAPACITY: usize = (WINDOW_DURATION / SAMPLE_DELAY) as usize;
+
+    /// Creates a new empty [`History`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self { samples: VecDeque::with_capacity(Self::CAPACITY) }
+    }
+
+    /// Records a sample, forgetting the oldest sample if the history is full.
+    pub fn push(&mut self, sample: Sample) {
+        if self.samples.len() == Self::CAPACITY {
+            self.samples.pop_front();
+        }
+
+        self.samples.push_back(sample);
+    }
+
+    /// Forgets every recorded sample.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Returns `true` if the history covers its full duration and the cube baby was stuck for all of it.
+    #[must_use]
+    pub fn is_stuck(&self) -> bool {
+        self.samples.len() == Self::CAPACITY && self::is_stuck(self.samples.iter().copied(), MAXIMUM_EXTENT, REST_SPEED)
+    }
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns `true` if the given samples stay within a tiny area despite never coming to rest.
+///
+/// Returns `false` if there are no samples.
+pub fn is_stuck(samples: impl IntoIterator<Item = Sample>, maximum_extent: f32, rest_speed: f32) -> bool {
+    let mut samples = samples.into_iter().peekable();
+    let Some(first) = samples.peek().copied() else { return false };
+
+    let mut minimum = first.position.0;
+    let mut maximum = first.position.0;
+
+    for sample in samples {
+        if !sample.speed.is_finite() || sample.speed <= rest_speed {
+            return false;
+        }
+
+        minimum = minimum.min(sample.position.0);
+        maximum = maximum.max(sample.position.0);
+    }
+
+    (maximum - minimum).max_element() <= maximum_extent
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a history filled with samples produced by the given function of each sample's index.
+    fn history(sample: impl Fn(usize) -> Sample) -> History {
+        let mut history = History::new();
+
+        for index in 0 .. History::CAPACITY {
+            history.push(sample(index));
+        }
+
+        history
+    }
+
+    #[test]
+    fn vibrating_in_place_is_stuck() {
+        let history = history(|index| {
+            let offset = if index % 2 == 0 { 1.0 } else { -1.0 };
+
+            Sample { position: ScreenPos::new(100.0 + offset, 200.0 - offset), speed: 500.0 }
+        });
+
+        assert!(history.is_stuck());
+    }
+
+    #[test]
+    fn travelling_is_not_stuck() {
+        let history = history(|index| Sample { position: ScreenPos::new(index as f32 * 10.0, 200.0), speed: 100.0 });
+
+        assert!(!history.is_stuck());
+    }
+
+    #[test]
+    fn resting_is_not_stuck() {
+        let history = history(|_| Sample { position: ScreenPos::new(100.0, 200.0), speed: 0.0 });
+
+        assert!(!history.is_stuck());
+    }
+
+    #[test]
+    fn briefly_resting_is_not_stuck() {
+        let history = history(|index| Sample {
+            position: ScreenPos::new(100.0, 200.0),
+            speed: if index == History::CAPACITY / 2 { 0.0 } else { 500.0 },
+        });
+
+        assert!(!history.is_stuck());
+    }
+
+    #[test]
+    fn partial_history_is_not_stuck() {
+        let mut history = History::new();
+
+        for _ in 1 .. History::CAPACITY {
+            history.push(Sample { position: ScreenPos::new(100.0, 200.0), speed: 500.0 });
+        }
+
+        assert!(!history.is_stuck());
+
+        history.push(Sample { position: ScreenPos::new(100.0, 200.0), speed: 500.0 });
+
+        assert!(history.is_stuck());
+    }
+
+    #[test]
+    fn cleared_history_is_not_stuck() {
+        let mut history = history(|_| Sample { position: ScreenPos::new(100.0, 200.0), speed: 500.0 });
+
+        history.clear();
+
+        assert!(!history.is_stuck());
+    }
+
+    #[test]
+    fn non_finite_speed_is_not_stuck() {
+        let samples = [Sample { position: ScreenPos::new(0.0, 0.0), speed: f32::NAN }];
+
+        assert!(!is_stuck(samples, MAXIMUM_EXTENT, REST_SPEED));
+        assert!(!is_stuck([], MAXIMUM_EXTENT, REST_SPEED));
+    }
+}
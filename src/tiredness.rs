@@ -0,0 +1,161 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright © 2025 Jaxydog
+//
+// This file is part of Desktop Cube Baby.
+//
+// Desktop Cube Baby is free software: you can redistribute it and/or modify it under the terms of the GNU General
+// Public License as published by the Free Software Foundation, either version 3 of the License, or (at your option)
+// any later version.
+//
+// Desktop Cube Baby is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the
+// implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more
+// details.
+//
+// You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
+// see <https://www.gnu.org/licenses/>.
+
+use bevy::prelude::*;
+
+use crate::coords::ScreenPos;
+use crate::resources::Mood;
+
+/// The mood of a cube baby at the very start of a session, which is a little zippier than usual.
+pub const ENERGETIC_MOOD: Mood = Mood { drag: 0.9, wander: 1.25, gloom: 0.0 };
+/// The mood of a cube baby once the session has lasted long enough to tire it out.
+pub const TIRED_MOOD: Mood = Mood { drag: 1.25, wander: 0.75, gloom: 0.0 };
+/// The mood of a cube baby once the session has lasted long enough to put it to sleep.
+pub const ASLEEP_MOOD: Mood = Mood { drag: 1.75, wander: 0.25, gloom: 0.0 };
+/// The distance from the display's edge that a cube baby flops down at, in physical pixels.
+pub const FLOP_MARGIN: f32 = 8.0;
+
+/// How far through a session the cube babies are.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SessionStage {
+    /// The session has just begun, and the cube babies are full of energy.
+    Fresh,
+    /// The session has lasted for a while, and the cube babies are dragging themselves around.
+    Tired,
+    /// The session has lasted far too long, and the cube babies have flopped down to sleep.
+    Asleep,
+}
+
+/// Returns the stage of a session that has lasted for the given number of hours.
+///
+/// If the cube babies would fall asleep before tiring, they fall asleep at the earlier of the two thresholds.
+#[must_use]
+pub fn stage(hours: f32, tired_after: f32, asleep_after: f32) -> SessionStage {
+    if hours >= asleep_after {
+        SessionStage::Asleep
+    } else if hours >= tired_after {
+        SessionStage::Tired
+    } else {
+        SessionStage::Fresh
+    }
+}
+
+/// Returns the mood of a cube baby in a session that has lasted for the given number of hours.
+///
+/// The cube baby's energy fades from [`ENERGETIC_MOOD`] to neutral while the session is fresh, then sinks from
+/// [`TIRED_MOOD`] to [`ASLEEP_MOOD`] until it falls asleep.
+#[must_use]
+pub fn mood(hours: f32, tired_after: f32, asleep_after: f32) -> Mood {
+    let lerp = |from: Mood, to: Mood, fraction: f32| {
+        let fraction = if fraction.is_finite() { fraction.clamp(0.0, 1.0) } else { 1.0 };
+
+        Mood {
+            drag: from.drag.lerp(to.drag, fraction),
+            wander: from.wander.lerp(to.wander, fraction),
+            gloom: from.gloom.lerp(to.gloom, fraction),
+        }
+    };
+
+    match self::stage(hours, tired_after, asleep_after) {
+        SessionStage::Fresh => lerp(ENERGETIC_MOOD, Mood::NEUTRAL, hours / tired_after),
+        SessionStage::Tired => lerp(TIRED_MOOD, ASLEEP_MOOD, (hours - tired_after) / (asleep_after - tired_after)),
+        SessionStage::Asleep => ASLEEP_MOOD,
+    }
+}
+
+/// Returns the window position that a sleepy cube baby flops down at, just inside the display edge nearest to it.
+///
+/// The bounds are given as the minimum and maximum positions that the window's edges may reach.
+#[must_use]
+pub fn flop_target(position: ScreenPos, extent: Vec2, minimum: ScreenPos, maximum: ScreenPos) -> ScreenPos {
+    // Prevent inverted bounds if the window is larger than the available space.
+    let maximum = (maximum - extent).max(minimum.0);
+    let inner_minimum = (minimum.0 + FLOP_MARGIN).min(maximum);
+    let inner_maximum = (maximum - FLOP_MARGIN).max(inner_minimum);
+    let mut target = position.0.clamp(inner_minimum, inner_maximum);
+
+    let distances = [
+        (target.x - minimum.x, 0, inner_minimum.x),
+        (maximum.x - target.x, 0, inner_maximum.x),
+        (target.y - minimum.y, 1, inner_minimum.y),
+        (maximum.y - target.y, 1, inner_maximum.y),
+    ];
+    let (_, axis, edge) = distances.into_iter().fold(distances[3], |v, w| if w.0 < v.0 { w } else { v });
+
+    target[axis] = edge;
+
+    ScreenPos(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sessions_move_through_each_stage() {
+        assert_eq!(self::stage(0.0, 4.0, 8.0), SessionStage::Fresh);
+        assert_eq!(self::stage(3.99, 4.0, 8.0), SessionStage::Fresh);
+        assert_eq!(self::stage(4.0, 4.0, 8.0), SessionStage::Tired);
+        assert_eq!(self::stage(8.0, 4.0, 8.0), SessionStage::Asleep);
+        assert_eq!(self::stage(100.0, 4.0, 8.0), SessionStage::Asleep);
+        // Falling asleep first skips straight past tiring.
+        assert_eq!(self::stage(3.0, 4.0, 2.0), SessionStage::Asleep);
+    }
+
+    #[test]
+    fn tiredness_grows_with_uptime() {
+        assert_eq!(self::mood(0.0, 4.0, 8.0), ENERGETIC_MOOD);
+        assert_eq!(self::mood(4.0, 4.0, 8.0), TIRED_MOOD);
+        assert_eq!(self::mood(12.0, 4.0, 8.0), ASLEEP_MOOD);
+
+        let drags = [0.0, 1.0, 2.0, 3.99, 4.0, 5.0, 6.0, 7.0, 7.99, 8.0].map(|v| self::mood(v, 4.0, 8.0).drag);
+        let wanders = [0.0, 1.0, 2.0, 3.99, 4.0, 5.0, 6.0, 7.0, 7.99, 8.0].map(|v| self::mood(v, 4.0, 8.0).wander);
+
+        assert!(drags.windows(2).all(|v| v[0] <= v[1]), "{drags:?}");
+        assert!(wanders.windows(2).all(|v| v[0] >= v[1]), "{wanders:?}");
+    }
+
+    #[test]
+    fn degenerate_thresholds_stay_finite() {
+        for (tired_after, asleep_after) in [(0.0, 0.0), (0.0, 8.0), (4.0, 4.0), (4.0, 2.0)] {
+            for hours in [0.0, 2.0, 4.0, 8.0] {
+                let mood = self::mood(hours, tired_after, asleep_after);
+
+                assert!(mood.drag.is_finite() && mood.wander.is_finite(), "{tired_after}, {asleep_after}, {hours}");
+            }
+        }
+    }
+
+    #[test]
+    fn flops_land_near_the_nearest_edge() {
+        let (minimum, maximum) = (ScreenPos::new(0.0, 0.0), ScreenPos::new(1920.0, 1080.0));
+        let extent = Vec2::splat(64.0);
+
+        let target = self::flop_target(ScreenPos::new(300.0, 900.0), extent, minimum, maximum);
+
+        assert_eq!(target, ScreenPos::new(300.0, 1016.0 - FLOP_MARGIN));
+
+        let target = self::flop_target(ScreenPos::new(20.0, 500.0), extent, minimum, maximum);
+
+        assert_eq!(target, ScreenPos::new(FLOP_MARGIN, 500.0));
+
+        // A window larger than the display still lands somewhere within it.
+        let target = self::flop_target(ScreenPos::new(20.0, 500.0), Vec2::splat(4096.0), minimum, maximum);
+
+        assert!(target.is_finite());
+    }
+}
@@ -0,0 +1,213 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright © 2025 Jaxydog
+//
+// This file is part of Desktop Cube Baby.
+//
+// Desktop Cube Baby is free software: you can redistribute it and/or modify it under the terms of the GNU General
+// Public License as published by the Free Software Foundation, either version 3 of the License, or (at your option)
+// any later version.
+//
+// Desktop Cube Baby is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the
+// implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more
+// details.
+//
+// You should have received a copy of the GNU General Public License along with Desktop Cube Baby. If not,
+// see <https://www.gnu.org/licenses/>.
+
+use std::sync::Mutex;
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::resources::Mood;
+
+/// The endpoint that the weather is fetched from.
+const API_URL: &str = "https://api.open-meteo.com/v1/forecast";
+/// The amount of time between weather fetches.
+pub const FETCH_DELAY: Duration = Duration::from_secs(30 * 60);
+/// The amount of time to wait before retrying a failed weather fetch.
+pub const RETRY_DELAY: Duration = Duration::from_secs(5 * 60);
+/// The amount of time to wait for a weather fetch to complete before giving up on it.
+pub const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+/// The wind speed at which the wind blows as strongly as the wind settings allow, in kilometers per hour.
+pub const GALE_SPEED: f32 = 50.0;
+
+/// The current weather at a location, as reported by Open-Meteo.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub struct Weather {
+    /// The WMO weather interpretation code.
+    #[serde(rename = "weather_code")]
+    pub code: u8,
+    /// The wind speed ten meters above the ground, in kilometers per hour.
+    #[serde(rename = "wind_speed_10m")]
+    pub wind_speed: f32,
+    /// The direction that the wind blows from, in degrees clockwise from north.
+    #[serde(rename = "wind_direction_10m")]
+    pub wind_direction: f32,
+}
+
+impl Weather {
+    /// Returns the unit direction that the wind blows towards, using screen axes with north towards the top.
+    #[must_use]
+    pub fn wind_heading(&self) -> Vec2 {
+        let (sin, cos) = self.wind_direction.to_radians().sin_cos();
+
+        // Reported directions are where the wind comes from, so the heading points the opposite way.
+        Vec2::new(-sin, cos)
+    }
+
+    /// Returns how strongly the wind blows, from zero to one.
+    #[must_use]
+    pub fn wind_strength(&self) -> f32 {
+        (self.wind_speed / GALE_SPEED).clamp(0.0, 1.0)
+    }
+}
+
+/// Returns the mood caused by the given WMO weather interpretation code.
+///
+/// Unknown codes leave the cube baby's mood untouched.
+#[must_use]
+pub const fn mood(code: u8) -> Mood {
+    match code {
+        // Clear and mostly clear skies.
+        0 | 1 => Mood { drag: 0.75, wander: 1.5, gloom: 0.0 },
+        // Clouds and fog.
+        2 | 3 | 45 | 48 => Mood::NEUTRAL,
+        // Drizzle, rain, and rain showers.
+        51 ..= 67 | 80 ..= 82 => Mood { drag: 1.5, wander: 0.5, gloom: 0.35 },
+        // Snow and snow showers.
+        71 ..= 77 | 85 | 86 => Mood { drag: 1.25, wander: 0.75, gloom: 0.15 },
+        // Thunderstorms.
+        95 ..= 99 => Mood { drag: 1.75, wander: 0.25, gloom: 0.5 },
+        _ => Mood::NEUTRAL,
+    }
+}
+
+/// Fetches the current weather at the given location, blocking until it arrives.
+///
+/// # Errors
+///
+/// This function will return an error if the request fails or its response is malformed.
+pub fn fetch(latitude: f32, longitude: f32) -> Result<Weather, Box<dyn std::error::Error + Send + Sync>> {
+    /// The body of a forecast response.
+    #[derive(Deserialize)]
+    struct Response {
+        /// The current weather.
+        current: Weather,
+    }
+
+    let response: Response = ureq::get(API_URL)
+        .query("latitude", &latitude.to_string())
+        .query("longitude", &longitude.to_string())
+        .query("current", "weather_code,wind_speed_10m,wind_direction_10m")
+        .timeout(FETCH_TIMEOUT)
+        .call()?
+        .into_json()?;
+
+    Ok(response.current)
+}
+
+/// Receives the weather fetched in the background.
+#[derive(Debug, Resource)]
+pub struct WeatherReceiver(Mutex<Receiver<Weather>>);
+
+impl WeatherReceiver {
+    /// Starts fetching the weather at the given location on a background thread, returning a receiver for each report.
+    ///
+    /// Failed fetches are retried after a short delay, and are only logged at the debug level, since the weather is
+    /// purely cosmetic.
+    #[must_use]
+    pub fn spawn(latitude: f32, longitude: f32) -> Self {
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        std::thread::spawn(move || {
+            loop {
+                let delay = match self::fetch(latitude, longitude) {
+                    Ok(weather) => {
+                        // Nothing is left to receive the weather once the application has exited.
+                        if sender.send(weather).is_err() {
+                            return;
+                        }
+
+                        FETCH_DELAY
+                    }
+                    Err(error) => {
+                        debug!("failed to fetch the weather: {error}");
+
+                        RETRY_DELAY
+                    }
+                };
+
+                std::thread::sleep(delay);
+            }
+        });
+
+        Self(Mutex::new(receiver))
+    }
+
+    /// Returns the latest weather report received since the last call, if any.
+    #[must_use]
+    pub fn latest(&self) -> Option<Weather> {
+        self.0.lock().ok()?.try_iter().last()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clear_skies_are_zippy() {
+        for code in [0, 1] {
+            let mood = self::mood(code);
+
+            assert!(mood.drag < 1.0);
+            assert!(mood.wander > 1.0);
+            assert_eq!(mood.gloom, 0.0);
+        }
+    }
+
+    #[test]
+    fn rain_is_sluggish_and_gloomy() {
+        for code in [51, 53, 55, 61, 63, 65, 66, 67, 80, 81, 82, 95, 96, 99] {
+            let mood = self::mood(code);
+
+            assert!(mood.drag > 1.0, "code {code}");
+            assert!(mood.wander < 1.0, "code {code}");
+            assert!(mood.gloom > 0.0, "code {code}");
+        }
+
+        // Thunderstorms are the gloomiest weather of all.
+        assert!(self::mood(95).gloom > self::mood(61).gloom);
+    }
+
+    #[test]
+    fn unremarkable_weather_is_neutral() {
+        for code in [2, 3, 45, 48, 4, 100, u8::MAX] {
+            assert_eq!(self::mood(code), Mood::NEUTRAL, "code {code}");
+        }
+    }
+
+    #[test]
+    fn wind_blows_away_from_its_reported_direction() {
+        let heading = |wind_direction| Weather { code: 0, wind_speed: 0.0, wind_direction }.wind_heading();
+
+        // A northerly wind blows south, towards the bottom of the screen.
+        assert!((heading(0.0) - Vec2::Y).length() < 1e-5);
+        // A westerly wind blows east, towards the right of the screen.
+        assert!((heading(270.0) - Vec2::X).length() < 1e-5);
+        assert!((heading(90.0) - Vec2::NEG_X).length() < 1e-5);
+    }
+
+    #[test]
+    fn wind_strength_is_clamped() {
+        let strength = |wind_speed| Weather { code: 0, wind_speed, wind_direction: 0.0 }.wind_strength();
+
+        assert_eq!(strength(0.0), 0.0);
+        assert_eq!(strength(GALE_SPEED / 2.0), 0.5);
+        assert_eq!(strength(GALE_SPEED * 3.0), 1.0);
+    }
+}